@@ -0,0 +1,13 @@
+//! Decides whether the x86 kernels are compiled. They are on x86_64 unless the
+//! `scalar` feature asks for the portable kernels alone, which is also what
+//! every other target gets.
+
+fn main() {
+    println!("cargo::rustc-check-cfg=cfg(simd_x86)");
+    println!("cargo::rerun-if-changed=build.rs");
+
+    let x86_64 = std::env::var("CARGO_CFG_TARGET_ARCH").is_ok_and(|arch| arch == "x86_64");
+    if x86_64 && std::env::var_os("CARGO_FEATURE_SCALAR").is_none() {
+        println!("cargo::rustc-cfg=simd_x86");
+    }
+}
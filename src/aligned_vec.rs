@@ -0,0 +1,261 @@
+//! A growable vector whose buffer is aligned to a compile-time boundary.
+
+use std::fmt;
+use std::mem::MaybeUninit;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use crate::array::Array;
+use crate::lane::Lane;
+
+/// `Vec`-like container whose buffer is always aligned to `ALIGN` bytes.
+///
+/// Kernels taking `&AlignedVec<T, 16>` can use aligned loads such as
+/// `_mm_load_si128` on every 16-byte chunk without checking the pointer first.
+/// `ALIGN` must be a power of two, which is checked at compile time.
+///
+/// ```
+/// use simd::aligned_vec::AlignedVec;
+///
+/// let mut v = AlignedVec::<u8, 32>::new();
+/// v.extend(0..40);
+/// v.truncate(33);
+/// assert_eq!(v.len(), 33);
+/// assert_eq!(v.as_ptr() as usize % 32, 0);
+/// ```
+pub struct AlignedVec<T, const ALIGN: usize> {
+    buf: Array<MaybeUninit<T>>,
+    len: usize,
+}
+
+impl<T, const ALIGN: usize> AlignedVec<T, ALIGN> {
+    const VALID_ALIGN: () = assert!(ALIGN.is_power_of_two(), "ALIGN must be a power of two");
+
+    /// Creates an empty vector. Does not allocate.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty vector with room for at least `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_ALIGN;
+        Self { buf: Array::uninit(capacity, ALIGN), len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Makes room for at least `additional` more elements, at least doubling
+    /// the capacity when it has to reallocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        if required <= self.capacity() {
+            return;
+        }
+        let capacity = required.max(self.capacity() * 2).max(4);
+        let mut buf = Array::uninit(capacity, ALIGN);
+        unsafe { ptr::copy_nonoverlapping(self.buf.as_ptr(), buf.as_mut_ptr(), self.len) };
+        // The old buffer only holds `MaybeUninit`s, so dropping it frees the
+        // memory without dropping the moved elements.
+        self.buf = buf;
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+        self.buf.as_mut_slice()[self.len].write(value);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(unsafe { self.buf.as_slice()[self.len].assume_init_read() })
+    }
+
+    /// Shortens the vector to `len` elements, dropping the rest. Does nothing
+    /// if `len` is not less than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        let tail = ptr::slice_from_raw_parts_mut(unsafe { self.as_mut_ptr().add(len) }, self.len - len);
+        // Shrink first so a panicking destructor cannot lead to a double drop.
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Resizes the vector to `len` elements, filling new slots with `value`.
+    pub fn resize(&mut self, len: usize, value: T)
+    where T: Clone
+    {
+        if len <= self.len {
+            self.truncate(len);
+            return;
+        }
+        self.reserve(len - self.len);
+        while self.len < len {
+            self.push(value.clone());
+        }
+    }
+
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where T: Clone
+    {
+        self.extend(other.iter().cloned());
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.buf.as_ptr() as *const T
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr() as *mut T
+    }
+}
+
+impl<T: Lane, const ALIGN: usize> AlignedVec<T, ALIGN> {
+    crate::chunks::impl_chunk_methods!();
+}
+
+impl<T, const ALIGN: usize> Drop for AlignedVec<T, ALIGN> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) };
+    }
+}
+
+impl<T, const ALIGN: usize> Default for AlignedVec<T, ALIGN> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const ALIGN: usize> Clone for AlignedVec<T, ALIGN> {
+    fn clone(&self) -> Self {
+        let mut v = Self::with_capacity(self.len);
+        v.extend_from_slice(self);
+        v
+    }
+}
+
+impl<T: fmt::Debug, const ALIGN: usize> fmt::Debug for AlignedVec<T, ALIGN> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const ALIGN: usize> AsRef<[T]> for AlignedVec<T, ALIGN> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const ALIGN: usize> Deref for AlignedVec<T, ALIGN> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const ALIGN: usize> DerefMut for AlignedVec<T, ALIGN> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T, const ALIGN: usize> Extend<T> for AlignedVec<T, ALIGN> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T, const ALIGN: usize> FromIterator<T> for AlignedVec<T, ALIGN> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_aligned_vec_growth_keeps_alignment() {
+        let mut v = AlignedVec::<u8, 64>::new();
+        assert_eq!(v.capacity(), 0);
+        for i in 0..1000 {
+            v.push(i as u8);
+            assert_eq!(v.as_ptr() as usize % 64, 0);
+        }
+        assert_eq!(v.len(), 1000);
+        assert!(v.iter().enumerate().all(|(i, &x)| x == i as u8));
+
+        v.reserve(5000);
+        assert!(v.capacity() >= 6000);
+        assert_eq!(v[999], (999 % 256) as u8);
+    }
+
+    #[test]
+    fn test_aligned_vec_truncate_resize() {
+        let mut v: AlignedVec<String, 16> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        v.resize(5, "x".to_string());
+        assert_eq!(v.as_slice(), &["a", "b", "c", "x", "x"]);
+        v.truncate(2);
+        assert_eq!(v.as_slice(), &["a", "b"]);
+        v.resize(1, String::new());
+        assert_eq!(v.pop().as_deref(), Some("a"));
+        assert_eq!(v.pop(), None);
+        v.extend_from_slice(&["d".to_string()]);
+        assert_eq!(format!("{:?}", v.clone()), "[\"d\"]");
+    }
+
+    #[test]
+    fn test_aligned_vec_drops_elements() {
+        let rc = Rc::new(());
+        let mut v = AlignedVec::<Rc<()>, 32>::new();
+        v.resize(10, rc.clone());
+        v.reserve(100);
+        assert_eq!(Rc::strong_count(&rc), 11);
+        v.truncate(4);
+        assert_eq!(Rc::strong_count(&rc), 5);
+        drop(v);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
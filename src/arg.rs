@@ -0,0 +1,415 @@
+//! Positions of the smallest and largest elements: argmin and argmax.
+//!
+//! Like [`reduce`](crate::reduce), every function takes anything that views as
+//! a slice and runs on AVX2 when available, on SSE2 otherwise, and with a
+//! sequential loop at [`Isa::Scalar`](crate::dispatch::Isa::Scalar). Ties resolve
+//! to the first occurrence, so `argmin(data)` equals
+//! `data.iter().position(|&x| x == min)`.
+//!
+//! ```
+//! use simd::arg;
+//!
+//! let data = [7u8, 3, 9, 3, 9, 1, 1];
+//! assert_eq!(arg::argmin(&data), Some(5));
+//! assert_eq!(arg::argmax(&data), Some(2));
+//! assert_eq!(arg::min_max_with_index(&data), Some(((1, 5), (9, 2))));
+//! ```
+//!
+//! The kernels keep, next to the vector of best values, a vector of the chunk
+//! number at which each lane last improved. The chunk number is stored in
+//! lanes as wide as the values, so the data is processed in blocks of at most
+//! 2^8 chunks for `u8` and 2^16 for `i16`; each block is then folded lane by
+//! lane into the running result. Lanes only move on a strictly better value,
+//! which keeps the first occurrence within a lane, and the fold breaks ties by
+//! the smaller index.
+//!
+//! Floats follow [`reduce::min`](crate::reduce::min): NaNs are skipped, and if
+//! every element is NaN the result is index 0.
+
+#[cfg(simd_x86)]
+use crate::chunks::{ChunksExact, Register};
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::reduce::{IdentityLane, ReduceLane};
+#[cfg(simd_x86)]
+use crate::xmm::Xmm;
+#[cfg(simd_x86)]
+use crate::ymm::Ymm;
+
+/// Lane types supported by the argmin/argmax kernels.
+pub trait ArgLane: ReduceLane {
+    #[doc(hidden)]
+    fn min_max_index_slice(data: &[Self]) -> Option<((Self, usize), (Self, usize))>;
+}
+
+/// Returns the index of the first smallest element, `None` if there are none.
+pub fn argmin<T: ArgLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<usize> {
+    T::min_max_index_slice(data.as_ref()).map(|((_, i), _)| i)
+}
+
+/// Returns the index of the first largest element, `None` if there are none.
+pub fn argmax<T: ArgLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<usize> {
+    T::min_max_index_slice(data.as_ref()).map(|(_, (_, i))| i)
+}
+
+/// Returns `((min, argmin), (max, argmax))` in one pass, `None` if there are
+/// no elements.
+pub fn min_max_with_index<T: ArgLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<((T, usize), (T, usize))> {
+    T::min_max_index_slice(data.as_ref())
+}
+
+macro_rules! impl_arg_lane {
+    ($($t:ident => $kernel:ident;)*) => {
+        $(
+            impl ArgLane for $t {
+                #[inline]
+                fn min_max_index_slice(data: &[Self]) -> Option<((Self, usize), (Self, usize))> {
+                    if data.is_empty() {
+                        return None;
+                    }
+                    Some(select!(
+                        Avx2 => unsafe { avx2::$kernel(data) },
+                        Sse2 => sse2::$kernel(data),
+                        _ => scalar::min_max_index(data),
+                    ))
+                }
+            }
+        )*
+    };
+}
+
+impl_arg_lane! {
+    u8 => min_max_index_u8;
+    i16 => min_max_index_i16;
+    i32 => min_max_index_i32;
+    f32 => min_max_index_f32;
+}
+
+/// Running best value and its index.
+struct Best<T> {
+    best: Option<(T, usize)>,
+    better: fn(T, T) -> bool,
+}
+
+impl<T: Lane> Best<T> {
+    fn new(better: fn(T, T) -> bool) -> Self {
+        Self { best: None, better }
+    }
+
+    #[inline]
+    fn offer(&mut self, value: T, index: usize) {
+        let take = match self.best {
+            None => true,
+            Some((v, i)) => (self.better)(value, v) || (value == v && index < i),
+        };
+        if take {
+            self.best = Some((value, index));
+        }
+    }
+
+    /// Offers every lane of a block that moved away from `identity`. Lane `l`
+    /// was last improved in chunk `base + chunks[l]`.
+    #[cfg(simd_x86)]
+    fn offer_block<I: Copy + Into<u64>>(&mut self, values: &[T], chunks: &[I], base: usize, identity: T) {
+        let lanes = values.len();
+        for (l, (&v, &c)) in values.iter().zip(chunks).enumerate() {
+            if v != identity {
+                self.offer(v, (base + c.into() as usize) * lanes + l);
+            }
+        }
+    }
+
+    /// Offers every value of `values`, the elements from index `offset` on,
+    /// that is strictly better than `identity`. NaNs are skipped.
+    fn offer_scalar(&mut self, values: &[T], offset: usize, identity: T) {
+        for (i, &x) in values.iter().enumerate() {
+            if (self.better)(x, identity) {
+                self.offer(x, offset + i);
+            }
+        }
+    }
+
+    /// Falls back to the first `identity`, or index 0, if no value beat it,
+    /// which happens when every element is the identity or NaN.
+    fn finish(self, data: &[T], identity: T) -> (T, usize) {
+        self.best.unwrap_or_else(|| {
+            let i = data.iter().position(|&x| x == identity).unwrap_or(0);
+            (data[i], i)
+        })
+    }
+}
+
+/// Processes `data` in blocks of at most `block` chunks. In a block, every
+/// lane keeps its best value and, in an index vector of `$idx` lanes, the
+/// chunk number where it was found. `lt`/`gt` return all-ones masks and
+/// `select(mask, a, b)` picks `a` where the mask is set.
+#[cfg(simd_x86)]
+macro_rules! arg_driver {
+    ($name:ident, $(#[$attr:meta])* [$($unsafety:tt)*] $reg:ident, $t:ty, $idx:ty, block: $block:expr,
+     lt: $lt:path, gt: $gt:path, select: $select:path, inc: $inc:path) => {
+        $(#[$attr])*
+        pub $($unsafety)* fn $name(data: &[$t]) -> (($t, usize), ($t, usize)) {
+            let (highest, lowest) = (<$t as IdentityLane>::HIGHEST, <$t as IdentityLane>::LOWEST);
+            let mut min = Best::new(|a: $t, b: $t| a < b);
+            let mut max = Best::new(|a: $t, b: $t| a > b);
+            let mut chunks = ChunksExact::<$t, $reg>::new(data);
+            let mut base = 0;
+            loop {
+                let mut lo = <$reg as Register>::splat(highest);
+                let mut hi = <$reg as Register>::splat(lowest);
+                let (mut lo_idx, mut hi_idx, mut cur) = ($reg::default(), $reg::default(), $reg::default());
+                let mut n = 0;
+                for v in chunks.by_ref().take($block) {
+                    let m = $lt(v, lo);
+                    lo = $select(m, v, lo);
+                    lo_idx = $select(m, cur, lo_idx);
+                    let m = $gt(v, hi);
+                    hi = $select(m, v, hi);
+                    hi_idx = $select(m, cur, hi_idx);
+                    cur = $inc(cur);
+                    n += 1;
+                }
+                if n == 0 {
+                    break;
+                }
+                min.offer_block(lo.lanes::<$t>(), lo_idx.lanes::<$idx>(), base, highest);
+                max.offer_block(hi.lanes::<$t>(), hi_idx.lanes::<$idx>(), base, lowest);
+                base += n;
+            }
+            // Like the vector lanes, the tail skips NaNs and identities.
+            let offset = data.len() - chunks.remainder().len();
+            min.offer_scalar(chunks.remainder(), offset, highest);
+            max.offer_scalar(chunks.remainder(), offset, lowest);
+            (min.finish(data, highest), max.finish(data, lowest))
+        }
+    };
+}
+
+/// Portable kernels.
+mod scalar {
+    use super::*;
+
+    pub fn min_max_index<T: IdentityLane>(data: &[T]) -> ((T, usize), (T, usize)) {
+        let (highest, lowest) = (T::HIGHEST, T::LOWEST);
+        let mut min = Best::new(|a: T, b: T| a < b);
+        let mut max = Best::new(|a: T, b: T| a > b);
+        min.offer_scalar(data, 0, highest);
+        max.offer_scalar(data, 0, lowest);
+        (min.finish(data, highest), max.finish(data, lowest))
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    macro_rules! mask {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic($vec::from(a), $vec::from(b)) }.into()
+            }
+        };
+    }
+
+    macro_rules! inc {
+        ($name:ident, $add:ident, $one:expr) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm) -> Xmm {
+                unsafe { $add(a.into(), $one) }.into()
+            }
+        };
+    }
+
+    mask!(lt_epi16, __m128i, _mm_cmplt_epi16);
+    mask!(gt_epi16, __m128i, _mm_cmpgt_epi16);
+    mask!(lt_epi32, __m128i, _mm_cmplt_epi32);
+    mask!(gt_epi32, __m128i, _mm_cmpgt_epi32);
+    mask!(lt_ps, __m128, _mm_cmplt_ps);
+    mask!(gt_ps, __m128, _mm_cmpgt_ps);
+
+    inc!(inc_epi8, _mm_add_epi8, _mm_set1_epi8(1));
+    inc!(inc_epi16, _mm_add_epi16, _mm_set1_epi16(1));
+    inc!(inc_epi32, _mm_add_epi32, _mm_set1_epi32(1));
+
+    /// There is no unsigned byte compare: flip the sign bits and compare signed.
+    #[inline(always)]
+    pub fn gt_epu8(a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let s = _mm_set1_epi8(i8::MIN);
+            _mm_cmpgt_epi8(_mm_xor_si128(a.into(), s), _mm_xor_si128(b.into(), s)).into()
+        }
+    }
+
+    #[inline(always)]
+    pub fn lt_epu8(a: Xmm, b: Xmm) -> Xmm {
+        gt_epu8(b, a)
+    }
+
+    #[inline(always)]
+    pub fn select(mask: Xmm, a: Xmm, b: Xmm) -> Xmm {
+        let mask: __m128i = mask.into();
+        unsafe { _mm_or_si128(_mm_and_si128(mask, a.into()), _mm_andnot_si128(mask, b.into())) }.into()
+    }
+
+    arg_driver!(min_max_index_u8, [] Xmm, u8, u8, block: 1 << 8,
+        lt: lt_epu8, gt: gt_epu8, select: select, inc: inc_epi8);
+    arg_driver!(min_max_index_i16, [] Xmm, i16, u16, block: 1 << 16,
+        lt: lt_epi16, gt: gt_epi16, select: select, inc: inc_epi16);
+    arg_driver!(min_max_index_i32, [] Xmm, i32, u32, block: 1 << 32,
+        lt: lt_epi32, gt: gt_epi32, select: select, inc: inc_epi32);
+    arg_driver!(min_max_index_f32, [] Xmm, f32, u32, block: 1 << 32,
+        lt: lt_ps, gt: gt_ps, select: select, inc: inc_epi32);
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    macro_rules! mask {
+        ($name:ident, $intrinsic:ident, swap: $swap:literal) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                let (a, b) = if $swap { (b, a) } else { (a, b) };
+                $intrinsic(a.into(), b.into()).into()
+            }
+        };
+    }
+
+    macro_rules! inc {
+        ($name:ident, $add:ident, $one:expr) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm) -> Ymm {
+                $add(a.into(), $one).into()
+            }
+        };
+    }
+
+    mask!(lt_epi16, _mm256_cmpgt_epi16, swap: true);
+    mask!(gt_epi16, _mm256_cmpgt_epi16, swap: false);
+    mask!(lt_epi32, _mm256_cmpgt_epi32, swap: true);
+    mask!(gt_epi32, _mm256_cmpgt_epi32, swap: false);
+
+    inc!(inc_epi8, _mm256_add_epi8, _mm256_set1_epi8(1));
+    inc!(inc_epi16, _mm256_add_epi16, _mm256_set1_epi16(1));
+    inc!(inc_epi32, _mm256_add_epi32, _mm256_set1_epi32(1));
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn gt_epu8(a: Ymm, b: Ymm) -> Ymm {
+        let s = _mm256_set1_epi8(i8::MIN);
+        _mm256_cmpgt_epi8(_mm256_xor_si256(a.into(), s), _mm256_xor_si256(b.into(), s)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn lt_epu8(a: Ymm, b: Ymm) -> Ymm {
+        gt_epu8(b, a)
+    }
+
+    /// Ordered, non-signalling compares, so NaN lanes are never selected.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn lt_ps(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_cmp_ps::<_CMP_LT_OQ>(a.into(), b.into()).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn gt_ps(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_cmp_ps::<_CMP_GT_OQ>(a.into(), b.into()).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn select(mask: Ymm, a: Ymm, b: Ymm) -> Ymm {
+        _mm256_blendv_epi8(b.into(), a.into(), mask.into()).into()
+    }
+
+    arg_driver!(min_max_index_u8, #[target_feature(enable = "avx2")] [unsafe] Ymm, u8, u8, block: 1 << 8,
+        lt: lt_epu8, gt: gt_epu8, select: select, inc: inc_epi8);
+    arg_driver!(min_max_index_i16, #[target_feature(enable = "avx2")] [unsafe] Ymm, i16, u16, block: 1 << 16,
+        lt: lt_epi16, gt: gt_epi16, select: select, inc: inc_epi16);
+    arg_driver!(min_max_index_i32, #[target_feature(enable = "avx2")] [unsafe] Ymm, i32, u32, block: 1 << 32,
+        lt: lt_epi32, gt: gt_epi32, select: select, inc: inc_epi32);
+    arg_driver!(min_max_index_f32, #[target_feature(enable = "avx2")] [unsafe] Ymm, f32, u32, block: 1 << 32,
+        lt: lt_ps, gt: gt_ps, select: select, inc: inc_epi32);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::array::Array;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    /// Scalar reference with `Iterator::position` semantics.
+    fn reference<T: Lane>(data: &[T]) -> ((T, usize), (T, usize)) {
+        let min = data.iter().copied().fold(data[0], |a, b| if b < a { b } else { a });
+        let max = data.iter().copied().fold(data[0], |a, b| if b > a { b } else { a });
+        let (i, j) = (data.iter().position(|&x| x == min).unwrap(), data.iter().position(|&x| x == max).unwrap());
+        ((min, i), (max, j))
+    }
+
+    /// Few distinct values so ties are frequent, lengths across several
+    /// `u8` index blocks, at every supported level.
+    macro_rules! check {
+        ($t:ident, $gen:expr) => {{
+            let mut rng = rand::thread_rng();
+            for len in (1..80).chain([4095, 4096, 4097, 9000, 70_000]) {
+                let data: Vec<$t> = (0..len).map(|_| $gen(&mut rng)).collect();
+                let expected = reference(&data);
+                for isa in Isa::supported() {
+                    let got = with_isa(isa, || min_max_with_index(&data));
+                    assert_eq!(got, Some(expected), "{} at {}, len {}", stringify!($t), isa, len);
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_arg_kernels_match_scalar() {
+        check!(u8, |rng: &mut rand::rngs::ThreadRng| rng.gen_range(0..8u8) * 36);
+        check!(i16, |rng: &mut rand::rngs::ThreadRng| rng.gen_range(-4..4i16) * 8000);
+        check!(i32, |rng: &mut rand::rngs::ThreadRng| rng.gen_range(-5..5i32) << 28);
+        check!(f32, |rng: &mut rand::rngs::ThreadRng| rng.gen_range(-5..5) as f32 * 0.25);
+    }
+
+    #[test]
+    fn test_extreme_in_late_block() {
+        let mut data = vec![100u8; 20_000];
+        data[19_999] = 0;
+        data[5000] = 255;
+        data[17_000] = 255;
+        assert_eq!(argmin(&data), Some(19_999));
+        assert_eq!(argmax(&data), Some(5000));
+
+        let data = vec![u8::MAX; 33];
+        assert_eq!(min_max_with_index(&data), Some(((255, 0), (255, 0))));
+    }
+
+    #[test]
+    fn test_float_nan_and_infinity() {
+        let a = [f32::NAN, 2.0, f32::NAN, -3.0, 8.0, -3.0, f32::NAN, 1.0, 8.0];
+        let b = [f32::NAN, f32::INFINITY, f32::NAN, f32::INFINITY, 1.0, f32::NAN, f32::NAN, f32::NAN, f32::NAN];
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                assert_eq!(min_max_with_index(&a), Some(((-3.0, 3), (8.0, 4))), "{}", isa);
+                assert_eq!(min_max_with_index(&b), Some(((1.0, 4), (f32::INFINITY, 1))), "{}", isa);
+                assert_eq!(argmin(&[f32::NAN; 20]), Some(0), "{}", isa);
+            });
+        }
+
+        let array = Array::from_fn(100, 32, |i| (i as i32 - 40).abs());
+        assert_eq!(argmin(&array), Some(40));
+        assert_eq!(argmax(&array), Some(99));
+        assert_eq!(argmin(&[] as &[i16]), None);
+    }
+}
@@ -0,0 +1,386 @@
+//! Wrapping and saturating integer addition and subtraction for every lane width.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//!
+//! let a = Xmm::from_i16([10, 200, 30, -32766, 50, 60, 32000, -32000]);
+//! let b = Xmm::from_i16([100, -200, 32760, -400, 500, -600, 1200, -950]);
+//! assert_eq!(a.add_saturating::<i16>(b).lane_i16(6), i16::MAX);
+//! assert_eq!(a.add_wrapping::<i16>(b).lane_i16(6), -32336);
+//! ```
+//!
+//! SSE2 and AVX2 only have saturating instructions for 8- and 16-bit lanes.
+//! For 32- and 64-bit lanes saturation is emulated: the wrapped result is
+//! computed first, overflowed lanes are detected from the sign bits of the
+//! operands and the result, and those lanes are replaced by the bound. The
+//! portable kernels use the scalar `wrapping_*` and `saturating_*` methods.
+
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::scalar::zip;
+use crate::xmm::Xmm;
+use crate::ymm::{by_halves, Ymm};
+
+/// Integer lane types supported by the wrapping and saturating arithmetic.
+pub trait ArithLane: Lane {
+    #[doc(hidden)]
+    fn add_wrapping_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn add_saturating_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn sub_wrapping_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn sub_saturating_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn add_wrapping_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn add_saturating_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn sub_wrapping_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn sub_saturating_ymm(a: Ymm, b: Ymm) -> Ymm;
+}
+
+macro_rules! impl_arith_lane {
+    ($t:ty, $add:ident, $adds:ident, $sub:ident, $subs:ident) => {
+        impl ArithLane for $t {
+            #[inline(always)]
+            fn add_wrapping_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$add(a, b), _ => zip(a, b, <$t>::wrapping_add))
+            }
+
+            #[inline(always)]
+            fn add_saturating_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$adds(a, b), _ => zip(a, b, <$t>::saturating_add))
+            }
+
+            #[inline(always)]
+            fn sub_wrapping_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$sub(a, b), _ => zip(a, b, <$t>::wrapping_sub))
+            }
+
+            #[inline(always)]
+            fn sub_saturating_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$subs(a, b), _ => zip(a, b, <$t>::saturating_sub))
+            }
+
+            #[inline(always)]
+            fn add_wrapping_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$add(a, b) }, _ => by_halves(a, b, Self::add_wrapping_xmm))
+            }
+
+            #[inline(always)]
+            fn add_saturating_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$adds(a, b) }, _ => by_halves(a, b, Self::add_saturating_xmm))
+            }
+
+            #[inline(always)]
+            fn sub_wrapping_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$sub(a, b) }, _ => by_halves(a, b, Self::sub_wrapping_xmm))
+            }
+
+            #[inline(always)]
+            fn sub_saturating_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$subs(a, b) }, _ => by_halves(a, b, Self::sub_saturating_xmm))
+            }
+        }
+    };
+}
+
+impl_arith_lane!(i8, add_epi8, adds_epi8, sub_epi8, subs_epi8);
+impl_arith_lane!(i16, add_epi16, adds_epi16, sub_epi16, subs_epi16);
+impl_arith_lane!(i32, add_epi32, adds_epi32, sub_epi32, subs_epi32);
+impl_arith_lane!(i64, add_epi64, adds_epi64, sub_epi64, subs_epi64);
+impl_arith_lane!(u8, add_epi8, adds_epu8, sub_epi8, subs_epu8);
+impl_arith_lane!(u16, add_epi16, adds_epu16, sub_epi16, subs_epu16);
+impl_arith_lane!(u32, add_epi32, adds_epu32, sub_epi32, subs_epu32);
+impl_arith_lane!(u64, add_epi64, adds_epu64, sub_epi64, subs_epu64);
+
+macro_rules! impl_arith_methods {
+    ($reg:ident, $add_wrapping:ident, $add_saturating:ident, $sub_wrapping:ident, $sub_saturating:ident) => {
+        impl $reg {
+            /// Adds packed `T` lanes, wrapping around on overflow.
+            #[inline(always)]
+            pub fn add_wrapping<T: ArithLane>(self, rhs: $reg) -> $reg {
+                T::$add_wrapping(self, rhs)
+            }
+
+            /// Adds packed `T` lanes, clamping to `T::MIN`/`T::MAX` on overflow.
+            #[inline(always)]
+            pub fn add_saturating<T: ArithLane>(self, rhs: $reg) -> $reg {
+                T::$add_saturating(self, rhs)
+            }
+
+            /// Subtracts packed `T` lanes, wrapping around on overflow.
+            #[inline(always)]
+            pub fn sub_wrapping<T: ArithLane>(self, rhs: $reg) -> $reg {
+                T::$sub_wrapping(self, rhs)
+            }
+
+            /// Subtracts packed `T` lanes, clamping to `T::MIN`/`T::MAX` on overflow.
+            #[inline(always)]
+            pub fn sub_saturating<T: ArithLane>(self, rhs: $reg) -> $reg {
+                T::$sub_saturating(self, rhs)
+            }
+        }
+    };
+}
+
+impl_arith_methods!(Xmm, add_wrapping_xmm, add_saturating_xmm, sub_wrapping_xmm, sub_saturating_xmm);
+impl_arith_methods!(Ymm, add_wrapping_ymm, add_saturating_ymm, sub_wrapping_ymm, sub_saturating_ymm);
+
+/// Emulates saturating arithmetic for lanes without a native instruction.
+///
+/// Signed: the operation overflowed iff the result's sign differs from both
+/// operands' (add) or from `a` while `a` and `b` differ in sign (sub); the bound
+/// is `MAX` for non-negative `a` and `MIN` otherwise, i.e. `sign(a) ^ MAX`.
+/// Unsigned: the carry/borrow out of the top bit is recomputed from the
+/// operands and the wrapped result, then used to force the lane to `MAX`/`0`.
+#[cfg(simd_x86)]
+macro_rules! emulated_saturating {
+    (
+        $(#[$attr:meta])* [$($unsafety:tt)*] $reg:ident, $vec:ident,
+        $adds_i:ident, $adds_u:ident, $subs_i:ident, $subs_u:ident,
+        add: $add:ident, sub: $sub:ident, and: $and:ident, andnot: $andnot:ident, or: $or:ident, xor: $xor:ident,
+        sign_mask: $sign_mask:ident, max: $max:expr
+    ) => {
+        $(#[$attr])*
+        pub $($unsafety)* fn $adds_i(a: $reg, b: $reg) -> $reg {
+            unsafe {
+                let (a, b): ($vec, $vec) = (a.into(), b.into());
+                let sum = $add(a, b);
+                let overflow = $sign_mask($and($xor(a, sum), $xor(b, sum)));
+                let bound = $xor($sign_mask(a), $max);
+                $or($and(overflow, bound), $andnot(overflow, sum)).into()
+            }
+        }
+
+        $(#[$attr])*
+        pub $($unsafety)* fn $subs_i(a: $reg, b: $reg) -> $reg {
+            unsafe {
+                let (a, b): ($vec, $vec) = (a.into(), b.into());
+                let diff = $sub(a, b);
+                let overflow = $sign_mask($and($xor(a, b), $xor(a, diff)));
+                let bound = $xor($sign_mask(a), $max);
+                $or($and(overflow, bound), $andnot(overflow, diff)).into()
+            }
+        }
+
+        $(#[$attr])*
+        pub $($unsafety)* fn $adds_u(a: $reg, b: $reg) -> $reg {
+            unsafe {
+                let (a, b): ($vec, $vec) = (a.into(), b.into());
+                let sum = $add(a, b);
+                let carry = $sign_mask($or($and(a, b), $andnot(sum, $or(a, b))));
+                $or(sum, carry).into()
+            }
+        }
+
+        $(#[$attr])*
+        pub $($unsafety)* fn $subs_u(a: $reg, b: $reg) -> $reg {
+            unsafe {
+                let (a, b): ($vec, $vec) = (a.into(), b.into());
+                let diff = $sub(a, b);
+                let borrow = $sign_mask($or($andnot(a, b), $andnot($xor(a, b), diff)));
+                $andnot(borrow, diff).into()
+            }
+        }
+    };
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic(a.into(), b.into()) }.into()
+            }
+        };
+    }
+
+    binary!(add_epi8, _mm_add_epi8);
+    binary!(add_epi16, _mm_add_epi16);
+    binary!(add_epi32, _mm_add_epi32);
+    binary!(add_epi64, _mm_add_epi64);
+    binary!(sub_epi8, _mm_sub_epi8);
+    binary!(sub_epi16, _mm_sub_epi16);
+    binary!(sub_epi32, _mm_sub_epi32);
+    binary!(sub_epi64, _mm_sub_epi64);
+    binary!(adds_epi8, _mm_adds_epi8);
+    binary!(adds_epi16, _mm_adds_epi16);
+    binary!(adds_epu8, _mm_adds_epu8);
+    binary!(adds_epu16, _mm_adds_epu16);
+    binary!(subs_epi8, _mm_subs_epi8);
+    binary!(subs_epi16, _mm_subs_epi16);
+    binary!(subs_epu8, _mm_subs_epu8);
+    binary!(subs_epu16, _mm_subs_epu16);
+
+    #[inline(always)]
+    unsafe fn sign_mask_epi32(x: __m128i) -> __m128i {
+        _mm_srai_epi32::<31>(x)
+    }
+
+    /// There is no `_mm_srai_epi64`: negate the logically shifted sign bit.
+    #[inline(always)]
+    unsafe fn sign_mask_epi64(x: __m128i) -> __m128i {
+        _mm_sub_epi64(_mm_setzero_si128(), _mm_srli_epi64::<63>(x))
+    }
+
+    emulated_saturating!(
+        #[inline(always)] [] Xmm, __m128i,
+        adds_epi32, adds_epu32, subs_epi32, subs_epu32,
+        add: _mm_add_epi32, sub: _mm_sub_epi32, and: _mm_and_si128, andnot: _mm_andnot_si128, or: _mm_or_si128,
+        xor: _mm_xor_si128, sign_mask: sign_mask_epi32, max: _mm_set1_epi32(i32::MAX)
+    );
+
+    emulated_saturating!(
+        #[inline(always)] [] Xmm, __m128i,
+        adds_epi64, adds_epu64, subs_epi64, subs_epu64,
+        add: _mm_add_epi64, sub: _mm_sub_epi64, and: _mm_and_si128, andnot: _mm_andnot_si128, or: _mm_or_si128,
+        xor: _mm_xor_si128, sign_mask: sign_mask_epi64, max: _mm_set1_epi64x(i64::MAX)
+    );
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic(a.into(), b.into()).into()
+            }
+        };
+    }
+
+    binary!(add_epi8, _mm256_add_epi8);
+    binary!(add_epi16, _mm256_add_epi16);
+    binary!(add_epi32, _mm256_add_epi32);
+    binary!(add_epi64, _mm256_add_epi64);
+    binary!(sub_epi8, _mm256_sub_epi8);
+    binary!(sub_epi16, _mm256_sub_epi16);
+    binary!(sub_epi32, _mm256_sub_epi32);
+    binary!(sub_epi64, _mm256_sub_epi64);
+    binary!(adds_epi8, _mm256_adds_epi8);
+    binary!(adds_epi16, _mm256_adds_epi16);
+    binary!(adds_epu8, _mm256_adds_epu8);
+    binary!(adds_epu16, _mm256_adds_epu16);
+    binary!(subs_epi8, _mm256_subs_epi8);
+    binary!(subs_epi16, _mm256_subs_epi16);
+    binary!(subs_epu8, _mm256_subs_epu8);
+    binary!(subs_epu16, _mm256_subs_epu16);
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sign_mask_epi32(x: __m256i) -> __m256i {
+        _mm256_srai_epi32::<31>(x)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sign_mask_epi64(x: __m256i) -> __m256i {
+        _mm256_sub_epi64(_mm256_setzero_si256(), _mm256_srli_epi64::<63>(x))
+    }
+
+    emulated_saturating!(
+        #[inline] #[target_feature(enable = "avx2")] [unsafe] Ymm, __m256i,
+        adds_epi32, adds_epu32, subs_epi32, subs_epu32,
+        add: _mm256_add_epi32, sub: _mm256_sub_epi32, and: _mm256_and_si256, andnot: _mm256_andnot_si256,
+        or: _mm256_or_si256, xor: _mm256_xor_si256, sign_mask: sign_mask_epi32, max: _mm256_set1_epi32(i32::MAX)
+    );
+
+    emulated_saturating!(
+        #[inline] #[target_feature(enable = "avx2")] [unsafe] Ymm, __m256i,
+        adds_epi64, adds_epu64, subs_epi64, subs_epu64,
+        add: _mm256_add_epi64, sub: _mm256_sub_epi64, and: _mm256_and_si256, andnot: _mm256_andnot_si256,
+        or: _mm256_or_si256, xor: _mm256_xor_si256, sign_mask: sign_mask_epi64, max: _mm256_set1_epi64x(i64::MAX)
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::distributions::{Distribution, Standard};
+    use rand::Rng;
+
+    /// Random lanes biased towards the extremes, where saturation happens.
+    fn operand<T: Lane + Copy>(rng: &mut impl Rng, min: T, max: T) -> T
+    where
+        Standard: Distribution<T>,
+    {
+        match rng.gen_range(0..8) {
+            0 => min,
+            1 => max,
+            _ => rng.gen(),
+        }
+    }
+
+    macro_rules! check_against_scalar {
+        ($reg:ident, $t:ty) => {{
+            let mut rng = rand::thread_rng();
+            for _ in 0..256 {
+                let a = $reg::from_fn::<$t>(|_| operand(&mut rng, <$t>::MIN, <$t>::MAX));
+                let b = $reg::from_fn::<$t>(|_| operand(&mut rng, <$t>::MIN, <$t>::MAX));
+                let (x, y) = (a.lanes::<$t>(), b.lanes::<$t>());
+                let expect = |f: fn($t, $t) -> $t| $reg::from_fn::<$t>(|i| f(x[i], y[i]));
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        let t = stringify!($t);
+                        assert_eq!(a.add_wrapping::<$t>(b), expect(<$t>::wrapping_add), "{} add_wrapping at {}", t, isa);
+                        assert_eq!(a.add_saturating::<$t>(b), expect(<$t>::saturating_add), "{} add_saturating at {}", t, isa);
+                        assert_eq!(a.sub_wrapping::<$t>(b), expect(<$t>::wrapping_sub), "{} sub_wrapping at {}", t, isa);
+                        assert_eq!(a.sub_saturating::<$t>(b), expect(<$t>::saturating_sub), "{} sub_saturating at {}", t, isa);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xmm_arith_matches_scalar() {
+        check_against_scalar!(Xmm, i8);
+        check_against_scalar!(Xmm, i16);
+        check_against_scalar!(Xmm, i32);
+        check_against_scalar!(Xmm, i64);
+        check_against_scalar!(Xmm, u8);
+        check_against_scalar!(Xmm, u16);
+        check_against_scalar!(Xmm, u32);
+        check_against_scalar!(Xmm, u64);
+    }
+
+    #[test]
+    fn test_ymm_arith_matches_scalar() {
+        check_against_scalar!(Ymm, i8);
+        check_against_scalar!(Ymm, i16);
+        check_against_scalar!(Ymm, i32);
+        check_against_scalar!(Ymm, i64);
+        check_against_scalar!(Ymm, u8);
+        check_against_scalar!(Ymm, u16);
+        check_against_scalar!(Ymm, u32);
+        check_against_scalar!(Ymm, u64);
+    }
+
+    #[test]
+    fn test_saturating_bounds() {
+        let a = Xmm::from_i32([i32::MAX, i32::MIN, -1, 5]);
+        let b = Xmm::from_i32([1, -1, i32::MIN, -7]);
+        assert_eq!(a.add_saturating::<i32>(b).as_i32(), &[i32::MAX, i32::MIN, i32::MIN, -2]);
+        assert_eq!(a.sub_saturating::<i32>(b).as_i32(), &[i32::MAX - 1, i32::MIN + 1, i32::MAX, 12]);
+
+        let c = Xmm::from_u64([u64::MAX - 1, 3]);
+        let d = Xmm::from_u64([2, 4]);
+        assert_eq!(c.add_saturating::<u64>(d).as_u64(), &[u64::MAX, 7]);
+        assert_eq!(c.sub_saturating::<u64>(d).as_u64(), &[u64::MAX - 3, 0]);
+    }
+}
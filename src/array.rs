@@ -1,9 +1,15 @@
-use std::mem::needs_drop;
-use std::ptr::{NonNull};
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ptr::{self, NonNull};
 use rand::distributions::Distribution;
 use std::alloc;
 
-/// Allocates an array of type T with a given length and alignment.
+use crate::lane::Lane;
+
+/// Heap-allocated array of type T with a given length and alignment.
+///
+/// All elements are initialised for the whole lifetime of the array. To fill
+/// an array element by element, start from [`Array::uninit`] and finish with
+/// [`Array::assume_init`].
 pub struct Array<T> {
     data: NonNull<T>,
     len: usize,
@@ -11,16 +17,63 @@ pub struct Array<T> {
     _marker: std::marker::PhantomData<[T]>,
 }
 
-unsafe impl<T> Send for Array<T> {}
-unsafe impl<T> Sync for Array<T> {}
+unsafe impl<T: Send> Send for Array<T> {}
+unsafe impl<T: Sync> Sync for Array<T> {}
 
 impl<T> Array<T> {
-    pub fn new(len: usize, align: usize) -> Self {
-        let layout = alloc::Layout::from_size_align(std::mem::size_of::<T>() * len, align).unwrap();
-        let data = unsafe { alloc::alloc(layout) };
+    /// Computes the layout of `len` elements aligned to at least `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two or the size overflows `isize`.
+    fn layout(len: usize, align: usize) -> alloc::Layout {
+        alloc::Layout::array::<T>(len)
+            .and_then(|layout| layout.align_to(align))
+            .expect("invalid array size or alignment")
+    }
 
-        Self {
-            data: NonNull::new(data as *mut T).unwrap(),
+    /// Allocates memory for `layout`. Zero-sized layouts are not allocated and
+    /// get a dangling pointer aligned to `layout.align()` instead.
+    fn allocate(layout: alloc::Layout, zeroed: bool) -> NonNull<T> {
+        if layout.size() == 0 {
+            return NonNull::new(ptr::without_provenance_mut(layout.align())).unwrap();
+        }
+        let data = unsafe {
+            if zeroed {
+                alloc::alloc_zeroed(layout)
+            } else {
+                alloc::alloc(layout)
+            }
+        };
+        match NonNull::new(data as *mut T) {
+            Some(data) => data,
+            None => alloc::handle_alloc_error(layout),
+        }
+    }
+
+    /// Allocates an array whose elements are all `value`.
+    pub fn from_elem(len: usize, align: usize, value: T) -> Self
+    where T: Clone
+    {
+        Self::from_fn(len, align, |_| value.clone())
+    }
+
+    /// Allocates an array whose element `i` is `f(i)`.
+    ///
+    /// If `f` panics, the elements created so far are leaked.
+    pub fn from_fn(len: usize, align: usize, mut f: impl FnMut(usize) -> T) -> Self {
+        let mut array = Self::uninit(len, align);
+        for (i, elem) in array.as_mut_slice().iter_mut().enumerate() {
+            elem.write(f(i));
+        }
+        unsafe { array.assume_init() }
+    }
+
+    /// Allocates an array of uninitialised elements.
+    pub fn uninit(len: usize, align: usize) -> Array<MaybeUninit<T>> {
+        let layout = Self::layout(len, align);
+        Array {
+            data: Array::<T>::allocate(layout, false).cast(),
             len,
             layout,
             _marker: std::marker::PhantomData,
@@ -28,7 +81,7 @@ impl<T> Array<T> {
     }
 
     pub fn is_aligned(&self, align: usize) -> bool {
-        self.data.as_ptr() as usize % align == 0
+        (self.data.as_ptr() as usize).is_multiple_of(align)
     }
 
     pub fn len(&self) -> usize {
@@ -42,9 +95,7 @@ impl<T> Array<T> {
     pub fn fill(&mut self, value: T)
     where T: Clone
     {
-        for i in 0..self.len {
-            unsafe { std::ptr::write(self.data.as_ptr().add(i), value.clone()) };
-        }
+        self.as_mut_slice().fill(value);
     }
 
     pub fn randomise(&mut self, min: T, max: T, exclude_zero: bool)
@@ -52,14 +103,14 @@ impl<T> Array<T> {
     {
         let mut rng = rand::thread_rng();
         let uniform = rand::distributions::Uniform::new(min, max);
-        for i in 0..self.len {
+        for elem in self.as_mut_slice() {
             let mut value = uniform.sample(&mut rng);
             if exclude_zero {
                 while value == T::default() {
                     value = uniform.sample(&mut rng);
                 }
             }
-            unsafe { std::ptr::write(self.data.as_ptr().add(i), value) };
+            *elem = value;
         }
     }
 
@@ -80,50 +131,122 @@ impl<T> Array<T> {
     }
 }
 
+impl<T: Lane> Array<T> {
+    /// Allocates an array with all bits cleared. Restricted to [`Lane`] types,
+    /// for which the all-zero bit pattern is a valid value.
+    pub fn zeroed(len: usize, align: usize) -> Self {
+        let layout = Self::layout(len, align);
+        Self {
+            data: Self::allocate(layout, true),
+            len,
+            layout,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    crate::chunks::impl_chunk_methods!();
+}
+
+impl<T> Array<MaybeUninit<T>> {
+    /// Converts to `Array<T>` without touching the elements.
+    ///
+    /// # Safety
+    ///
+    /// Every element must have been initialised.
+    pub unsafe fn assume_init(self) -> Array<T> {
+        let this = ManuallyDrop::new(self);
+        Array {
+            data: this.data.cast(),
+            len: this.len,
+            layout: this.layout,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> AsRef<[T]> for Array<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
 impl<T> Drop for Array<T> {
     fn drop(&mut self) {
-        if needs_drop::<T>() {
-            for i in 0..self.len {
-                unsafe { std::ptr::drop_in_place(self.data.as_ptr().add(i)) };
+        unsafe {
+            ptr::drop_in_place(self.as_mut_slice());
+            if self.layout.size() != 0 {
+                alloc::dealloc(self.data.as_ptr() as *mut u8, self.layout);
             }
         }
-        unsafe { alloc::dealloc(self.data.as_ptr() as *mut u8, self.layout) };
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::rc::Rc;
 
     #[test]
     fn test_array_copyable_element_dropping() {
-        let mut array = Array::<u8>::new(16, 16);
-        array.fill(0);
+        let mut array = Array::<u8>::zeroed(16, 16);
         assert_eq!(array.as_slice(), &[0; 16]);
 
         array.randomise(0, 255, false);
-        let slice = array.as_slice();
+        assert!(array.as_slice().iter().all(|&val| val < 255));
     }
 
     #[test]
     fn test_array_cloneable_element_dropping() {
-        let mut array = Array::<String>::new(16, 16);
-        array.fill("a".to_string());
+        let mut array = Array::from_elem(16, 16, "a".to_string());
+        array.fill("b".to_string());
 
         let slice = array.as_slice();
         for val in slice {
-            assert_eq!(val, "a");
+            assert_eq!(val, "b");
         }
     }
 
     #[test]
     fn test_array_referenced_element_dropping() {
-        let mut array = Array::<String>::new(16, 16);
-        array.fill("a".to_string());
+        let rc = Rc::new(0);
+        let array = Array::from_elem(16, 64, rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 17);
+        assert!(array.is_aligned(64));
 
-        let slice = array.as_slice();
-        for val in slice {
-            assert_eq!(val, "a");
+        drop(array);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_array_from_fn_and_uninit() {
+        let array = Array::from_fn(5, 32, |i| i * i);
+        assert_eq!(array.as_slice(), &[0, 1, 4, 9, 16]);
+
+        let mut array = Array::<String>::uninit(3, 16);
+        for (i, val) in array.as_mut_slice().iter_mut().enumerate() {
+            val.write(i.to_string());
         }
+        let array = unsafe { array.assume_init() };
+        assert_eq!(array.as_slice(), &["0", "1", "2"]);
+    }
+
+    #[test]
+    fn test_array_zero_length() {
+        let array = Array::<u64>::zeroed(0, 32);
+        assert!(array.is_empty());
+        assert!(array.is_aligned(32));
+        assert_eq!(array.as_slice(), &[] as &[u64]);
+
+        let array = Array::from_elem(0, 16, "a".to_string());
+        assert!(array.as_slice().is_empty());
+
+        let array = Array::from_elem(4, 16, ());
+        assert_eq!(array.len(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_array_invalid_alignment() {
+        Array::<u8>::zeroed(16, 3);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,870 @@
+//! Sequential micro-benchmarks of kernel variants.
+//!
+//! A [`Bench`] times its variants one after the other on the calling thread,
+//! so they neither share caches nor compete for turbo frequency. Each variant
+//! is warmed up first, then timed over a number of samples, each running the
+//! closure often enough to dwarf the timer resolution. Results go through
+//! [`black_box`] so the optimiser cannot drop the work; inputs captured by
+//! the closure should go through it too.
+//!
+//! ```no_run
+//! use simd::bench::{black_box, Bench};
+//! use simd::reduce;
+//!
+//! let data = vec![1u8; 1 << 20];
+//! let mut bench = Bench::new("u8 sum").bytes(data.len());
+//! bench.run("scalar", || black_box(&data).iter().map(|&x| x as u64).sum::<u64>());
+//! bench.run("simd", || reduce::sum(black_box(&data)));
+//! bench.print();
+//! ```
+//!
+//! The first variant is the baseline speed-ups are reported against.
+//!
+//! Results can also be saved as JSON or CSV [`Record`]s, one per variant, and
+//! [compared](compare) with a saved baseline to catch regressions. Benchmark
+//! binaries get both through [`Options::from_args`] and [`report`]:
+//!
+//! ```sh
+//! simd bench --format json --output base.json
+//! simd bench --baseline base.json --threshold 5
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::dispatch::{level, Isa};
+use crate::lane::Lane;
+
+pub use std::hint::black_box;
+
+/// Summary of the per-iteration times of one variant, in nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub median: f64,
+    pub min: f64,
+    pub mean: f64,
+    /// Population standard deviation.
+    pub stddev: f64,
+}
+
+impl Stats {
+    /// Summarises `samples`. Panics if there are none.
+    pub fn from_samples(samples: &[f64]) -> Stats {
+        assert!(!samples.is_empty(), "no samples");
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        let median = if n % 2 == 1 { sorted[n / 2] } else { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let variance = sorted.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n as f64;
+        Stats { median, min: sorted[0], mean, stddev: variance.sqrt() }
+    }
+}
+
+/// Timing of one variant.
+#[derive(Clone, Debug)]
+pub struct Measurement {
+    pub name: String,
+    /// Iterations timed together in every sample.
+    pub iters: u64,
+    pub stats: Stats,
+    /// Bytes processed by one iteration, if known.
+    pub bytes: Option<usize>,
+    /// Dispatch level the variant ran at.
+    pub isa: Isa,
+}
+
+impl Measurement {
+    /// Returns the bytes processed per second at the median time.
+    pub fn throughput(&self) -> Option<f64> {
+        self.bytes.map(|bytes| bytes as f64 * 1e9 / self.stats.median)
+    }
+}
+
+/// A group of variants of the same computation, timed one after the other.
+#[derive(Clone, Debug)]
+pub struct Bench {
+    name: String,
+    bytes: Option<usize>,
+    elements: Option<(&'static str, usize)>,
+    warm_up: Duration,
+    sample_time: Duration,
+    samples: usize,
+    results: Vec<Measurement>,
+}
+
+impl Bench {
+    /// Creates a group with 200 ms of warm-up and 30 samples of about 10 ms
+    /// per variant.
+    pub fn new(name: impl Into<String>) -> Self {
+        Bench {
+            name: name.into(),
+            bytes: None,
+            elements: None,
+            warm_up: Duration::from_millis(200),
+            sample_time: Duration::from_millis(10),
+            samples: 30,
+            results: Vec::new(),
+        }
+    }
+
+    /// Sets the bytes one iteration processes, to report throughput.
+    pub fn bytes(mut self, bytes: usize) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    /// Sets the number and type of the elements one iteration processes, which
+    /// also sets the bytes.
+    pub fn elements<T: Lane>(mut self, n: usize) -> Self {
+        self.elements = Some((T::NAME, n));
+        self.bytes(n * std::mem::size_of::<T>())
+    }
+
+    /// Sets how long each variant runs before it is timed.
+    pub fn warm_up(mut self, warm_up: Duration) -> Self {
+        self.warm_up = warm_up;
+        self
+    }
+
+    /// Sets the target duration of one sample.
+    pub fn sample_time(mut self, sample_time: Duration) -> Self {
+        self.sample_time = sample_time;
+        self
+    }
+
+    /// Sets the number of samples per variant. Panics if `samples` is zero.
+    pub fn samples(mut self, samples: usize) -> Self {
+        assert!(samples > 0, "at least one sample is needed");
+        self.samples = samples;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Warms up and times the variant `name`, and records its measurement.
+    pub fn run<R>(&mut self, name: impl Into<String>, mut f: impl FnMut() -> R) -> &Measurement {
+        // The warm-up runs at least once and estimates the time of one
+        // iteration, which sizes the samples.
+        let start = Instant::now();
+        let mut warm_iters = 0u64;
+        loop {
+            black_box(f());
+            warm_iters += 1;
+            if start.elapsed() >= self.warm_up {
+                break;
+            }
+        }
+        let per_iter = start.elapsed().as_nanos() as f64 / warm_iters as f64;
+        let iters = (self.sample_time.as_nanos() as f64 / per_iter.max(1.0)) as u64;
+        let iters = iters.max(1);
+
+        let samples: Vec<f64> = (0..self.samples)
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..iters {
+                    black_box(f());
+                }
+                start.elapsed().as_nanos() as f64 / iters as f64
+            })
+            .collect();
+
+        self.results.push(Measurement {
+            name: name.into(),
+            iters,
+            stats: Stats::from_samples(&samples),
+            bytes: self.bytes,
+            isa: level(),
+        });
+        self.results.last().unwrap()
+    }
+
+    /// Returns the measurements in the order the variants ran.
+    pub fn results(&self) -> &[Measurement] {
+        &self.results
+    }
+
+    /// Returns how many times faster `m` is than the first variant, by median.
+    pub fn speedup(&self, m: &Measurement) -> f64 {
+        self.results.first().map_or(1.0, |base| base.stats.median / m.stats.median)
+    }
+
+    /// Prints the results as a table on stdout.
+    pub fn print(&self) {
+        print!("{}", self);
+    }
+
+    /// Returns one record per variant.
+    pub fn records(&self) -> Vec<Record> {
+        self.results
+            .iter()
+            .map(|m| Record {
+                kernel: self.name.clone(),
+                variant: m.name.clone(),
+                isa: m.isa,
+                ty: self.elements.map(|(ty, _)| ty.to_string()),
+                n: self.elements.map(|(_, n)| n),
+                ns_per_iter: m.stats.median,
+                min_ns: m.stats.min,
+                stddev_ns: m.stats.stddev,
+                bytes_per_sec: m.throughput(),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Bench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bytes {
+            Some(bytes) => writeln!(f, "{} ({} bytes per iteration)", self.name, bytes)?,
+            None => writeln!(f, "{}", self.name)?,
+        }
+        let w = self.results.iter().map(|m| m.name.len()).max().unwrap_or(0).max("variant".len());
+        writeln!(
+            f,
+            "  {:<w$}  {:>11}  {:>11}  {:>11}  {:>12}  {:>8}",
+            "variant", "median", "min", "stddev", "throughput", "speed-up"
+        )?;
+        for m in &self.results {
+            let throughput = m.throughput().map_or_else(|| "-".to_string(), fmt_throughput);
+            writeln!(
+                f,
+                "  {:<w$}  {:>11}  {:>11}  {:>11}  {:>12}  {:>7.2}x",
+                m.name,
+                fmt_time(m.stats.median),
+                fmt_time(m.stats.min),
+                fmt_time(m.stats.stddev),
+                throughput,
+                self.speedup(m)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats nanoseconds with the largest unit that keeps the value above one.
+pub fn fmt_time(ns: f64) -> String {
+    match ns {
+        ns if ns < 1e3 => format!("{:.2} ns", ns),
+        ns if ns < 1e6 => format!("{:.2} µs", ns / 1e3),
+        ns if ns < 1e9 => format!("{:.2} ms", ns / 1e6),
+        ns => format!("{:.2} s", ns / 1e9),
+    }
+}
+
+/// Formats bytes per second in decimal units.
+pub fn fmt_throughput(bytes_per_sec: f64) -> String {
+    match bytes_per_sec {
+        b if b < 1e3 => format!("{:.2} B/s", b),
+        b if b < 1e6 => format!("{:.2} KB/s", b / 1e3),
+        b if b < 1e9 => format!("{:.2} MB/s", b / 1e6),
+        b => format!("{:.2} GB/s", b / 1e9),
+    }
+}
+
+/// One saved measurement. Records are matched with a baseline by kernel,
+/// variant, ISA, element type and element count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub kernel: String,
+    pub variant: String,
+    pub isa: Isa,
+    /// Element type, if the bench set [`Bench::elements`].
+    pub ty: Option<String>,
+    /// Element count, if the bench set [`Bench::elements`].
+    pub n: Option<usize>,
+    /// Median time of one iteration.
+    pub ns_per_iter: f64,
+    pub min_ns: f64,
+    pub stddev_ns: f64,
+    pub bytes_per_sec: Option<f64>,
+}
+
+impl Record {
+    fn same_case(&self, other: &Record) -> bool {
+        self.kernel == other.kernel
+            && self.variant == other.variant
+            && self.isa == other.isa
+            && self.ty == other.ty
+            && self.n == other.n
+    }
+}
+
+/// Output format of [`report`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The tables printed by [`Bench::print`].
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format `{}`, expected text, json or csv", s)),
+        }
+    }
+}
+
+const CSV_HEADER: &str = "kernel,variant,isa,type,n,ns_per_iter,min_ns,stddev_ns,bytes_per_sec";
+
+/// Writes `records` as a JSON array of objects with the fields of [`Record`],
+/// where `ty` is named `type`.
+pub fn write_json(w: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    writeln!(w, "[")?;
+    for (i, r) in records.iter().enumerate() {
+        let ty = r.ty.as_deref().map_or_else(|| "null".to_string(), json_string);
+        let n = r.n.map_or_else(|| "null".to_string(), |n| n.to_string());
+        writeln!(
+            w,
+            "  {{\"kernel\": {}, \"variant\": {}, \"isa\": \"{}\", \"type\": {}, \"n\": {}, \"ns_per_iter\": {}, \
+             \"min_ns\": {}, \"stddev_ns\": {}, \"bytes_per_sec\": {}}}{}",
+            json_string(&r.kernel),
+            json_string(&r.variant),
+            r.isa,
+            ty,
+            n,
+            json_number(r.ns_per_iter),
+            json_number(r.min_ns),
+            json_number(r.stddev_ns),
+            r.bytes_per_sec.map_or_else(|| "null".to_string(), json_number),
+            if i + 1 < records.len() { "," } else { "" }
+        )?;
+    }
+    writeln!(w, "]")
+}
+
+/// Writes `records` as CSV with a header line.
+pub fn write_csv(w: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    writeln!(w, "{}", CSV_HEADER)?;
+    for r in records {
+        writeln!(
+            w,
+            "{},{},{},{},{},{},{},{},{}",
+            csv_field(&r.kernel),
+            csv_field(&r.variant),
+            r.isa,
+            csv_field(r.ty.as_deref().unwrap_or("")),
+            r.n.map_or_else(String::new, |n| n.to_string()),
+            r.ns_per_iter,
+            r.min_ns,
+            r.stddev_ns,
+            r.bytes_per_sec.map_or_else(String::new, |b| b.to_string())
+        )?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON has no infinities or NaN, which a variant too fast to time produces.
+/// They are written as `null` and read back as NaN.
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Error returned when a baseline file cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRecordsError(String);
+
+impl fmt::Display for ParseRecordsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseRecordsError {}
+
+/// Parses records written by [`write_json`] or [`write_csv`]. Text starting
+/// with `[` is read as JSON, anything else as CSV.
+pub fn parse_records(text: &str) -> Result<Vec<Record>, ParseRecordsError> {
+    if text.trim_start().starts_with('[') {
+        json::parse(text)?.into_iter().map(|fields| record_from(&fields)).collect()
+    } else {
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let header = match lines.next() {
+            Some((_, line)) => csv_split(line)?,
+            None => return Ok(Vec::new()),
+        };
+        lines
+            .map(|(i, line)| {
+                let values = csv_split(line)?;
+                if values.len() != header.len() {
+                    return Err(ParseRecordsError(format!("line {}: expected {} fields", i + 1, header.len())));
+                }
+                let fields: Vec<_> =
+                    header.iter().cloned().zip(values.into_iter().map(|v| (!v.is_empty()).then_some(v))).collect();
+                record_from(&fields).map_err(|e| ParseRecordsError(format!("line {}: {}", i + 1, e)))
+            })
+            .collect()
+    }
+}
+
+/// Splits one CSV line, unquoting `"…"` fields.
+fn csv_split(line: &str) -> Result<Vec<String>, ParseRecordsError> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(ParseRecordsError(format!("unterminated quote in `{}`", line)));
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Builds a record from field names and values, `None` standing for null.
+/// A null time is read as NaN, but the field itself must be present.
+fn record_from(fields: &[(String, Option<String>)]) -> Result<Record, ParseRecordsError> {
+    let field = |name: &str| fields.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+    let get = |name: &str| field(name).flatten();
+    let missing = |name: &str| ParseRecordsError(format!("missing `{}`", name));
+    let required = |name: &str| get(name).ok_or_else(|| missing(name));
+    fn number<T: FromStr>(name: &str, v: String) -> Result<T, ParseRecordsError> {
+        v.parse().map_err(|_| ParseRecordsError(format!("invalid `{}`: `{}`", name, v)))
+    }
+    Ok(Record {
+        kernel: required("kernel")?,
+        variant: required("variant")?,
+        isa: required("isa")?.parse().map_err(|e: crate::dispatch::ParseIsaError| ParseRecordsError(e.to_string()))?,
+        ty: get("type"),
+        n: get("n").map(|v| number("n", v)).transpose()?,
+        ns_per_iter: match field("ns_per_iter").ok_or_else(|| missing("ns_per_iter"))? {
+            Some(v) => number("ns_per_iter", v)?,
+            None => f64::NAN,
+        },
+        min_ns: get("min_ns").map(|v| number("min_ns", v)).transpose()?.unwrap_or(f64::NAN),
+        stddev_ns: get("stddev_ns").map(|v| number("stddev_ns", v)).transpose()?.unwrap_or(f64::NAN),
+        bytes_per_sec: get("bytes_per_sec").map(|v| number("bytes_per_sec", v)).transpose()?,
+    })
+}
+
+/// Just enough JSON to read back an array of flat objects. Values are kept
+/// as text, strings unescaped, and `null` as `None`.
+mod json {
+    use super::ParseRecordsError;
+
+    type Object = Vec<(String, Option<String>)>;
+
+    struct Parser<'a> {
+        text: &'a str,
+        pos: usize,
+    }
+
+    pub fn parse(text: &str) -> Result<Vec<Object>, ParseRecordsError> {
+        let mut p = Parser { text, pos: 0 };
+        let objects = p.list('[', ']', Parser::object)?;
+        p.skip_ws();
+        if p.pos < text.len() {
+            return Err(p.error("trailing characters"));
+        }
+        Ok(objects)
+    }
+
+    impl Parser<'_> {
+        fn error(&self, what: &str) -> ParseRecordsError {
+            let line = self.text[..self.pos].lines().count().max(1);
+            ParseRecordsError(format!("invalid JSON at line {}: {}", line, what))
+        }
+
+        fn skip_ws(&mut self) {
+            let rest = &self.text[self.pos..];
+            self.pos += rest.len() - rest.trim_start().len();
+        }
+
+        fn peek(&mut self) -> Option<char> {
+            self.skip_ws();
+            self.text[self.pos..].chars().next()
+        }
+
+        fn expect(&mut self, c: char) -> Result<(), ParseRecordsError> {
+            if self.peek() != Some(c) {
+                return Err(self.error(&format!("expected `{}`", c)));
+            }
+            self.pos += 1;
+            Ok(())
+        }
+
+        /// Parses `open item, item, … close`.
+        fn list<T>(
+            &mut self,
+            open: char,
+            close: char,
+            mut item: impl FnMut(&mut Self) -> Result<T, ParseRecordsError>,
+        ) -> Result<Vec<T>, ParseRecordsError> {
+            self.expect(open)?;
+            let mut items = Vec::new();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(items);
+            }
+            loop {
+                items.push(item(self)?);
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    Some(c) if c == close => {
+                        self.pos += 1;
+                        return Ok(items);
+                    }
+                    _ => return Err(self.error(&format!("expected `,` or `{}`", close))),
+                }
+            }
+        }
+
+        fn object(&mut self) -> Result<Object, ParseRecordsError> {
+            self.list('{', '}', |p| {
+                let key = p.string()?;
+                p.expect(':')?;
+                Ok((key, p.value()?))
+            })
+        }
+
+        fn value(&mut self) -> Result<Option<String>, ParseRecordsError> {
+            match self.peek() {
+                Some('"') => self.string().map(Some),
+                Some('n') if self.text[self.pos..].starts_with("null") => {
+                    self.pos += 4;
+                    Ok(None)
+                }
+                Some(c) if c == '-' || c.is_ascii_digit() => {
+                    let rest = &self.text[self.pos..];
+                    let len = rest.find(|c: char| !matches!(c, '0'..='9' | '-' | '+' | '.' | 'e' | 'E')).unwrap_or(rest.len());
+                    self.pos += len;
+                    Ok(Some(rest[..len].to_string()))
+                }
+                _ => Err(self.error("expected a string, number or null")),
+            }
+        }
+
+        fn string(&mut self) -> Result<String, ParseRecordsError> {
+            self.expect('"')?;
+            let mut out = String::new();
+            let mut chars = self.text[self.pos..].char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.pos += i + 1;
+                        return Ok(out);
+                    }
+                    '\\' => match chars.next().map(|(_, c)| c) {
+                        Some('n') => out.push('\n'),
+                        Some('t') => out.push('\t'),
+                        Some('r') => out.push('\r'),
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                            out.push(c.ok_or_else(|| self.error("invalid \\u escape"))?);
+                        }
+                        Some(c @ ('"' | '\\' | '/')) => out.push(c),
+                        _ => return Err(self.error("invalid escape")),
+                    },
+                    c => out.push(c),
+                }
+            }
+            Err(self.error("unterminated string"))
+        }
+    }
+}
+
+/// A record of the current run next to its baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub current: Record,
+    /// Median time in the baseline, `None` if the baseline lacks the case or
+    /// its time is zero or not finite, as nothing can be compared with it.
+    pub baseline_ns: Option<f64>,
+    /// Whether the slowdown exceeds the threshold.
+    pub regressed: bool,
+}
+
+impl Comparison {
+    /// Returns the relative change of the median time: 0.1 is 10% slower.
+    pub fn change(&self) -> Option<f64> {
+        self.baseline_ns.map(|base| self.current.ns_per_iter / base - 1.0)
+    }
+}
+
+/// Matches every current record with the baseline. A case regresses when its
+/// median time grew by more than `threshold`, as a fraction.
+pub fn compare(current: &[Record], baseline: &[Record], threshold: f64) -> Vec<Comparison> {
+    current
+        .iter()
+        .map(|r| {
+            let baseline_ns = baseline
+                .iter()
+                .find(|b| b.same_case(r))
+                .map(|b| b.ns_per_iter)
+                .filter(|&ns| ns.is_finite() && ns > 0.0);
+            let mut c = Comparison { current: r.clone(), baseline_ns, regressed: false };
+            c.regressed = c.change().is_some_and(|change| change > threshold);
+            c
+        })
+        .collect()
+}
+
+/// Settings of a benchmark binary, parsed from its command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub format: Format,
+    /// File to write the results to instead of stdout.
+    pub output: Option<PathBuf>,
+    /// Saved results to compare with.
+    pub baseline: Option<PathBuf>,
+    /// Slowdown that counts as a regression, as a fraction.
+    pub threshold: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { format: Format::Text, output: None, baseline: None, threshold: 0.05 }
+    }
+}
+
+impl Options {
+    pub const USAGE: &'static str = "\
+options:
+  --format <text|json|csv>  output format (default: text)
+  --output <path>           write the results to a file instead of stdout
+  --baseline <path>         compare with results saved as JSON or CSV
+  --threshold <percent>     slowdown that counts as a regression (default: 5)";
+
+    /// Parses the arguments after the program name.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+        let mut opts = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+            match arg.as_str() {
+                "--format" => opts.format = value()?.parse()?,
+                "--output" => opts.output = Some(value()?.into()),
+                "--baseline" => opts.baseline = Some(value()?.into()),
+                "--threshold" => {
+                    let v = value()?;
+                    let percent = v.trim_end_matches('%').parse::<f64>().ok().filter(|p| p.is_finite() && *p >= 0.0);
+                    let percent = percent.ok_or_else(|| format!("invalid threshold `{}`", v))?;
+                    opts.threshold = percent / 100.0;
+                }
+                _ => return Err(format!("unknown option `{}`\n{}", arg, Options::USAGE)),
+            }
+        }
+        Ok(opts)
+    }
+}
+
+/// Writes the results of `benches` in the format of `opts`, then compares
+/// them with the baseline, if any, on stderr. Fails if writing or reading a
+/// file fails or a case regressed.
+pub fn report(benches: &[Bench], opts: &Options) -> ExitCode {
+    match try_report(benches, opts) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn try_report(benches: &[Bench], opts: &Options) -> Result<bool, Box<dyn std::error::Error>> {
+    let records: Vec<Record> = benches.iter().flat_map(Bench::records).collect();
+    let mut out: Box<dyn Write> = match &opts.output {
+        Some(path) => Box::new(io::BufWriter::new(fs::File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    match opts.format {
+        Format::Text => benches.iter().try_for_each(|b| write!(out, "{}", b))?,
+        Format::Json => write_json(&mut out, &records)?,
+        Format::Csv => write_csv(&mut out, &records)?,
+    }
+    out.flush()?;
+
+    let Some(path) = &opts.baseline else {
+        return Ok(true);
+    };
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let baseline = parse_records(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let comparisons = compare(&records, &baseline, opts.threshold);
+    for c in &comparisons {
+        let r = &c.current;
+        let change = c.change().map_or_else(|| "no baseline".to_string(), |x| format!("{:+.1}%", x * 100.0));
+        let flag = if c.regressed { "  REGRESSION" } else { "" };
+        eprintln!("{} / {} [{}]: {} ({}){}", r.kernel, r.variant, r.isa, fmt_time(r.ns_per_iter), change, flag);
+    }
+    let regressions = comparisons.iter().filter(|c| c.regressed).count();
+    if regressions > 0 {
+        eprintln!("{} of {} cases regressed by more than {}%", regressions, comparisons.len(), opts.threshold * 100.0);
+    }
+    Ok(regressions == 0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let s = Stats::from_samples(&[4.0, 1.0, 3.0]);
+        assert_eq!((s.median, s.min), (3.0, 1.0));
+        let s = Stats::from_samples(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!((s.median, s.mean, s.stddev), (4.5, 5.0, 2.0));
+    }
+
+    #[test]
+    fn test_run_and_report() {
+        let data = vec![3u8; 4096];
+        let mut bench = Bench::new("sum").elements::<u8>(data.len()).warm_up(Duration::ZERO).sample_time(Duration::ZERO).samples(3);
+        let m = bench.run("scalar", || black_box(&data).iter().map(|&x| x as u64).sum::<u64>());
+        assert_eq!(m.iters, 1);
+        bench.run("simd", || crate::reduce::sum(black_box(&data)));
+
+        assert_eq!(bench.results().len(), 2);
+        assert_eq!(bench.speedup(&bench.results()[0]), 1.0);
+        assert!(bench.results()[1].throughput().unwrap() > 0.0);
+        let table = bench.to_string();
+        assert!(table.starts_with("sum (4096 bytes per iteration)\n  variant"));
+        assert_eq!(table.lines().count(), 4);
+
+        let records = bench.records();
+        assert_eq!((records[1].variant.as_str(), records[1].ty.as_deref(), records[1].n), ("simd", Some("u8"), Some(4096)));
+        assert_eq!(records[1].isa, level());
+    }
+
+    fn record(variant: &str, ns: f64) -> Record {
+        Record {
+            kernel: "mean, \"u8\"".to_string(),
+            variant: variant.to_string(),
+            isa: Isa::Sse2,
+            ty: Some("u8".to_string()),
+            n: Some(1000),
+            ns_per_iter: ns,
+            min_ns: ns - 0.5,
+            stddev_ns: 1.25,
+            bytes_per_sec: Some(1000.0 * 1e9 / ns),
+        }
+    }
+
+    #[test]
+    fn test_records_round_trip() {
+        let mut records = vec![record("scalar", 300.0), record("simd", 75.5)];
+        records[1].ty = None;
+        records[1].n = None;
+        records[1].bytes_per_sec = None;
+
+        let mut json = Vec::new();
+        write_json(&mut json, &records).unwrap();
+        assert_eq!(parse_records(std::str::from_utf8(&json).unwrap()), Ok(records.clone()));
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &records).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("kernel,variant,isa,type,n,ns_per_iter"));
+        assert_eq!(parse_records(&csv), Ok(records));
+
+        let mut untimed = record("simd", f64::INFINITY);
+        untimed.min_ns = f64::NAN;
+        for write in [write_json, write_csv] {
+            let mut out = Vec::new();
+            write(&mut out, std::slice::from_ref(&untimed)).unwrap();
+            let read = parse_records(std::str::from_utf8(&out).unwrap()).unwrap();
+            assert!(!read[0].ns_per_iter.is_finite() && read[0].min_ns.is_nan());
+        }
+
+        assert_eq!(parse_records("[]"), Ok(vec![]));
+        let err = parse_records("[{\"kernel\": \"k\", \"variant\": \"v\"}]").unwrap_err();
+        assert_eq!(err.to_string(), "missing `isa`");
+        let err = parse_records("kernel,variant,isa,ns_per_iter\nk,v,sse2,fast\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: invalid `ns_per_iter`: `fast`");
+        assert!(parse_records("[{\"kernel\": }]").unwrap_err().to_string().starts_with("invalid JSON at line 1"));
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = [record("scalar", 100.0), record("simd", 100.0)];
+        let mut other_isa = record("simd", 100.0);
+        other_isa.isa = Isa::Avx2;
+        let current = [record("scalar", 104.0), record("simd", 110.0), other_isa];
+
+        let cmp = compare(&current, &baseline, 0.05);
+        assert_eq!(cmp.iter().map(|c| c.regressed).collect::<Vec<_>>(), [false, true, false]);
+        assert!((cmp[1].change().unwrap() - 0.1).abs() < 1e-12);
+        assert_eq!(cmp[2].baseline_ns, None);
+
+        let baseline = [record("scalar", 0.0), record("simd", f64::NAN)];
+        let current = [record("scalar", 10.0), record("simd", 10.0)];
+        for c in compare(&current, &baseline, 0.05) {
+            assert_eq!((c.baseline_ns, c.change(), c.regressed), (None, None, false));
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+        assert_eq!(Options::from_args(args("")), Ok(Options::default()));
+        let opts = Options::from_args(args("--format CSV --baseline base.json --threshold 2.5%")).unwrap();
+        assert_eq!(opts.format, Format::Csv);
+        assert_eq!(opts.baseline, Some(PathBuf::from("base.json")));
+        assert_eq!(opts.threshold, 0.025);
+        assert_eq!(Options::from_args(args("--output")), Err("--output needs a value".to_string()));
+        for bad in ["nan", "inf", "-1", "-0.5%"] {
+            let err = Options::from_args(args(&format!("--threshold {}", bad)));
+            assert_eq!(err, Err(format!("invalid threshold `{}`", bad)));
+        }
+        assert!(Options::from_args(args("--format xml")).unwrap_err().contains("unknown format `xml`"));
+    }
+
+    #[test]
+    fn test_units() {
+        assert_eq!(fmt_time(512.0), "512.00 ns");
+        assert_eq!(fmt_time(1_500_000.0), "1.50 ms");
+        assert_eq!(fmt_throughput(2.5e9), "2.50 GB/s");
+        assert_eq!(fmt_throughput(999.0), "999.00 B/s");
+    }
+}
@@ -0,0 +1,95 @@
+//! Just enough command line parsing for the subcommands: `--name value` or
+//! `--name=value` options, `--flag` switches and positional operands, in any
+//! order. Operands may start with `-`, as in `-5,3,0,1`.
+
+use std::str::FromStr;
+
+#[derive(Debug, Default)]
+pub struct Args {
+    options: Vec<(String, String)>,
+    switches: Vec<String>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    /// `options` take a value and `switches` do not. Any other argument
+    /// starting with `--` is an error.
+    pub fn parse(args: impl IntoIterator<Item = String>, options: &[&str], switches: &[&str]) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with("--") {
+                parsed.positional.push(arg);
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            if options.contains(&name.as_str()) {
+                let value = inline.or_else(|| args.next()).ok_or_else(|| format!("{} needs a value", name))?;
+                parsed.options.push((name, value));
+            } else if switches.contains(&name.as_str()) && inline.is_none() {
+                parsed.switches.push(name);
+            } else {
+                return Err(format!("unknown option `{}`", arg));
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the last value given for `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    /// Parses the value of `name`, or returns `default` if it is missing.
+    pub fn get_or<T: FromStr>(&self, name: &str, default: T) -> Result<T, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        match self.get(name) {
+            Some(v) => v.parse().map_err(|e| format!("{}: {}", name, e)),
+            None => Ok(default),
+        }
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|s| s == name)
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    /// Returns exactly `N` positional operands.
+    pub fn operands<const N: usize>(&self) -> Result<[&str; N], String> {
+        let found = self.positional.len();
+        let operands: Vec<&str> = self.positional.iter().map(String::as_str).collect();
+        operands.try_into().map_err(|_| format!("expected {} operand{}, got {}", N, if N == 1 { "" } else { "s" }, found))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Args, String> {
+        Args::parse(s.split_whitespace().map(String::from), &["--type", "--width"], &["--sat"])
+    }
+
+    #[test]
+    fn test_parse() {
+        let args = parse("--type i16 1,2 --sat -3,4 --width=256 --type u8").unwrap();
+        assert_eq!(args.get("--type"), Some("u8"));
+        assert_eq!(args.get_or("--width", 128u32), Ok(256));
+        assert_eq!(args.get_or("--missing", 7), Ok(7));
+        assert!(args.switch("--sat"));
+        assert_eq!(args.operands(), Ok(["1,2", "-3,4"]));
+        assert_eq!(args.operands::<1>(), Err("expected 1 operand, got 2".to_string()));
+
+        assert_eq!(parse("--type").unwrap_err(), "--type needs a value");
+        assert_eq!(parse("--sat=1").unwrap_err(), "unknown option `--sat=1`");
+        assert_eq!(parse("--wat").unwrap_err(), "unknown option `--wat`");
+    }
+}
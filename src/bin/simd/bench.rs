@@ -0,0 +1,65 @@
+//! The `bench` subcommand: the reduction kernels against a scalar loop, at
+//! every supported dispatch level.
+
+use std::process::ExitCode;
+
+use rand::Rng;
+use simd::aligned_vec::AlignedVec;
+use simd::bench::{self, black_box, Bench, Options};
+use simd::dispatch::{with_isa, Isa};
+use simd::{arg, reduce};
+
+/// Runs `kernel` once per supported level, as the variant `name/<level>`.
+fn run_levels<R>(bench: &mut Bench, name: &str, kernel: impl Fn() -> R) {
+    for isa in Isa::supported() {
+        with_isa(isa, || bench.run(format!("{}/{}", name, isa), &kernel));
+    }
+}
+
+pub fn bench(args: Vec<String>) -> Result<ExitCode, String> {
+    // `--len` is ours, the rest are the harness options.
+    let mut len = 1_000_000;
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--len" {
+            let v = args.next().ok_or("--len needs a value")?;
+            len = v.parse().map_err(|_| format!("invalid length `{}`", v))?;
+        } else {
+            rest.push(arg);
+        }
+    }
+    let opts = Options::from_args(rest)?;
+
+    let mut rng = rand::thread_rng();
+    let bytes: AlignedVec<u8, 32> = (0..len).map(|_| rng.gen()).collect();
+    let floats: AlignedVec<f32, 32> = (0..len).map(|_| rng.gen_range(-1e3..1e3)).collect();
+
+    let mut mean = Bench::new("mean u8").elements::<u8>(len);
+    mean.run("scalar", || {
+        let data = black_box(&bytes);
+        data.iter().map(|&x| x as u64).sum::<u64>() as f64 / data.len() as f64
+    });
+    run_levels(&mut mean, "reduce::mean", || reduce::mean(black_box(&bytes)));
+
+    let mut min_max = Bench::new("min_max u8").elements::<u8>(len);
+    min_max.run("scalar", || {
+        black_box(&bytes).iter().fold((u8::MAX, u8::MIN), |(lo, hi), &x| (lo.min(x), hi.max(x)))
+    });
+    run_levels(&mut min_max, "reduce::min_max", || reduce::min_max(black_box(&bytes)));
+
+    let mut float_min_max = Bench::new("min_max f32").elements::<f32>(len);
+    float_min_max.run("scalar", || {
+        black_box(&floats).iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &x| (lo.min(x), hi.max(x)))
+    });
+    run_levels(&mut float_min_max, "reduce::min_max", || reduce::min_max(black_box(&floats)));
+
+    let mut argmax = Bench::new("argmax f32").elements::<f32>(len);
+    argmax.run("scalar", || {
+        let data = black_box(&floats);
+        (0..data.len()).reduce(|best, i| if data[i] > data[best] { i } else { best })
+    });
+    run_levels(&mut argmax, "arg::argmax", || arg::argmax(black_box(&floats)));
+
+    Ok(bench::report(&[mean, min_max, float_min_max, argmax], &opts))
+}
@@ -0,0 +1,134 @@
+//! Command line front end to the library: applies packed operations to
+//! registers given as lane lists, reduces arrays, benchmarks the kernels and
+//! reports what the CPU supports.
+//!
+//! ```sh
+//! simd add --type i16 --sat 10,200,30,-32766,50,60,32000,-32000 100,-200,32760,-400,500,-600,1200,-950
+//! simd --isa sse2 bitwise --shift 4 0x1234,0xFFB0,0x00CC,0x8080,0x00FF,0xAAAA,0x0F0F,0x0101 0xFF00
+//! ```
+
+mod args;
+mod bench;
+mod ops;
+mod reduce;
+mod repl;
+mod table;
+mod values;
+
+use std::process::ExitCode;
+
+use simd::dispatch::{self, level, with_isa, Isa};
+
+const USAGE: &str = "\
+usage: simd [--isa <level>] <command> [options] [operands]
+
+commands:
+  add       add packed integers, wraparound or saturated with --sat
+  sub       subtract packed integers, wraparound or saturated with --sat
+  mul       multiply packed integers: low and high halves, full products
+  bitwise   and, or, xor and not, and shifts with --shift <n>
+  float     add, subtract, multiply, divide and negate packed floats
+  minmax    minimum and maximum of a list, or of --len <n> random values
+  mean      sum and mean of a list, or of --len <n> random values
+  repl      define registers and apply operations interactively
+  bench     time the kernels against scalar loops at every supported level
+  cpuinfo   show the detected and selected instruction sets
+  help      show this message
+
+register options:
+  --type <t>      lane type: i8, i16, i32, i64, u8, u16, u32, u64, f32, f64
+                  (default: i16, u16 for bitwise, f32 for float, u8 for lists)
+  --width <bits>  register width, 128 or 256 (default: 128)
+
+Operands are comma-separated lanes, such as 10,200,-30,4; a single value fills
+every lane. Integers can also be written in hex, such as 0xFF00, which may set
+the sign bit. A register can also be given with its own lane type, such as
+u16x:[0x1234,...] or f32:[1.5,...], or as 0x and 32 or 64 hex digits, most
+significant first. --isa lowers the dispatch level like SIMD_FORCE_ISA.
+
+repl options:
+  --history <file>  history file (default: $SIMD_HISTORY or .simd_history)
+  --no-history      keep no history file
+
+bench options:
+  --len <n>       elements per array (default: 1000000)
+";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut isa = None;
+    if args.first().map(String::as_str) == Some("--isa") {
+        match args.get(1).map(|s| s.parse::<Isa>()) {
+            Some(Ok(level)) => isa = Some(level),
+            Some(Err(e)) => return usage_error(&e.to_string()),
+            None => return usage_error("--isa needs a value"),
+        }
+        args.drain(..2);
+    }
+    if args.is_empty() {
+        return usage_error("no command given");
+    }
+    let command = args.remove(0);
+
+    let result = match isa {
+        Some(isa) => {
+            if !isa.is_supported() {
+                eprintln!("--isa {}: not supported by this CPU or above {}, using {}", isa, dispatch::FORCE_ISA_VAR, level());
+            }
+            with_isa(isa, || run(&command, args))
+        }
+        None => run(&command, args),
+    };
+    result.unwrap_or_else(|e| usage_error(&e))
+}
+
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("error: {}", message);
+    eprintln!("run `simd help` for usage");
+    ExitCode::from(2)
+}
+
+fn run(command: &str, args: Vec<String>) -> Result<ExitCode, String> {
+    match command {
+        "add" => ops::add_sub(args, false)?,
+        "sub" => ops::add_sub(args, true)?,
+        "mul" => ops::mul(args)?,
+        "bitwise" => ops::bitwise(args)?,
+        "float" => ops::float(args)?,
+        "minmax" => reduce::min_max(args)?,
+        "mean" => reduce::mean(args)?,
+        "repl" => repl::repl(args)?,
+        "bench" => return bench::bench(args),
+        "cpuinfo" => cpuinfo(),
+        "help" | "--help" | "-h" => print!("{}", USAGE),
+        _ => return Err(format!("unknown command `{}`", command)),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn cpuinfo() {
+    let supported: Vec<&str> = Isa::supported().map(Isa::name).collect();
+    println!("detected:  {}", Isa::detect());
+    match std::env::var(dispatch::FORCE_ISA_VAR) {
+        Ok(forced) => println!("in use:    {} ({}={})", level(), dispatch::FORCE_ISA_VAR, forced),
+        Err(_) => println!("in use:    {}", level()),
+    }
+    println!("supported: {}", supported.join(", "));
+    println!("kernels:   {}", if cfg!(simd_x86) { "x86 and portable" } else { "portable only" });
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        macro_rules! features {
+            ($($name:tt),*) => {
+                [$(($name, is_x86_feature_detected!($name))),*]
+            };
+        }
+        let features = features!(
+            "sse2", "sse3", "ssse3", "sse4.1", "sse4.2", "popcnt", "avx", "avx2", "fma", "bmi1", "bmi2", "avx512f",
+            "avx512bw", "avx512dq", "avx512vl"
+        );
+        let flags: Vec<String> =
+            features.iter().map(|(name, on)| format!("{}{}", if *on { "+" } else { "-" }, name)).collect();
+        println!("features:  {}", flags.join(" "));
+    }
+}
@@ -0,0 +1,124 @@
+//! Subcommands applying one packed operation to two registers given on the
+//! command line.
+
+use simd::dispatch::level;
+
+use crate::args::Args;
+use crate::values::{parse_reg, with_float, with_int, LaneType, Show, Width};
+
+fn register_args(args: Vec<String>, options: &[&str], switches: &[&str], default: LaneType) -> Result<(Args, LaneType, Width), String> {
+    let args = Args::parse(args, options, switches)?;
+    let ty = args.get_or("--type", default)?;
+    let width = args.get_or("--width", Width::W128)?;
+    Ok((args, ty, width))
+}
+
+/// `add` and `sub`: wraparound, or saturated with `--sat`.
+pub fn add_sub(args: Vec<String>, sub: bool) -> Result<(), String> {
+    let (args, ty, width) = register_args(args, &["--type", "--width"], &["--sat"], LaneType::I16)?;
+    let [a, b] = args.operands()?;
+    let sat = args.switch("--sat");
+    with_int!(ty, width, |T, R, V| {
+        let (a, b) = (parse_reg::<T, R>(a)?, parse_reg::<T, R>(b)?);
+        let c = match (sub, sat) {
+            (false, false) => a.add_wrapping::<T>(b),
+            (false, true) => a.add_saturating::<T>(b),
+            (true, false) => a.sub_wrapping::<T>(b),
+            (true, true) => a.sub_saturating::<T>(b),
+        };
+        let (name, op) = if sub { ("Subtraction", "-") } else { ("Addition", "+") };
+        println!("{} {}[{},{}] {}:", name, ty, T::MIN, T::MAX, level());
+        print!("a:                  {}", a.show(ty));
+        print!("b:                  {}", b.show(ty));
+        if sat {
+            print!("a {} b (saturated):  {}", op, c.show(ty));
+        } else {
+            print!("a {} b (wraparound): {}", op, c.show(ty));
+        }
+        Ok(())
+    })
+}
+
+/// `mul`: low and high halves, and the full products.
+pub fn mul(args: Vec<String>) -> Result<(), String> {
+    let (args, ty, width) = register_args(args, &["--type", "--width"], &[], LaneType::I16)?;
+    let [a, b] = args.operands()?;
+    with_int!(ty, width, |T, R, V| {
+        let (a, b) = (parse_reg::<T, R>(a)?, parse_reg::<T, R>(b)?);
+        let (lo, hi) = a.widening_mul::<T>(b);
+        println!("Multiplication {}[{},{}] {}:", ty, T::MIN, T::MAX, level());
+        print!("a:                  {}", a.show(ty));
+        print!("b:                  {}", b.show(ty));
+        print!("a * b (low half):   {}", a.mul_lo::<T>(b).show(ty));
+        print!("a * b (high half):  {}", a.mul_hi::<T>(b).show(ty));
+        let (lo, hi) = match ty.widened() {
+            Some(wide) => (lo.show(wide), hi.show(wide)),
+            None => (show_products(lo.lanes::<u64>(), ty), show_products(hi.lanes::<u64>(), ty)),
+        };
+        print!("a * b (lo lanes):   {}", lo);
+        print!("a * b (hi lanes):   {}", hi);
+        Ok(())
+    })
+}
+
+/// Formats 128-bit products, stored as low and high halves in consecutive
+/// `u64` lanes, as one `i128` for `i64` lanes and one `u128` otherwise.
+fn show_products(halves: &[u64], ty: LaneType) -> String {
+    let mut s = String::new();
+    for (i, pair) in halves.chunks_exact(2).enumerate() {
+        let bits = (pair[1] as u128) << 64 | pair[0] as u128;
+        if ty == LaneType::I64 {
+            s += &format!("{:41}", bits as i128);
+        } else {
+            s += &format!("{:41}", bits);
+        }
+        if i + 1 == halves.len() / 4 {
+            s += "    |";
+        }
+    }
+    s + "\n"
+}
+
+/// `bitwise`: and, or, xor, not, and shifts by `--shift`.
+pub fn bitwise(args: Vec<String>) -> Result<(), String> {
+    let (args, ty, width) = register_args(args, &["--type", "--width", "--shift"], &[], LaneType::U16)?;
+    let [a, b] = args.operands()?;
+    let shift = args.get("--shift").map(|s| s.parse::<u32>().map_err(|e| format!("--shift: {}", e))).transpose()?;
+    with_int!(ty, width, |T, R, V| {
+        let (a, b) = (parse_reg::<T, R>(a)?, parse_reg::<T, R>(b)?);
+        let (va, vb) = (V::from(a), V::from(b));
+        println!("Bitwise {} {}:", ty, level());
+        print!("a:      {}", a.show_hex(ty));
+        print!("b:      {}", b.show_hex(ty));
+        print!("a & b:  {}", R::from(va & vb).show_hex(ty));
+        print!("a | b:  {}", R::from(va | vb).show_hex(ty));
+        print!("a ^ b:  {}", R::from(va ^ vb).show_hex(ty));
+        print!("!a:     {}", R::from(!va).show_hex(ty));
+        if let Some(n) = shift {
+            let kind = if <T as simd::lane::Lane>::SIGNED { "arithmetic" } else { "logical" };
+            println!("Shifts by {} ({} right shift):", n, kind);
+            print!("a << {}: {}", n, R::from(va << n).show_hex(ty));
+            print!("a >> {}: {}", n, R::from(va >> n).show_hex(ty));
+        }
+        Ok(())
+    })
+}
+
+/// `float`: the four basic operations and negation.
+pub fn float(args: Vec<String>) -> Result<(), String> {
+    let (args, ty, width) = register_args(args, &["--type", "--width"], &[], LaneType::F32)?;
+    let [a, b] = args.operands()?;
+    with_float!(ty, width, |T, R, V| {
+        let (a, b) = (parse_reg::<T, R>(a)?, parse_reg::<T, R>(b)?);
+        let (va, vb) = (V::from(a), V::from(b));
+        println!("Packed {} {}:", ty, level());
+        print!("a:      {}", a.show(ty));
+        print!("b:      {}", b.show(ty));
+        print!("a + b:  {}", R::from(va + vb).show(ty));
+        print!("a - b:  {}", R::from(va - vb).show(ty));
+        print!("a * b:  {}", R::from(va * vb).show(ty));
+        print!("a / b:  {}", R::from(va / vb).show(ty));
+        print!("-a:     {}", R::from(-va).show(ty));
+        Ok(())
+    })
+}
@@ -0,0 +1,63 @@
+//! Subcommands reducing a list of values given on the command line, or a
+//! random array of `--len` elements.
+
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+use simd::dispatch::level;
+use simd::parse::ParseLane;
+use simd::reduce;
+
+use crate::args::Args;
+use crate::values::{parse_list, with_lane, LaneType};
+
+/// Values from the operands, which may be split by commas or spaces, or
+/// `len` random ones if there are none.
+fn values<T: ParseLane>(args: &Args, len: usize) -> Result<Vec<T>, String>
+where
+    Standard: Distribution<T>,
+{
+    if args.positional().is_empty() {
+        let mut rng = rand::thread_rng();
+        return Ok((0..len).map(|_| rng.gen()).collect());
+    }
+    let mut data = Vec::new();
+    for operand in args.positional() {
+        data.extend(parse_list::<T>(operand)?);
+    }
+    Ok(data)
+}
+
+pub fn min_max(args: Vec<String>) -> Result<(), String> {
+    let args = Args::parse(args, &["--type", "--len"], &[])?;
+    let ty = args.get_or("--type", LaneType::U8)?;
+    let len = args.get_or("--len", 1_000_000usize)?;
+    with_lane!(ty, |T| {
+        let data = values::<T>(&args, len)?;
+        println!("Min & max of {} {} {}:", data.len(), ty, level());
+        match reduce::min_max(&data) {
+            Some((min, max)) => {
+                let at = |x: T| data.iter().position(|&y| y == x).map_or_else(|| "-".to_string(), |i| i.to_string());
+                println!("min: {} (first at {})", min, at(min));
+                println!("max: {} (first at {})", max, at(max));
+            }
+            None => println!("no values"),
+        }
+        Ok(())
+    })
+}
+
+pub fn mean(args: Vec<String>) -> Result<(), String> {
+    let args = Args::parse(args, &["--type", "--len"], &[])?;
+    let ty = args.get_or("--type", LaneType::U8)?;
+    let len = args.get_or("--len", 1_000_000usize)?;
+    with_lane!(ty, |T| {
+        let data = values::<T>(&args, len)?;
+        println!("Mean of {} {} {}:", data.len(), ty, level());
+        println!("sum:  {}", reduce::sum(&data));
+        match reduce::mean(&data) {
+            Some(mean) => println!("mean: {:.6}", mean),
+            None => println!("mean: -"),
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,471 @@
+//! The `repl` subcommand: an interactive shell over the operation table.
+//!
+//! ```text
+//! > a = i16:[10, 200, 30, -32766, 50, 60, 32000, -32000]
+//! > b = i16:[100, -200, 32760, -400, 500, -600, 1200, -950]
+//! > c = adds_epi16 a b
+//! > c as u16x
+//! ```
+//!
+//! Lines are appended to a history file, `.simd_history` in the current
+//! directory unless `--history` or `SIMD_HISTORY` names another; `history`
+//! lists it and `!n` or `!!` runs an entry again.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use simd::dispatch::{level, with_isa, Isa};
+use simd::parse::ParseRegisterError;
+use simd::xmm::Xmm;
+use simd::ymm::Ymm;
+
+use crate::args::Args;
+use crate::table::{self, Kernel, View, OPS};
+use crate::values::{LaneType, Show};
+
+const HELP: &str = "\
+  <name> = <expr>        define a register, showing it
+  <expr> [as <view>]     show a register, in another lane view with `as`
+  ops [filter]           list the operations whose name contains filter
+  vars                   list the registers
+  isa [level]            show or set the dispatch level
+  history                list the history; !n and !! run an entry again
+  help                   show this message
+  quit                   leave, as does end of input
+
+An expression is a register name, a literal or an operation and its operands,
+such as `adds_epi16 a b` or `srai_epi16 a 3`. Literals are a typed list such
+as i16:[1,2,...] or u16x:[0x1234,...], or 0x and 32 or 64 hex digits; a list
+of 16 i16 lanes makes a 256-bit register. Operation names may keep their
+_mm_ or _mm256_ prefix. Views are lane types, such as i16 or f32, or u16x for
+u16 lanes in hex.
+";
+
+/// Environment variable naming the history file.
+const HISTORY_VAR: &str = "SIMD_HISTORY";
+const HISTORY_FILE: &str = ".simd_history";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Reg {
+    Xmm(Xmm),
+    Ymm(Ymm),
+}
+
+/// A register and the view it is shown in by default.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Value {
+    reg: Reg,
+    view: View,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match (self.reg, self.view) {
+            (Reg::Xmm(x), View::Lanes(ty)) => x.show(ty),
+            (Reg::Xmm(x), View::U16Hex) => x.show_hex(LaneType::U16),
+            (Reg::Ymm(y), View::Lanes(ty)) => y.show(ty),
+            (Reg::Ymm(y), View::U16Hex) => y.show_hex(LaneType::U16),
+        };
+        f.write_str(s.trim_end())
+    }
+}
+
+impl View {
+    fn name(self) -> &'static str {
+        match self {
+            View::Lanes(ty) => ty.name(),
+            View::U16Hex => "u16x",
+        }
+    }
+}
+
+/// Parses a register literal, 128-bit if the lanes fit and 256-bit if there
+/// are twice as many. It is shown in the view its type prefix names.
+fn parse_literal(s: &str) -> Result<Value, String> {
+    let view = match s.split_once(':') {
+        Some((ty, _)) if ty.trim() == "u16x" => View::U16Hex,
+        Some((ty, _)) => {
+            let ty = ty.trim();
+            View::parse(ty.strip_suffix('x').unwrap_or(ty))?
+        }
+        None => View::U16Hex,
+    };
+    let reg = match s.parse::<Xmm>() {
+        Ok(x) => Reg::Xmm(x),
+        Err(xmm_err) => match s.parse::<Ymm>() {
+            Ok(y) => Reg::Ymm(y),
+            Err(ymm_err) => {
+                // A size error only means the literal is not for an `Xmm`.
+                let size = |e: &ParseRegisterError| {
+                    matches!(e, ParseRegisterError::LaneCount { .. } | ParseRegisterError::HexDigits { .. })
+                };
+                return Err(match (size(&xmm_err), size(&ymm_err)) {
+                    (true, true) => format!("{} (or twice as many for a 256-bit register)", xmm_err),
+                    (true, false) => ymm_err.to_string(),
+                    (false, _) => xmm_err.to_string(),
+                });
+            }
+        },
+    };
+    Ok(Value { reg, view })
+}
+
+/// Splits a line at whitespace outside brackets, so literals may contain
+/// spaces.
+fn tokens(line: &str) -> Result<Vec<&str>, String> {
+    let mut tokens = Vec::new();
+    let (mut depth, mut start) = (0usize, None);
+    for (i, c) in line.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.checked_sub(1).ok_or("unbalanced `]`")?,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    tokens.push(&line[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if depth != 0 {
+        return Err("unbalanced `[`".to_string());
+    }
+    if let Some(s) = start {
+        tokens.push(&line[s..]);
+    }
+    Ok(tokens)
+}
+
+fn is_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Command lines, in memory and appended to a file.
+struct History {
+    lines: Vec<String>,
+    file: Option<File>,
+}
+
+impl History {
+    fn new() -> History {
+        History { lines: Vec::new(), file: None }
+    }
+
+    /// Loads the history in `path` and appends new lines to it.
+    fn open(path: PathBuf) -> io::Result<History> {
+        let lines = match File::open(&path) {
+            Ok(file) => BufReader::new(file).lines().collect::<io::Result<_>>()?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(History { lines, file: Some(file) })
+    }
+
+    fn push(&mut self, line: &str) {
+        if self.lines.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        self.lines.push(line.to_string());
+        if let Some(file) = &mut self.file {
+            if let Err(e) = writeln!(file, "{}", line) {
+                eprintln!("warning: history not saved: {}", e);
+                self.file = None;
+            }
+        }
+    }
+
+    /// Replaces a `!!` or `!n` line with the entry it names, counting from 1.
+    fn expand(&self, line: &str) -> Result<String, String> {
+        let Some(entry) = line.strip_prefix('!') else {
+            return Ok(line.to_string());
+        };
+        let found = match entry {
+            "!" => self.lines.last(),
+            n => {
+                let n: usize = n.parse().map_err(|_| format!("invalid history entry `{}`", line))?;
+                n.checked_sub(1).and_then(|i| self.lines.get(i))
+            }
+        };
+        found.cloned().ok_or_else(|| format!("no history entry `{}`", line))
+    }
+}
+
+struct Session {
+    /// Registers in the order they were first defined.
+    vars: Vec<(String, Value)>,
+    isa: Isa,
+    history: History,
+}
+
+impl Session {
+    fn new(history: History) -> Session {
+        Session { vars: Vec::new(), isa: level(), history }
+    }
+
+    fn var(&self, name: &str) -> Option<&Value> {
+        self.vars.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    fn set(&mut self, name: &str, value: Value) {
+        match self.vars.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.vars.push((name.to_string(), value)),
+        }
+    }
+
+    fn operand(&self, s: &str) -> Result<Value, String> {
+        match self.var(s) {
+            Some(&value) => Ok(value),
+            None if is_name(s) => Err(format!("unknown register or operation `{}`", s)),
+            None => parse_literal(s),
+        }
+    }
+
+    fn apply(&self, name: &str, args: &[&str]) -> Result<Value, String> {
+        let op = table::find(name).ok_or_else(|| format!("unknown register or operation `{}`", name))?;
+        let arity = match op.kernel {
+            Kernel::Unary(..) => 1,
+            Kernel::Binary(..) | Kernel::Shift(..) => 2,
+        };
+        if args.len() != arity {
+            return Err(format!("usage: {}", op.usage()));
+        }
+        let a = self.operand(args[0])?.reg;
+        let reg = with_isa(self.isa, || match op.kernel {
+            Kernel::Unary(x, y) => Ok(match a {
+                Reg::Xmm(a) => Reg::Xmm(x(a)),
+                Reg::Ymm(a) => Reg::Ymm(y(a)),
+            }),
+            Kernel::Binary(x, y) => match (a, self.operand(args[1])?.reg) {
+                (Reg::Xmm(a), Reg::Xmm(b)) => Ok(Reg::Xmm(x(a, b))),
+                (Reg::Ymm(a), Reg::Ymm(b)) => Ok(Reg::Ymm(y(a, b))),
+                _ => Err(format!("operands of `{}` differ in width", op.name)),
+            },
+            Kernel::Shift(x, y) => {
+                let n: u32 = args[1].parse().map_err(|_| format!("invalid shift count `{}`", args[1]))?;
+                Ok(match a {
+                    Reg::Xmm(a) => Reg::Xmm(x(a, n)),
+                    Reg::Ymm(a) => Reg::Ymm(y(a, n)),
+                })
+            }
+        })?;
+        Ok(Value { reg, view: op.view })
+    }
+
+    /// Evaluates an expression with an optional `as <view>` suffix.
+    fn expr(&self, tokens: &[&str]) -> Result<Value, String> {
+        let (tokens, view) = match tokens {
+            [rest @ .., "as", view] => (rest, Some(View::parse(view)?)),
+            _ => (tokens, None),
+        };
+        let mut value = match tokens {
+            [] => return Err("missing expression".to_string()),
+            [s] => self.operand(s)?,
+            [name, args @ ..] => self.apply(name, args)?,
+        };
+        if let Some(view) = view {
+            value.view = view;
+        }
+        Ok(value)
+    }
+
+    /// Runs one line and returns what it prints.
+    fn eval(&mut self, line: &str) -> Result<String, String> {
+        let tokens = tokens(line)?;
+        match tokens[..] {
+            [] => Ok(String::new()),
+            ["help"] => Ok(HELP.to_string()),
+            ["ops"] | ["ops", _] => {
+                let filter = tokens.get(1).copied().unwrap_or("");
+                let width = OPS.iter().map(|op| op.usage().len()).max().unwrap_or(0);
+                Ok(OPS
+                    .iter()
+                    .filter(|op| op.name.contains(filter))
+                    .map(|op| format!("  {:w$}  {}\n", op.usage(), op.doc, w = width))
+                    .collect())
+            }
+            ["vars"] => Ok(self
+                .vars
+                .iter()
+                .map(|(name, value)| self.show(name, value))
+                .collect()),
+            ["isa"] => Ok(format!("{}\n", self.isa)),
+            ["isa", isa] => {
+                let isa: Isa = isa.parse().map_err(|e: simd::dispatch::ParseIsaError| e.to_string())?;
+                // `with_isa` clamps to the detected level.
+                self.isa = with_isa(isa, level);
+                match self.isa == isa {
+                    true => Ok(format!("{}\n", isa)),
+                    false => Ok(format!("{} is not supported by this CPU, using {}\n", isa, self.isa)),
+                }
+            }
+            ["history"] => Ok(self
+                .history
+                .lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| format!("{:5}  {}\n", i + 1, line))
+                .collect()),
+            [name, "=", ref rest @ ..] => {
+                if !is_name(name) || table::find(name).is_some() {
+                    return Err(format!("`{}` cannot name a register", name));
+                }
+                let value = self.expr(rest)?;
+                self.set(name, value);
+                Ok(self.show(name, &value))
+            }
+            _ => Ok(self.show("", &self.expr(&tokens)?)),
+        }
+    }
+
+    fn show(&self, name: &str, value: &Value) -> String {
+        let width = self.vars.iter().map(|(n, _)| n.len()).max().unwrap_or(0).max(name.len());
+        format!("{:w$} {:5} {}\n", name, value.view.name(), value, w = width)
+    }
+}
+
+pub fn repl(args: Vec<String>) -> Result<(), String> {
+    let args = Args::parse(args, &["--history"], &["--no-history"])?;
+    args.operands::<0>()?;
+    let history = match args.get("--history") {
+        _ if args.switch("--no-history") => History::new(),
+        path => {
+            let path = path
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os(HISTORY_VAR).map(PathBuf::from))
+                .unwrap_or_else(|| PathBuf::from(HISTORY_FILE));
+            History::open(path.clone()).unwrap_or_else(|e| {
+                eprintln!("warning: history file {}: {}", path.display(), e);
+                History::new()
+            })
+        }
+    };
+    let mut session = Session::new(history);
+    println!("simd repl at {}, `help` for commands, `ops` for operations", session.isa);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+        let line = line.map_err(|e| e.to_string())?;
+        let line = line.trim();
+        let expanded = match session.history.expand(line) {
+            Ok(expanded) => expanded,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                continue;
+            }
+        };
+        if expanded != line {
+            println!("{}", expanded);
+        }
+        let line = expanded;
+        if matches!(line.as_str(), "quit" | "exit") {
+            return Ok(());
+        }
+        let result = session.eval(&line);
+        if !line.is_empty() {
+            session.history.push(&line);
+        }
+        match result {
+            Ok(out) => print!("{}", out),
+            Err(e) => eprintln!("error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(tokens("c = adds_epi16 a  i16:[1, 2]").unwrap(), ["c", "=", "adds_epi16", "a", "i16:[1, 2]"]);
+        assert_eq!(tokens("  ").unwrap(), Vec::<&str>::new());
+        assert!(tokens("a = i16:[1, 2").is_err());
+        assert!(tokens("a]").is_err());
+    }
+
+    #[test]
+    fn test_session() {
+        let mut s = Session::new(History::new());
+        s.eval("a = i16:[10, 200, 30, -32766, 50, 60, 32000, -32000]").unwrap();
+        s.eval("b = i16:[100, -200, 32760, -400, 500, -600, 1200, -950]").unwrap();
+        s.eval("c = _mm_adds_epi16 a b").unwrap();
+        let c = *s.var("c").unwrap();
+        assert_eq!(c.reg, Reg::Xmm(Xmm::from_i16([110, 0, 32767, -32768, 550, -540, 32767, -32768])));
+        assert_eq!(c.view, View::Lanes(LaneType::I16));
+        assert_eq!(s.eval("c as u16x").unwrap(), format!("  u16x  {}\n", Xmm::from_i16([110, 0, 32767, -32768, 550, -540, 32767, -32768]).fmt_u16hex().trim_end()));
+
+        s.eval("d = srai_epi16 i16:[-64, -64, -64, -64, -64, -64, -64, -64, 1, 2, 3, 4, 5, 6, 7, 8] 4").unwrap();
+        let d = s.var("d").unwrap().reg;
+        let Reg::Ymm(d) = d else { panic!("16 i16 lanes make a ymm") };
+        assert_eq!(d.as_i16()[..9], [-4, -4, -4, -4, -4, -4, -4, -4, 0]);
+
+        assert!(s.eval("e = add_epi16 a d").unwrap_err().contains("differ in width"));
+        assert!(s.eval("e = add_epi16 a").unwrap_err().contains("usage"));
+        assert!(s.eval("e = nope a b").unwrap_err().contains("unknown"));
+        assert!(s.eval("xor = a").is_err());
+        assert!(s.eval("a as i12").is_err());
+        assert!(s.var("e").is_none());
+    }
+
+    #[test]
+    fn test_literal_errors() {
+        let err = |s: &str| parse_literal(s).unwrap_err();
+        let bytes = |last: &str, n: usize| format!("u8:[{}{}]", "1,".repeat(n - 1), last);
+        assert_eq!(err(&bytes("256", 16)), "lane 15: `256` is out of range for u8");
+        assert_eq!(err(&bytes("256", 32)), "lane 31: `256` is out of range for u8");
+        assert_eq!(err("u8:[1, 2]"), "expected 16 u8 lanes, got 2 (or twice as many for a 256-bit register)");
+        assert_eq!(err("0x0102"), "expected 32 hex digits, got 4 (or twice as many for a 256-bit register)");
+        assert!(err("i12:[1]").starts_with("unknown lane type"));
+    }
+
+    #[test]
+    fn test_isa_levels_agree() {
+        let mut s = Session::new(History::new());
+        s.eval("a = u8:[250, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 255]").unwrap();
+        let mut results = Vec::new();
+        for isa in Isa::supported() {
+            s.eval(&format!("isa {}", isa)).unwrap();
+            assert_eq!(s.isa, isa);
+            s.eval("b = adds_epu8 a a").unwrap();
+            results.push(s.var("b").unwrap().reg);
+        }
+        assert!(results.windows(2).all(|w| w[0] == w[1]));
+    }
+
+    #[test]
+    fn test_history() {
+        let mut h = History::new();
+        h.push("a = 1");
+        h.push("a = 1");
+        h.push("a");
+        assert_eq!(h.lines, ["a = 1", "a"]);
+        assert_eq!(h.expand("!!").unwrap(), "a");
+        assert_eq!(h.expand("!1").unwrap(), "a = 1");
+        assert!(h.expand("!3").is_err());
+        assert!(h.expand("!0").is_err());
+        assert_eq!(h.expand("b").unwrap(), "b");
+
+        let path = std::env::temp_dir().join(format!("simd_history_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        History::open(path.clone()).unwrap().push("x = 0x0");
+        let mut h = History::open(path.clone()).unwrap();
+        h.push("x");
+        assert_eq!(History::open(path.clone()).unwrap().lines, ["x = 0x0", "x"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
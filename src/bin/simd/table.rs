@@ -0,0 +1,295 @@
+//! The operations the REPL knows, by intrinsic name without the `_mm_` or
+//! `_mm256_` prefix. Each maps to the library wrappers for both widths, so
+//! `adds_epi16` is `_mm_adds_epi16` on 128-bit operands and `_mm256_adds_epi16`
+//! on 256-bit ones, dispatched like every other library call.
+
+use simd::lanes::*;
+use simd::xmm::Xmm;
+use simd::ymm::Ymm;
+
+use crate::values::LaneType;
+
+/// How to show a register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Lanes(LaneType),
+    /// `u16` lanes in hex, as printed by `fmt_u16hex`.
+    U16Hex,
+}
+
+impl View {
+    pub fn parse(s: &str) -> Result<View, String> {
+        match s {
+            "u16x" => Ok(View::U16Hex),
+            s => s.parse().map(View::Lanes).map_err(|e: String| format!("{}, or u16x", e)),
+        }
+    }
+}
+
+pub enum Kernel {
+    Unary(fn(Xmm) -> Xmm, fn(Ymm) -> Ymm),
+    Binary(fn(Xmm, Xmm) -> Xmm, fn(Ymm, Ymm) -> Ymm),
+    /// A register and a shift count.
+    Shift(fn(Xmm, u32) -> Xmm, fn(Ymm, u32) -> Ymm),
+}
+
+pub struct Op {
+    pub name: &'static str,
+    pub kernel: Kernel,
+    /// View of the result.
+    pub view: View,
+    pub doc: &'static str,
+}
+
+impl Op {
+    pub fn usage(&self) -> String {
+        let args = match self.kernel {
+            Kernel::Unary(..) => "a",
+            Kernel::Binary(..) => "a b",
+            Kernel::Shift(..) => "a count",
+        };
+        format!("{} {}", self.name, args)
+    }
+}
+
+/// Looks up an operation, ignoring an `_mm_` or `_mm256_` prefix.
+pub fn find(name: &str) -> Option<&'static Op> {
+    let name = name.strip_prefix("_mm256_").or_else(|| name.strip_prefix("_mm_")).unwrap_or(name);
+    OPS.iter().find(|op| op.name == name)
+}
+
+/// A generic register method of the library, such as `add_saturating::<T>`
+/// or `sqrt_float::<T>()`.
+macro_rules! method {
+    ($name:literal, $method:ident::<$t:ident>, $view:ident, $doc:literal) => {
+        Op {
+            name: $name,
+            kernel: Kernel::Binary(|a: Xmm, b| a.$method::<$t>(b), |a: Ymm, b| a.$method::<$t>(b)),
+            view: View::Lanes(LaneType::$view),
+            doc: $doc,
+        }
+    };
+    ($name:literal, $method:ident::<$t:ident>(), $view:ident, $doc:literal) => {
+        Op {
+            name: $name,
+            kernel: Kernel::Unary(|a: Xmm| a.$method::<$t>(), |a: Ymm| a.$method::<$t>()),
+            view: View::Lanes(LaneType::$view),
+            doc: $doc,
+        }
+    };
+}
+
+/// An operator of the [`simd::lanes`] wrappers `$v128` and `$v256`.
+macro_rules! wrapped {
+    ($name:literal, $v128:ident, $v256:ident, |$a:ident, $b:ident| $op:expr, $view:expr, $doc:literal) => {
+        Op {
+            name: $name,
+            kernel: Kernel::Binary(
+                |a, b| {
+                    let ($a, $b) = ($v128::from(a), $v128::from(b));
+                    Xmm::from($op)
+                },
+                |a, b| {
+                    let ($a, $b) = ($v256::from(a), $v256::from(b));
+                    Ymm::from($op)
+                },
+            ),
+            view: $view,
+            doc: $doc,
+        }
+    };
+    ($name:literal, $v128:ident, $v256:ident, |$a:ident| $op:expr, $view:expr, $doc:literal) => {
+        Op {
+            name: $name,
+            kernel: Kernel::Unary(
+                |a| {
+                    let $a = $v128::from(a);
+                    Xmm::from($op)
+                },
+                |a| {
+                    let $a = $v256::from(a);
+                    Ymm::from($op)
+                },
+            ),
+            view: $view,
+            doc: $doc,
+        }
+    };
+}
+
+/// A shift operator of the [`simd::lanes`] wrappers.
+macro_rules! shift {
+    ($name:literal, $v128:ident, $v256:ident, $op:tt, $view:ident, $doc:literal) => {
+        Op {
+            name: $name,
+            kernel: Kernel::Shift(|a, n| Xmm::from($v128::from(a) $op n), |a, n| Ymm::from($v256::from(a) $op n)),
+            view: View::Lanes(LaneType::$view),
+            doc: $doc,
+        }
+    };
+}
+
+/// A comparison of the library, shown as the lanes of its mask.
+macro_rules! compare {
+    ($name:literal, $method:ident::<$t:ident>, $view:ident, $doc:literal) => {
+        Op {
+            name: $name,
+            kernel: Kernel::Binary(|a: Xmm, b| a.$method::<$t>(b).into(), |a: Ymm, b| a.$method::<$t>(b).into()),
+            view: View::Lanes(LaneType::$view),
+            doc: $doc,
+        }
+    };
+}
+
+pub static OPS: &[Op] = &[
+    method!("add_epi8", add_wrapping::<i8>, I8, "add 8-bit lanes, wraparound"),
+    method!("add_epi16", add_wrapping::<i16>, I16, "add 16-bit lanes, wraparound"),
+    method!("add_epi32", add_wrapping::<i32>, I32, "add 32-bit lanes, wraparound"),
+    method!("add_epi64", add_wrapping::<i64>, I64, "add 64-bit lanes, wraparound"),
+    method!("adds_epi8", add_saturating::<i8>, I8, "add signed 8-bit lanes, saturated"),
+    method!("adds_epi16", add_saturating::<i16>, I16, "add signed 16-bit lanes, saturated"),
+    method!("adds_epi32", add_saturating::<i32>, I32, "add signed 32-bit lanes, saturated (emulated)"),
+    method!("adds_epi64", add_saturating::<i64>, I64, "add signed 64-bit lanes, saturated (emulated)"),
+    method!("adds_epu8", add_saturating::<u8>, U8, "add unsigned 8-bit lanes, saturated"),
+    method!("adds_epu16", add_saturating::<u16>, U16, "add unsigned 16-bit lanes, saturated"),
+    method!("adds_epu32", add_saturating::<u32>, U32, "add unsigned 32-bit lanes, saturated (emulated)"),
+    method!("adds_epu64", add_saturating::<u64>, U64, "add unsigned 64-bit lanes, saturated (emulated)"),
+    method!("sub_epi8", sub_wrapping::<i8>, I8, "subtract 8-bit lanes, wraparound"),
+    method!("sub_epi16", sub_wrapping::<i16>, I16, "subtract 16-bit lanes, wraparound"),
+    method!("sub_epi32", sub_wrapping::<i32>, I32, "subtract 32-bit lanes, wraparound"),
+    method!("sub_epi64", sub_wrapping::<i64>, I64, "subtract 64-bit lanes, wraparound"),
+    method!("subs_epi8", sub_saturating::<i8>, I8, "subtract signed 8-bit lanes, saturated"),
+    method!("subs_epi16", sub_saturating::<i16>, I16, "subtract signed 16-bit lanes, saturated"),
+    method!("subs_epi32", sub_saturating::<i32>, I32, "subtract signed 32-bit lanes, saturated (emulated)"),
+    method!("subs_epi64", sub_saturating::<i64>, I64, "subtract signed 64-bit lanes, saturated (emulated)"),
+    method!("subs_epu8", sub_saturating::<u8>, U8, "subtract unsigned 8-bit lanes, saturated"),
+    method!("subs_epu16", sub_saturating::<u16>, U16, "subtract unsigned 16-bit lanes, saturated"),
+    method!("subs_epu32", sub_saturating::<u32>, U32, "subtract unsigned 32-bit lanes, saturated (emulated)"),
+    method!("subs_epu64", sub_saturating::<u64>, U64, "subtract unsigned 64-bit lanes, saturated (emulated)"),
+    method!("mullo_epi8", mul_lo::<i8>, I8, "low half of 8-bit products (emulated)"),
+    method!("mullo_epi16", mul_lo::<i16>, I16, "low half of 16-bit products"),
+    method!("mullo_epi32", mul_lo::<i32>, I32, "low half of 32-bit products"),
+    method!("mullo_epi64", mul_lo::<i64>, I64, "low half of 64-bit products (emulated)"),
+    method!("mulhi_epi8", mul_hi::<i8>, I8, "high half of signed 8-bit products (emulated)"),
+    method!("mulhi_epi16", mul_hi::<i16>, I16, "high half of signed 16-bit products"),
+    method!("mulhi_epi32", mul_hi::<i32>, I32, "high half of signed 32-bit products (emulated)"),
+    method!("mulhi_epi64", mul_hi::<i64>, I64, "high half of signed 64-bit products (emulated)"),
+    method!("mulhi_epu8", mul_hi::<u8>, U8, "high half of unsigned 8-bit products (emulated)"),
+    method!("mulhi_epu16", mul_hi::<u16>, U16, "high half of unsigned 16-bit products"),
+    method!("mulhi_epu32", mul_hi::<u32>, U32, "high half of unsigned 32-bit products (emulated)"),
+    method!("mulhi_epu64", mul_hi::<u64>, U64, "high half of unsigned 64-bit products (emulated)"),
+    wrapped!("and", U64x2, U64x4, |a, b| a & b, View::U16Hex, "bitwise and"),
+    wrapped!("or", U64x2, U64x4, |a, b| a | b, View::U16Hex, "bitwise or"),
+    wrapped!("xor", U64x2, U64x4, |a, b| a ^ b, View::U16Hex, "bitwise xor"),
+    wrapped!("andnot", U64x2, U64x4, |a, b| !a & b, View::U16Hex, "bitwise and of not a and b"),
+    wrapped!("not", U64x2, U64x4, |a| !a, View::U16Hex, "bitwise not"),
+    shift!("slli_epi8", U8x16, U8x32, <<, U8, "shift 8-bit lanes left (emulated)"),
+    shift!("slli_epi16", U16x8, U16x16, <<, U16, "shift 16-bit lanes left"),
+    shift!("slli_epi32", U32x4, U32x8, <<, U32, "shift 32-bit lanes left"),
+    shift!("slli_epi64", U64x2, U64x4, <<, U64, "shift 64-bit lanes left"),
+    shift!("srli_epi8", U8x16, U8x32, >>, U8, "shift 8-bit lanes right in zeros (emulated)"),
+    shift!("srli_epi16", U16x8, U16x16, >>, U16, "shift 16-bit lanes right in zeros"),
+    shift!("srli_epi32", U32x4, U32x8, >>, U32, "shift 32-bit lanes right in zeros"),
+    shift!("srli_epi64", U64x2, U64x4, >>, U64, "shift 64-bit lanes right in zeros"),
+    shift!("srai_epi8", I8x16, I8x32, >>, I8, "shift 8-bit lanes right in sign bits (emulated)"),
+    shift!("srai_epi16", I16x8, I16x16, >>, I16, "shift 16-bit lanes right in sign bits"),
+    shift!("srai_epi32", I32x4, I32x8, >>, I32, "shift 32-bit lanes right in sign bits"),
+    shift!("srai_epi64", I64x2, I64x4, >>, I64, "shift 64-bit lanes right in sign bits (emulated)"),
+    method!("sllv_epi8", shift_left_each::<u8>, U8, "shift 8-bit lanes left by the lanes of b (emulated)"),
+    method!("sllv_epi16", shift_left_each::<u16>, U16, "shift 16-bit lanes left by the lanes of b (emulated)"),
+    method!("sllv_epi32", shift_left_each::<u32>, U32, "shift 32-bit lanes left by the lanes of b"),
+    method!("sllv_epi64", shift_left_each::<u64>, U64, "shift 64-bit lanes left by the lanes of b"),
+    method!("srlv_epi8", shift_right_each::<u8>, U8, "shift 8-bit lanes right in zeros by the lanes of b (emulated)"),
+    method!("srlv_epi16", shift_right_each::<u16>, U16, "shift 16-bit lanes right in zeros by the lanes of b (emulated)"),
+    method!("srlv_epi32", shift_right_each::<u32>, U32, "shift 32-bit lanes right in zeros by the lanes of b"),
+    method!("srlv_epi64", shift_right_each::<u64>, U64, "shift 64-bit lanes right in zeros by the lanes of b"),
+    method!("srav_epi8", shift_right_each::<i8>, I8, "shift 8-bit lanes right in sign bits by the lanes of b (emulated)"),
+    method!("srav_epi16", shift_right_each::<i16>, I16, "shift 16-bit lanes right in sign bits by the lanes of b (emulated)"),
+    method!("srav_epi32", shift_right_each::<i32>, I32, "shift 32-bit lanes right in sign bits by the lanes of b"),
+    method!("srav_epi64", shift_right_each::<i64>, I64, "shift 64-bit lanes right in sign bits by the lanes of b (emulated)"),
+    method!("rolv_epi32", rotate_left_each::<u32>, U32, "rotate 32-bit lanes left by the lanes of b (emulated)"),
+    method!("rorv_epi32", rotate_right_each::<u32>, U32, "rotate 32-bit lanes right by the lanes of b (emulated)"),
+    method!("rolv_epi64", rotate_left_each::<u64>, U64, "rotate 64-bit lanes left by the lanes of b (emulated)"),
+    method!("rorv_epi64", rotate_right_each::<u64>, U64, "rotate 64-bit lanes right by the lanes of b (emulated)"),
+    wrapped!("add_ps", F32x4, F32x8, |a, b| a + b, View::Lanes(LaneType::F32), "add f32 lanes"),
+    wrapped!("sub_ps", F32x4, F32x8, |a, b| a - b, View::Lanes(LaneType::F32), "subtract f32 lanes"),
+    wrapped!("mul_ps", F32x4, F32x8, |a, b| a * b, View::Lanes(LaneType::F32), "multiply f32 lanes"),
+    wrapped!("div_ps", F32x4, F32x8, |a, b| a / b, View::Lanes(LaneType::F32), "divide f32 lanes"),
+    wrapped!("add_pd", F64x2, F64x4, |a, b| a + b, View::Lanes(LaneType::F64), "add f64 lanes"),
+    wrapped!("sub_pd", F64x2, F64x4, |a, b| a - b, View::Lanes(LaneType::F64), "subtract f64 lanes"),
+    wrapped!("mul_pd", F64x2, F64x4, |a, b| a * b, View::Lanes(LaneType::F64), "multiply f64 lanes"),
+    wrapped!("div_pd", F64x2, F64x4, |a, b| a / b, View::Lanes(LaneType::F64), "divide f64 lanes"),
+    method!("min_ps", min_float::<f32>, F32, "minimum of f32 lanes, the second if either is NaN"),
+    method!("max_ps", max_float::<f32>, F32, "maximum of f32 lanes, the second if either is NaN"),
+    method!("sqrt_ps", sqrt_float::<f32>(), F32, "square root of f32 lanes"),
+    method!("abs_ps", abs_float::<f32>(), F32, "absolute value of f32 lanes"),
+    method!("floor_ps", floor_float::<f32>(), F32, "round f32 lanes down"),
+    method!("ceil_ps", ceil_float::<f32>(), F32, "round f32 lanes up"),
+    method!("round_ps", round_float::<f32>(), F32, "round f32 lanes to nearest, ties to even"),
+    method!("rcp_ps", recip_float::<f32>(), F32, "approximate reciprocal of f32 lanes"),
+    method!("rsqrt_ps", rsqrt_float::<f32>(), F32, "approximate reciprocal square root of f32 lanes"),
+    method!("min_pd", min_float::<f64>, F64, "minimum of f64 lanes, the second if either is NaN"),
+    method!("max_pd", max_float::<f64>, F64, "maximum of f64 lanes, the second if either is NaN"),
+    method!("sqrt_pd", sqrt_float::<f64>(), F64, "square root of f64 lanes"),
+    method!("abs_pd", abs_float::<f64>(), F64, "absolute value of f64 lanes"),
+    method!("floor_pd", floor_float::<f64>(), F64, "round f64 lanes down"),
+    method!("ceil_pd", ceil_float::<f64>(), F64, "round f64 lanes up"),
+    method!("round_pd", round_float::<f64>(), F64, "round f64 lanes to nearest, ties to even"),
+    method!("rcp_pd", recip_float::<f64>(), F64, "approximate reciprocal of f64 lanes"),
+    method!("rsqrt_pd", rsqrt_float::<f64>(), F64, "approximate reciprocal square root of f64 lanes"),
+    compare!("cmpeq_epi8", cmp_eq::<i8>, I8, "mask of equal 8-bit lanes"),
+    compare!("cmpgt_epi8", cmp_gt::<i8>, I8, "mask of signed 8-bit lanes of a greater than b"),
+    compare!("cmplt_epi8", cmp_lt::<i8>, I8, "mask of signed 8-bit lanes of a less than b"),
+    compare!("cmpeq_epi16", cmp_eq::<i16>, I16, "mask of equal 16-bit lanes"),
+    compare!("cmpgt_epi16", cmp_gt::<i16>, I16, "mask of signed 16-bit lanes of a greater than b"),
+    compare!("cmplt_epi16", cmp_lt::<i16>, I16, "mask of signed 16-bit lanes of a less than b"),
+    compare!("cmpeq_epi32", cmp_eq::<i32>, I32, "mask of equal 32-bit lanes"),
+    compare!("cmpgt_epi32", cmp_gt::<i32>, I32, "mask of signed 32-bit lanes of a greater than b"),
+    compare!("cmplt_epi32", cmp_lt::<i32>, I32, "mask of signed 32-bit lanes of a less than b"),
+    compare!("cmpeq_epi64", cmp_eq::<i64>, I64, "mask of equal 64-bit lanes (emulated)"),
+    compare!("cmpgt_epi64", cmp_gt::<i64>, I64, "mask of signed 64-bit lanes of a greater than b (emulated)"),
+    compare!("cmplt_epi64", cmp_lt::<i64>, I64, "mask of signed 64-bit lanes of a less than b (emulated)"),
+    compare!("cmpgt_epu8", cmp_gt::<u8>, I8, "mask of unsigned 8-bit lanes of a greater than b (emulated)"),
+    compare!("cmplt_epu8", cmp_lt::<u8>, I8, "mask of unsigned 8-bit lanes of a less than b (emulated)"),
+    compare!("cmpgt_epu16", cmp_gt::<u16>, I16, "mask of unsigned 16-bit lanes of a greater than b (emulated)"),
+    compare!("cmplt_epu16", cmp_lt::<u16>, I16, "mask of unsigned 16-bit lanes of a less than b (emulated)"),
+    compare!("cmpgt_epu32", cmp_gt::<u32>, I32, "mask of unsigned 32-bit lanes of a greater than b (emulated)"),
+    compare!("cmplt_epu32", cmp_lt::<u32>, I32, "mask of unsigned 32-bit lanes of a less than b (emulated)"),
+    compare!("cmpgt_epu64", cmp_gt::<u64>, I64, "mask of unsigned 64-bit lanes of a greater than b (emulated)"),
+    compare!("cmplt_epu64", cmp_lt::<u64>, I64, "mask of unsigned 64-bit lanes of a less than b (emulated)"),
+    compare!("cmpeq_ps", cmp_eq::<f32>, I32, "mask of f32 lanes of a equal to b"),
+    compare!("cmpneq_ps", cmp_ne::<f32>, I32, "mask of f32 lanes of a unequal or unordered b"),
+    compare!("cmplt_ps", cmp_lt::<f32>, I32, "mask of f32 lanes of a less than b"),
+    compare!("cmple_ps", cmp_le::<f32>, I32, "mask of f32 lanes of a less than or equal b"),
+    compare!("cmpgt_ps", cmp_gt::<f32>, I32, "mask of f32 lanes of a greater than b"),
+    compare!("cmpge_ps", cmp_ge::<f32>, I32, "mask of f32 lanes of a greater than or equal b"),
+    compare!("cmpeq_pd", cmp_eq::<f64>, I64, "mask of f64 lanes of a equal to b"),
+    compare!("cmpneq_pd", cmp_ne::<f64>, I64, "mask of f64 lanes of a unequal or unordered b"),
+    compare!("cmplt_pd", cmp_lt::<f64>, I64, "mask of f64 lanes of a less than b"),
+    compare!("cmple_pd", cmp_le::<f64>, I64, "mask of f64 lanes of a less than or equal b"),
+    compare!("cmpgt_pd", cmp_gt::<f64>, I64, "mask of f64 lanes of a greater than b"),
+    compare!("cmpge_pd", cmp_ge::<f64>, I64, "mask of f64 lanes of a greater than or equal b"),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        for (i, op) in OPS.iter().enumerate() {
+            assert!(OPS[..i].iter().all(|o| o.name != op.name), "duplicate {}", op.name);
+        }
+        let op = find("_mm256_adds_epi16").unwrap();
+        let Kernel::Binary(xmm, ymm) = op.kernel else { panic!("adds_epi16 is binary") };
+        assert_eq!(xmm(Xmm::splat_i16(i16::MAX), Xmm::splat_i16(1)), Xmm::splat_i16(i16::MAX));
+        assert_eq!(ymm(Ymm::splat_i16(-5), Ymm::splat_i16(2)), Ymm::splat_i16(-3));
+        let Kernel::Shift(xmm, _) = find("srai_epi16").unwrap().kernel else { panic!("srai_epi16 is a shift") };
+        assert_eq!(xmm(Xmm::splat_i16(-64), 4), Xmm::splat_i16(-4));
+        let Kernel::Binary(xmm, _) = find("cmpgt_epu8").unwrap().kernel else { panic!("cmpgt_epu8 is binary") };
+        assert_eq!(xmm(Xmm::splat_i8(-1), Xmm::splat_i8(1)), Xmm::splat_i8(-1));
+        let Kernel::Binary(xmm, _) = find("srav_epi8").unwrap().kernel else { panic!("srav_epi8 is binary") };
+        assert_eq!(xmm(Xmm::splat_i8(-64), Xmm::splat_i8(3)), Xmm::splat_i8(-8));
+        assert!(find("madd_epi16").is_none());
+    }
+}
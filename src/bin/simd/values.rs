@@ -0,0 +1,271 @@
+//! Lane types, register widths and lane lists as they appear on the command
+//! line.
+
+use std::fmt;
+use std::str::FromStr;
+
+use simd::chunks::Register;
+use simd::parse::{parse_lanes, ParseLane, ParseRegisterError};
+use simd::xmm::Xmm;
+use simd::ymm::Ymm;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaneType {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+}
+
+impl LaneType {
+    pub const ALL: [LaneType; 10] = [
+        LaneType::I8,
+        LaneType::I16,
+        LaneType::I32,
+        LaneType::I64,
+        LaneType::U8,
+        LaneType::U16,
+        LaneType::U32,
+        LaneType::U64,
+        LaneType::F32,
+        LaneType::F64,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LaneType::I8 => "i8",
+            LaneType::I16 => "i16",
+            LaneType::I32 => "i32",
+            LaneType::I64 => "i64",
+            LaneType::U8 => "u8",
+            LaneType::U16 => "u16",
+            LaneType::U32 => "u32",
+            LaneType::U64 => "u64",
+            LaneType::F32 => "f32",
+            LaneType::F64 => "f64",
+        }
+    }
+
+    /// Returns the type of the full product of two lanes, `None` for the
+    /// 64-bit types whose 128-bit products have no lane type.
+    pub fn widened(self) -> Option<LaneType> {
+        match self {
+            LaneType::I8 => Some(LaneType::I16),
+            LaneType::I16 => Some(LaneType::I32),
+            LaneType::I32 => Some(LaneType::I64),
+            LaneType::U8 => Some(LaneType::U16),
+            LaneType::U16 => Some(LaneType::U32),
+            LaneType::U32 => Some(LaneType::U64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LaneType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for LaneType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LaneType::ALL
+            .into_iter()
+            .find(|t| t.name() == s)
+            .ok_or_else(|| format!("unknown lane type `{}`, expected one of i8…i64, u8…u64, f32, f64", s))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    W128,
+    W256,
+}
+
+impl FromStr for Width {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "128" | "xmm" => Ok(Width::W128),
+            "256" | "ymm" => Ok(Width::W256),
+            _ => Err(format!("unsupported width `{}`, expected 128 or 256", s)),
+        }
+    }
+}
+
+/// Parses a list of lanes separated by commas.
+pub fn parse_list<T: ParseLane>(s: &str) -> Result<Vec<T>, String> {
+    parse_lanes(s, false).map_err(|e| e.to_string())
+}
+
+/// Parses a register operand: a register in any notation [`simd::parse`]
+/// accepts, or a comma-separated list of all its `T` lanes, or a single value
+/// for every lane.
+pub fn parse_reg<T: ParseLane, R: Register + FromStr<Err = ParseRegisterError>>(s: &str) -> Result<R, String> {
+    let raw_hex = s.strip_prefix("0x").is_some_and(|digits| digits.replace('_', "").len() == 2 * R::BYTES);
+    if s.contains(':') || raw_hex {
+        return s.parse().map_err(|e: ParseRegisterError| e.to_string());
+    }
+    let lanes = parse_list::<T>(s)?;
+    let n = R::lane_count::<T>();
+    match lanes.len() {
+        1 => Ok(R::splat(lanes[0])),
+        len if len == n => {
+            let mut reg = R::default();
+            reg.lanes_mut::<T>().copy_from_slice(&lanes);
+            Ok(reg)
+        }
+        len => Err(format!("expected {} {} lanes, got {}", n, T::NAME, len)),
+    }
+}
+
+/// Formats a register with the `fmt_*` method of a lane type.
+pub trait Show {
+    fn show(&self, ty: LaneType) -> String;
+
+    /// Like [`Show::show`], but `u16` lanes are printed in hex.
+    fn show_hex(&self, ty: LaneType) -> String;
+}
+
+macro_rules! impl_show {
+    ($($reg:ident),*) => {
+        $(
+            impl Show for $reg {
+                fn show(&self, ty: LaneType) -> String {
+                    match ty {
+                        LaneType::I8 => self.fmt_i8(),
+                        LaneType::I16 => self.fmt_i16(),
+                        LaneType::I32 => self.fmt_i32(),
+                        LaneType::I64 => self.fmt_i64(),
+                        LaneType::U8 => self.fmt_u8(),
+                        LaneType::U16 => self.fmt_u16(),
+                        LaneType::U32 => self.fmt_u32(),
+                        LaneType::U64 => self.fmt_u64(),
+                        LaneType::F32 => self.fmt_f32(),
+                        LaneType::F64 => self.fmt_f64(),
+                    }
+                }
+
+                fn show_hex(&self, ty: LaneType) -> String {
+                    match ty {
+                        LaneType::U16 => self.fmt_u16hex(),
+                        ty => self.show(ty),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_show!(Xmm, Ymm);
+
+/// Runs `$body` with the type aliases `$t` for the lane type, `$r` for the
+/// register of the width and `$v` for the matching [`simd::lanes`] wrapper.
+/// Evaluates to an error for the lane types the macro does not cover.
+macro_rules! with_types {
+    (@arms $ty:expr, $width:expr, $t:ident, $r:ident, $v:ident, $body:expr, $what:literal,
+     $($name:ident $prim:ident $v128:ident $v256:ident),*) => {
+        match ($ty, $width) {
+            $(
+                ($crate::values::LaneType::$name, $crate::values::Width::W128) => {
+                    #[allow(dead_code)]
+                    type $t = $prim;
+                    #[allow(dead_code)]
+                    type $r = simd::xmm::Xmm;
+                    #[allow(dead_code)]
+                    type $v = simd::lanes::$v128;
+                    $body
+                }
+                ($crate::values::LaneType::$name, $crate::values::Width::W256) => {
+                    #[allow(dead_code)]
+                    type $t = $prim;
+                    #[allow(dead_code)]
+                    type $r = simd::ymm::Ymm;
+                    #[allow(dead_code)]
+                    type $v = simd::lanes::$v256;
+                    $body
+                }
+            )*
+            #[allow(unreachable_patterns)]
+            (ty, _) => Err(format!("{} is not {} type", ty, $what)),
+        }
+    };
+}
+
+/// [`with_types!`] over the integer lane types.
+macro_rules! with_int {
+    ($ty:expr, $width:expr, |$t:ident, $r:ident, $v:ident| $body:expr) => {
+        $crate::values::with_types!(@arms $ty, $width, $t, $r, $v, $body, "an integer",
+            I8 i8 I8x16 I8x32, I16 i16 I16x8 I16x16, I32 i32 I32x4 I32x8, I64 i64 I64x2 I64x4,
+            U8 u8 U8x16 U8x32, U16 u16 U16x8 U16x16, U32 u32 U32x4 U32x8, U64 u64 U64x2 U64x4)
+    };
+}
+
+/// [`with_types!`] over the float lane types.
+macro_rules! with_float {
+    ($ty:expr, $width:expr, |$t:ident, $r:ident, $v:ident| $body:expr) => {
+        $crate::values::with_types!(@arms $ty, $width, $t, $r, $v, $body, "a float",
+            F32 f32 F32x4 F32x8, F64 f64 F64x2 F64x4)
+    };
+}
+
+/// Runs `$body` with `$t` aliased to any lane type.
+macro_rules! with_lane {
+    ($ty:expr, |$t:ident| $body:expr) => {
+        $crate::values::with_lane!(@arms $ty, $t, $body, I8 i8, I16 i16, I32 i32, I64 i64, U8 u8, U16 u16, U32 u32, U64 u64, F32 f32, F64 f64)
+    };
+    (@arms $ty:expr, $t:ident, $body:expr, $($name:ident $prim:ident),*) => {
+        match $ty {
+            $(
+                $crate::values::LaneType::$name => {
+                    type $t = $prim;
+                    $body
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use {with_float, with_int, with_lane, with_types};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_operands() {
+        let a: Xmm = parse_reg::<i16, _>("10, 200,30,-32766,50,60,32000,0xFFFF").unwrap();
+        assert_eq!(a, Xmm::from_i16([10, 200, 30, -32766, 50, 60, 32000, -1]));
+        let b: Ymm = parse_reg::<u8, _>("7").unwrap();
+        assert_eq!(b, Ymm::splat_u8(7));
+        let c: Xmm = parse_reg::<i16, _>("u32:[1,2,3,4]").unwrap();
+        assert_eq!(c, Xmm::from_u32([1, 2, 3, 4]));
+        let d: Xmm = parse_reg::<u64, _>("0x0000000000000001_0000000000000002").unwrap();
+        assert_eq!(d, Xmm::from_u64([2, 1]));
+        assert_eq!(parse_reg::<u64, Xmm>("0x10").unwrap(), Xmm::splat_u64(16));
+
+        assert_eq!(parse_reg::<i32, Xmm>("1,2,3").unwrap_err(), "expected 4 i32 lanes, got 3");
+        assert_eq!(parse_reg::<u8, Xmm>("1,256").unwrap_err(), "lane 1: `256` is out of range for u8");
+        assert_eq!(parse_list::<i8>("-129").unwrap_err(), "lane 0: `-129` is out of range for i8");
+    }
+
+    #[test]
+    fn test_with_types() {
+        fn lanes(ty: LaneType, width: Width) -> Result<usize, String> {
+            with_int!(ty, width, |T, R, V| Ok(R::lane_count::<T>().min(V::LANES)))
+        }
+        assert_eq!(lanes(LaneType::U16, Width::W256), Ok(16));
+        assert_eq!(lanes(LaneType::F32, Width::W128), Err("f32 is not an integer type".to_string()));
+        assert_eq!("f64".parse::<LaneType>().map(LaneType::widened), Ok(None));
+        assert_eq!(Xmm::splat_u16(0xAB).show_hex(LaneType::U16), Xmm::splat_u16(0xAB).fmt_u16hex());
+    }
+}
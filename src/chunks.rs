@@ -0,0 +1,306 @@
+//! Iteration over slices of lanes one register at a time.
+//!
+//! [`ChunksExact`] yields one register per full block of lanes and leaves the
+//! tail that does not fill a register to [`ChunksExact::remainder`], or to
+//! [`ChunksExact::remainder_padded`] for kernels that would rather process it
+//! as one more register. [`Chunks`] does the latter on its own, padding with
+//! zero lanes, which suits sums and bitwise ORs. Reductions such as min/max
+//! should pad with their identity instead.
+//!
+//! ```
+//! use simd::array::Array;
+//! use simd::xmm::Xmm;
+//!
+//! let array = Array::from_fn(35, 16, |i| i as u8);
+//! let mut chunks = array.chunks_xmm_exact();
+//! assert_eq!(chunks.next(), Some(Xmm::from_fn::<u8>(|i| i as u8)));
+//! assert_eq!(chunks.next(), Some(Xmm::from_fn::<u8>(|i| 16 + i as u8)));
+//! assert_eq!(chunks.next(), None);
+//! assert_eq!(chunks.remainder(), &[32, 33, 34]);
+//! assert_eq!(chunks.remainder_padded(0xFF).unwrap().as_u8()[..4], [32, 33, 34, 0xFF]);
+//! ```
+//!
+//! Registers are loaded with `read_unaligned`, which compiles to
+//! `movdqu`/`vmovdqu`. On a buffer aligned to the register width, such as an
+//! [`Array`](crate::array::Array) allocated with that alignment or an
+//! [`AlignedVec`](crate::aligned_vec::AlignedVec), every load is aligned and
+//! runs at the speed of `movdqa`.
+
+use std::marker::PhantomData;
+use std::ptr;
+
+use crate::lane::Lane;
+use crate::xmm::Xmm;
+use crate::ymm::Ymm;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A SIMD register type that slices of lanes can be loaded into.
+pub trait Register: sealed::Sealed + Copy + Default + PartialEq + Send + Sync + 'static {
+    /// Width of the register in bytes.
+    const BYTES: usize;
+
+    /// Number of `T` lanes in the register.
+    fn lane_count<T: Lane>() -> usize {
+        Self::BYTES / std::mem::size_of::<T>()
+    }
+
+    /// Views the register as a slice of `T` lanes.
+    fn lanes<T: Lane>(&self) -> &[T];
+
+    /// Mutably views the register as a slice of `T` lanes.
+    fn lanes_mut<T: Lane>(&mut self) -> &mut [T];
+
+    /// Creates a register with every `T` lane set to `x`.
+    fn splat<T: Lane>(x: T) -> Self {
+        let mut reg = Self::default();
+        reg.lanes_mut::<T>().fill(x);
+        reg
+    }
+}
+
+macro_rules! impl_register {
+    ($($reg:ident),*) => {
+        $(
+            impl sealed::Sealed for $reg {}
+
+            impl Register for $reg {
+                const BYTES: usize = std::mem::size_of::<$reg>();
+
+                #[inline(always)]
+                fn lanes<T: Lane>(&self) -> &[T] {
+                    $reg::lanes::<T>(self)
+                }
+
+                #[inline(always)]
+                fn lanes_mut<T: Lane>(&mut self) -> &mut [T] {
+                    $reg::lanes_mut::<T>(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_register!(Xmm, Ymm);
+
+/// Loads up to one register worth of lanes into a register whose remaining
+/// lanes are `fill`. Returns `None` if `lanes` is empty.
+///
+/// # Panics
+///
+/// Panics if `lanes` does not fit in `R`.
+pub fn load_padded<T: Lane, R: Register>(lanes: &[T], fill: T) -> Option<R> {
+    if lanes.is_empty() {
+        return None;
+    }
+    let mut reg = R::splat(fill);
+    reg.lanes_mut::<T>()[..lanes.len()].copy_from_slice(lanes);
+    Some(reg)
+}
+
+/// Splits a slice into an unaligned head, a body of registers that can be
+/// read with aligned loads, and a tail. The head and the tail are each shorter
+/// than one register, so kernels can peel them off as padded registers (see
+/// [`load_padded`]) or scalar loops.
+///
+/// ```
+/// use simd::chunks::split_aligned;
+/// use simd::xmm::Xmm;
+///
+/// let data: Vec<u8> = (0..100).collect();
+/// let (head, body, tail) = split_aligned::<u8, Xmm>(&data[3..]);
+/// assert_eq!(head.len() + 16 * body.len() + tail.len(), 97);
+/// assert!(head.len() < 16 && tail.len() < 16);
+/// ```
+pub fn split_aligned<T: Lane, R: Register>(slice: &[T]) -> (&[T], &[R], &[T]) {
+    // Computing the split by hand keeps the tail shorter than one register
+    // whatever the head. The head relies on `align_offset` returning the real
+    // offset, which std does not promise but does for pointers at run time.
+    let lanes = R::lane_count::<T>();
+    let head_len = slice.as_ptr().align_offset(std::mem::align_of::<R>()).min(slice.len());
+    debug_assert!(head_len < lanes, "`align_offset` gave up on aligning to a register");
+    let (head, rest) = slice.split_at(head_len);
+    let (body, tail) = rest.split_at(rest.len() - rest.len() % lanes);
+    if body.is_empty() {
+        return (head, &[], tail);
+    }
+    // Sound because `body` starts at a register boundary, holds a whole
+    // number of registers and every bit pattern of lanes is a valid register.
+    let body = unsafe { std::slice::from_raw_parts(body.as_ptr() as *const R, body.len() / lanes) };
+    (head, body, tail)
+}
+
+/// Iterator over full registers of a slice, see the [module documentation](self).
+#[derive(Clone, Debug)]
+pub struct ChunksExact<'a, T, R> {
+    chunks: std::slice::ChunksExact<'a, T>,
+    _marker: PhantomData<R>,
+}
+
+impl<'a, T: Lane, R: Register> ChunksExact<'a, T, R> {
+    pub fn new(slice: &'a [T]) -> Self {
+        Self { chunks: slice.chunks_exact(R::lane_count::<T>()), _marker: PhantomData }
+    }
+
+    /// Returns the lanes after the last full register.
+    pub fn remainder(&self) -> &'a [T] {
+        self.chunks.remainder()
+    }
+
+    /// Returns the remainder loaded into a register whose missing lanes are
+    /// `fill`, or `None` if the remainder is empty.
+    pub fn remainder_padded(&self, fill: T) -> Option<R> {
+        load_padded(self.remainder(), fill)
+    }
+}
+
+impl<T: Lane, R: Register> Iterator for ChunksExact<'_, T, R> {
+    type Item = R;
+
+    #[inline]
+    fn next(&mut self) -> Option<R> {
+        self.chunks.next().map(|chunk| unsafe { ptr::read_unaligned(chunk.as_ptr() as *const R) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<T: Lane, R: Register> ExactSizeIterator for ChunksExact<'_, T, R> {}
+
+/// Iterator over the registers of a slice, ending with the remainder padded
+/// with zero lanes if it is not empty.
+#[derive(Clone, Debug)]
+pub struct Chunks<'a, T, R> {
+    body: ChunksExact<'a, T, R>,
+    tail: bool,
+}
+
+impl<'a, T: Lane, R: Register> Chunks<'a, T, R> {
+    pub fn new(slice: &'a [T]) -> Self {
+        let body = ChunksExact::new(slice);
+        let tail = !body.remainder().is_empty();
+        Self { body, tail }
+    }
+}
+
+impl<T: Lane, R: Register> Iterator for Chunks<'_, T, R> {
+    type Item = R;
+
+    #[inline]
+    fn next(&mut self) -> Option<R> {
+        match self.body.next() {
+            Some(reg) => Some(reg),
+            None if self.tail => {
+                self.tail = false;
+                self.body.remainder_padded(T::default())
+            }
+            None => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.body.len() + self.tail as usize;
+        (n, Some(n))
+    }
+}
+
+impl<T: Lane, R: Register> ExactSizeIterator for Chunks<'_, T, R> {}
+
+/// Adds `chunks_xmm`, `chunks_ymm` and their `_exact` variants to a container
+/// of lanes with an `as_slice` method.
+macro_rules! impl_chunk_methods {
+    () => {
+        /// Iterates over the elements one [`Xmm`](crate::xmm::Xmm) at a time,
+        /// ending with the zero-padded remainder.
+        pub fn chunks_xmm(&self) -> $crate::chunks::Chunks<'_, T, $crate::xmm::Xmm> {
+            $crate::chunks::Chunks::new(self.as_slice())
+        }
+
+        /// Iterates over the elements one [`Ymm`](crate::ymm::Ymm) at a time,
+        /// ending with the zero-padded remainder.
+        pub fn chunks_ymm(&self) -> $crate::chunks::Chunks<'_, T, $crate::ymm::Ymm> {
+            $crate::chunks::Chunks::new(self.as_slice())
+        }
+
+        /// Iterates over the full [`Xmm`](crate::xmm::Xmm) blocks of the elements.
+        pub fn chunks_xmm_exact(&self) -> $crate::chunks::ChunksExact<'_, T, $crate::xmm::Xmm> {
+            $crate::chunks::ChunksExact::new(self.as_slice())
+        }
+
+        /// Iterates over the full [`Ymm`](crate::ymm::Ymm) blocks of the elements.
+        pub fn chunks_ymm_exact(&self) -> $crate::chunks::ChunksExact<'_, T, $crate::ymm::Ymm> {
+            $crate::chunks::ChunksExact::new(self.as_slice())
+        }
+    };
+}
+
+pub(crate) use impl_chunk_methods;
+
+#[cfg(test)]
+mod test {
+    use super::split_aligned;
+    use crate::aligned_vec::AlignedVec;
+    use crate::array::Array;
+    use crate::ymm::Ymm;
+
+    #[test]
+    fn test_chunks_exact_and_remainder() {
+        for len in [0, 1, 7, 8, 9, 15, 16, 17, 40] {
+            let array = Array::from_fn(len, 32, |i| i as i32 - 3);
+            let expected = array.as_slice();
+
+            let mut chunks = array.chunks_xmm_exact();
+            assert_eq!(chunks.len(), len / 4);
+            let mut seen = Vec::new();
+            for reg in chunks.by_ref() {
+                seen.extend_from_slice(reg.as_i32());
+            }
+            seen.extend_from_slice(chunks.remainder());
+            assert_eq!(seen, expected);
+            assert_eq!(chunks.remainder_padded(i32::MAX).is_some(), len % 4 != 0);
+
+            let mut seen = Vec::new();
+            for reg in array.chunks_ymm() {
+                seen.extend_from_slice(reg.as_i32());
+            }
+            assert_eq!(seen.len(), len.div_ceil(8) * 8);
+            assert_eq!(&seen[..len], expected);
+            assert!(seen[len..].iter().all(|&x| x == 0));
+        }
+    }
+
+    #[test]
+    fn test_chunks_padded_remainder() {
+        let v: AlignedVec<u16, 32> = (1..=19).collect();
+        let chunks = v.chunks_ymm_exact();
+        assert_eq!(chunks.remainder(), &[17, 18, 19]);
+        let tail = chunks.remainder_padded(u16::MAX).unwrap();
+        assert_eq!(tail.as_u16()[..4], [17, 18, 19, u16::MAX]);
+
+        let last = v.chunks_xmm().last().unwrap();
+        assert_eq!(last.as_u16(), &[17, 18, 19, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_split_aligned_peels_less_than_a_register() {
+        let v: AlignedVec<u16, 32> = (0..100).collect();
+        for start in 0..20 {
+            for end in start..100 {
+                let slice = &v[start..end];
+                let (head, body, tail) = split_aligned::<u16, Ymm>(slice);
+                assert!(head.len() < 16 && tail.len() < 16);
+                assert_eq!(head.len(), ((16 - start % 16) % 16).min(slice.len()));
+                let mut seen = head.to_vec();
+                for reg in body {
+                    seen.extend_from_slice(reg.as_u16());
+                }
+                seen.extend_from_slice(tail);
+                assert_eq!(seen, slice);
+            }
+        }
+    }
+}
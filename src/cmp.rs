@@ -0,0 +1,691 @@
+//! Packed comparisons returning a [`Mask`].
+//!
+//! ```
+//! use simd::cmp::LT_OQ;
+//! use simd::xmm::Xmm;
+//!
+//! let a = Xmm::from_u8([0, 10, 200, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+//! let b = Xmm::splat_u8(100);
+//! assert_eq!(a.cmp_gt::<u8>(b).to_bitmask(), 0b1100);
+//! assert_eq!(a.cmp_gt::<i8>(b).to_bitmask(), 0);
+//!
+//! let x = Xmm::from_f32([1.0, f32::NAN, 3.0, -0.0]);
+//! let y = Xmm::from_f32([2.0, 2.0, 3.0, 0.0]);
+//! assert_eq!(x.compare::<f32, LT_OQ>(y).to_bitmask(), 0b0001);
+//! assert_eq!(x.cmp_ne::<f32>(y).to_bitmask(), 0b0011);
+//! assert_eq!(x.cmp_eq::<f32>(y).to_bitmask(), 0b1100);
+//! ```
+//!
+//! `cmp_eq`, `cmp_ne`, `cmp_lt`, `cmp_le`, `cmp_gt` and `cmp_ge` work for
+//! every lane type and agree with Rust's operators, also for floats: a NaN
+//! compares unequal to everything, so only `cmp_ne` holds for it. Float
+//! lanes can also be compared with any of the 32 `_CMP_*` predicates of
+//! `_mm256_cmp_ps` through [`Xmm::compare`] and [`Ymm::compare`], with the
+//! constants of this module. The signalling and quiet forms give the same
+//! masks since floating-point exceptions are masked.
+//!
+//! SSE2 only compares signed integers for equality and greater-than, and
+//! 64-bit lanes not at all. Unsigned lanes are compared as signed ones after
+//! flipping their sign bits. 64-bit equality combines the two 32-bit halves
+//! and 64-bit greater-than takes the high halves' comparison, or the borrow
+//! of the subtraction where the high halves are equal. Everything else is
+//! the negation or the swap of those. AVX2 has all of them but unsigned
+//! compares, and SSE2 has the float predicates of `cmpps` but not those of
+//! AVX's `vcmpps`, which are built from their negations and swaps.
+
+use std::cmp::Ordering::{Equal, Greater, Less};
+
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::mask::{Mask, MaskRegister};
+use crate::xmm::Xmm;
+use crate::ymm::{by_halves, Ymm};
+
+/// Equal, ordered, non-signalling.
+pub const EQ_OQ: i32 = 0x00;
+/// Less-than, ordered, signalling.
+pub const LT_OS: i32 = 0x01;
+/// Less-than-or-equal, ordered, signalling.
+pub const LE_OS: i32 = 0x02;
+/// Unordered, non-signalling: either operand is NaN.
+pub const UNORD_Q: i32 = 0x03;
+/// Not-equal, unordered, non-signalling.
+pub const NEQ_UQ: i32 = 0x04;
+/// Not-less-than, unordered, signalling.
+pub const NLT_US: i32 = 0x05;
+/// Not-less-than-or-equal, unordered, signalling.
+pub const NLE_US: i32 = 0x06;
+/// Ordered, non-signalling: neither operand is NaN.
+pub const ORD_Q: i32 = 0x07;
+/// Equal, unordered, non-signalling.
+pub const EQ_UQ: i32 = 0x08;
+/// Not-greater-than-or-equal, unordered, signalling.
+pub const NGE_US: i32 = 0x09;
+/// Not-greater-than, unordered, signalling.
+pub const NGT_US: i32 = 0x0a;
+/// False, ordered, non-signalling.
+pub const FALSE_OQ: i32 = 0x0b;
+/// Not-equal, ordered, non-signalling.
+pub const NEQ_OQ: i32 = 0x0c;
+/// Greater-than-or-equal, ordered, signalling.
+pub const GE_OS: i32 = 0x0d;
+/// Greater-than, ordered, signalling.
+pub const GT_OS: i32 = 0x0e;
+/// True, unordered, non-signalling.
+pub const TRUE_UQ: i32 = 0x0f;
+/// Equal, ordered, signalling.
+pub const EQ_OS: i32 = 0x10;
+/// Less-than, ordered, non-signalling.
+pub const LT_OQ: i32 = 0x11;
+/// Less-than-or-equal, ordered, non-signalling.
+pub const LE_OQ: i32 = 0x12;
+/// Unordered, signalling.
+pub const UNORD_S: i32 = 0x13;
+/// Not-equal, unordered, signalling.
+pub const NEQ_US: i32 = 0x14;
+/// Not-less-than, unordered, non-signalling.
+pub const NLT_UQ: i32 = 0x15;
+/// Not-less-than-or-equal, unordered, non-signalling.
+pub const NLE_UQ: i32 = 0x16;
+/// Ordered, signalling.
+pub const ORD_S: i32 = 0x17;
+/// Equal, unordered, signalling.
+pub const EQ_US: i32 = 0x18;
+/// Not-greater-than-or-equal, unordered, non-signalling.
+pub const NGE_UQ: i32 = 0x19;
+/// Not-greater-than, unordered, non-signalling.
+pub const NGT_UQ: i32 = 0x1a;
+/// False, ordered, signalling.
+pub const FALSE_OS: i32 = 0x1b;
+/// Not-equal, ordered, signalling.
+pub const NEQ_OS: i32 = 0x1c;
+/// Greater-than-or-equal, ordered, non-signalling.
+pub const GE_OQ: i32 = 0x1d;
+/// Greater-than, ordered, non-signalling.
+pub const GT_OQ: i32 = 0x1e;
+/// True, unordered, signalling.
+pub const TRUE_US: i32 = 0x1f;
+
+/// Evaluates the predicate `p` on two values. The signalling bit, `0x10`,
+/// does not change the result.
+pub fn predicate<T: PartialOrd>(p: i32, x: T, y: T) -> bool {
+    let ord = x.partial_cmp(&y);
+    match p & 0x0f {
+        EQ_OQ => ord == Some(Equal),
+        LT_OS => ord == Some(Less),
+        LE_OS => matches!(ord, Some(Less | Equal)),
+        UNORD_Q => ord.is_none(),
+        NEQ_UQ => ord != Some(Equal),
+        NLT_US => ord != Some(Less),
+        NLE_US => !matches!(ord, Some(Less | Equal)),
+        ORD_Q => ord.is_some(),
+        EQ_UQ => matches!(ord, Some(Equal) | None),
+        NGE_US => !matches!(ord, Some(Greater | Equal)),
+        NGT_US => ord != Some(Greater),
+        FALSE_OQ => false,
+        NEQ_OQ => matches!(ord, Some(Less | Greater)),
+        GE_OS => matches!(ord, Some(Greater | Equal)),
+        GT_OS => ord == Some(Greater),
+        _ => true,
+    }
+}
+
+/// Lane types supported by the comparisons.
+pub trait CmpLane: Lane {
+    #[doc(hidden)]
+    fn eq_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn ne_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn lt_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn le_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn eq_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn ne_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn lt_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn le_ymm(a: Ymm, b: Ymm) -> Ymm;
+}
+
+/// Float lane types supported by [`Xmm::compare`] and [`Ymm::compare`].
+pub trait CmpFloatLane: CmpLane {
+    #[doc(hidden)]
+    fn compare_xmm<const P: i32>(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn compare_ymm<const P: i32>(a: Ymm, b: Ymm) -> Ymm;
+}
+
+/// Implements [`CmpLane`] for an integer type from its equality and
+/// greater-than kernels. `$xmm` is the module of x86 [`Xmm`] kernels.
+macro_rules! impl_cmp_int {
+    ($t:ty, $xmm:ident, $eq:ident, $gt:ident) => {
+        impl CmpLane for $t {
+            #[inline(always)]
+            fn eq_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => $xmm::$eq(a, b), _ => scalar::compare::<$t, _>(a, b, |x, y| x == y))
+            }
+
+            #[inline(always)]
+            fn ne_xmm(a: Xmm, b: Xmm) -> Xmm {
+                Self::eq_xmm(a, b).not()
+            }
+
+            #[inline(always)]
+            fn lt_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => $xmm::$gt(b, a), _ => scalar::compare::<$t, _>(a, b, |x, y| x < y))
+            }
+
+            #[inline(always)]
+            fn le_xmm(a: Xmm, b: Xmm) -> Xmm {
+                Self::lt_xmm(b, a).not()
+            }
+
+            #[inline(always)]
+            fn eq_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$eq(a, b) }, _ => by_halves(a, b, Self::eq_xmm))
+            }
+
+            #[inline(always)]
+            fn ne_ymm(a: Ymm, b: Ymm) -> Ymm {
+                Self::eq_ymm(a, b).not()
+            }
+
+            #[inline(always)]
+            fn lt_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$gt(b, a) }, _ => by_halves(a, b, Self::lt_xmm))
+            }
+
+            #[inline(always)]
+            fn le_ymm(a: Ymm, b: Ymm) -> Ymm {
+                Self::lt_ymm(b, a).not()
+            }
+        }
+    };
+}
+
+impl_cmp_int!(i8, sse2, eq_epi8, gt_epi8);
+impl_cmp_int!(i16, sse2, eq_epi16, gt_epi16);
+impl_cmp_int!(i32, sse2, eq_epi32, gt_epi32);
+impl_cmp_int!(i64, sse41, eq_epi64, gt_epi64);
+impl_cmp_int!(u8, sse2, eq_epi8, gt_epu8);
+impl_cmp_int!(u16, sse2, eq_epi16, gt_epu16);
+impl_cmp_int!(u32, sse2, eq_epi32, gt_epu32);
+impl_cmp_int!(u64, sse41, eq_epi64, gt_epu64);
+
+/// Implements [`CmpLane`] and [`CmpFloatLane`] for a float type with the
+/// kernels in the `$k` module (`ps` or `pd`) of each level.
+macro_rules! impl_cmp_float {
+    ($t:ty, $k:ident) => {
+        impl CmpLane for $t {
+            #[inline(always)]
+            fn eq_xmm(a: Xmm, b: Xmm) -> Xmm {
+                Self::compare_xmm::<EQ_OQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn ne_xmm(a: Xmm, b: Xmm) -> Xmm {
+                Self::compare_xmm::<NEQ_UQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn lt_xmm(a: Xmm, b: Xmm) -> Xmm {
+                Self::compare_xmm::<LT_OQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn le_xmm(a: Xmm, b: Xmm) -> Xmm {
+                Self::compare_xmm::<LE_OQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn eq_ymm(a: Ymm, b: Ymm) -> Ymm {
+                Self::compare_ymm::<EQ_OQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn ne_ymm(a: Ymm, b: Ymm) -> Ymm {
+                Self::compare_ymm::<NEQ_UQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn lt_ymm(a: Ymm, b: Ymm) -> Ymm {
+                Self::compare_ymm::<LT_OQ>(a, b)
+            }
+
+            #[inline(always)]
+            fn le_ymm(a: Ymm, b: Ymm) -> Ymm {
+                Self::compare_ymm::<LE_OQ>(a, b)
+            }
+        }
+
+        impl CmpFloatLane for $t {
+            #[inline(always)]
+            fn compare_xmm<const P: i32>(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$k::compare::<P>(a, b), _ => {
+                    scalar::compare::<$t, _>(a, b, |x, y| predicate(P, x, y))
+                })
+            }
+
+            #[inline(always)]
+            fn compare_ymm<const P: i32>(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx::$k::compare::<P>(a, b) }, _ => by_halves(a, b, Self::compare_xmm::<P>))
+            }
+        }
+    };
+}
+
+impl_cmp_float!(f32, ps);
+impl_cmp_float!(f64, pd);
+
+macro_rules! impl_cmp_methods {
+    ($reg:ident, $eq:ident, $ne:ident, $lt:ident, $le:ident, $compare:ident) => {
+        impl $reg {
+            /// Lanes where `self == rhs`.
+            #[inline(always)]
+            pub fn cmp_eq<T: CmpLane>(self, rhs: $reg) -> Mask<$reg, T> {
+                Mask::from_register(T::$eq(self, rhs))
+            }
+
+            /// Lanes where `self != rhs`, including NaNs.
+            #[inline(always)]
+            pub fn cmp_ne<T: CmpLane>(self, rhs: $reg) -> Mask<$reg, T> {
+                Mask::from_register(T::$ne(self, rhs))
+            }
+
+            /// Lanes where `self < rhs`.
+            #[inline(always)]
+            pub fn cmp_lt<T: CmpLane>(self, rhs: $reg) -> Mask<$reg, T> {
+                Mask::from_register(T::$lt(self, rhs))
+            }
+
+            /// Lanes where `self <= rhs`.
+            #[inline(always)]
+            pub fn cmp_le<T: CmpLane>(self, rhs: $reg) -> Mask<$reg, T> {
+                Mask::from_register(T::$le(self, rhs))
+            }
+
+            /// Lanes where `self > rhs`.
+            #[inline(always)]
+            pub fn cmp_gt<T: CmpLane>(self, rhs: $reg) -> Mask<$reg, T> {
+                Mask::from_register(T::$lt(rhs, self))
+            }
+
+            /// Lanes where `self >= rhs`.
+            #[inline(always)]
+            pub fn cmp_ge<T: CmpLane>(self, rhs: $reg) -> Mask<$reg, T> {
+                Mask::from_register(T::$le(rhs, self))
+            }
+
+            /// Lanes where the predicate `P`, one of the constants of
+            /// [`cmp`](crate::cmp), holds for `self` and `rhs`.
+            #[inline(always)]
+            pub fn compare<T: CmpFloatLane, const P: i32>(self, rhs: $reg) -> Mask<$reg, T> {
+                const { assert!(0 <= P && P < 32, "not a _CMP_* predicate") };
+                Mask::from_register(T::$compare::<P>(self, rhs))
+            }
+        }
+    };
+}
+
+impl_cmp_methods!(Xmm, eq_xmm, ne_xmm, lt_xmm, le_xmm, compare_xmm);
+impl_cmp_methods!(Ymm, eq_ymm, ne_ymm, lt_ymm, le_ymm, compare_ymm);
+
+/// Portable kernels over any register.
+mod scalar {
+    use crate::chunks::Register;
+    use crate::lane::Lane;
+
+    /// Sets the lanes where `f` holds to all ones.
+    #[inline]
+    pub fn compare<T: Lane, R: Register>(a: R, b: R, f: impl Fn(T, T) -> bool) -> R {
+        let mut out = R::default();
+        let size = std::mem::size_of::<T>();
+        let lanes = a.lanes::<T>().iter().zip(b.lanes::<T>());
+        for (lane, (&x, &y)) in out.lanes_mut::<u8>().chunks_exact_mut(size).zip(lanes) {
+            lane.fill(if f(x, y) { 0xFF } else { 0 });
+        }
+        out
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic(a.into(), b.into()) }.into()
+            }
+        };
+    }
+
+    /// Unsigned greater-than as signed greater-than of the operands with
+    /// their sign bits flipped.
+    macro_rules! unsigned {
+        ($name:ident, $gt:ident, $sign:expr) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe {
+                    let sign = $sign;
+                    $gt(_mm_xor_si128(a.into(), sign).into(), _mm_xor_si128(b.into(), sign).into())
+                }
+            }
+        };
+    }
+
+    binary!(eq_epi8, _mm_cmpeq_epi8);
+    binary!(eq_epi16, _mm_cmpeq_epi16);
+    binary!(eq_epi32, _mm_cmpeq_epi32);
+    binary!(gt_epi8, _mm_cmpgt_epi8);
+    binary!(gt_epi16, _mm_cmpgt_epi16);
+    binary!(gt_epi32, _mm_cmpgt_epi32);
+    unsigned!(gt_epu8, gt_epi8, _mm_set1_epi8(i8::MIN));
+    unsigned!(gt_epu16, gt_epi16, _mm_set1_epi16(i16::MIN));
+    unsigned!(gt_epu32, gt_epi32, _mm_set1_epi32(i32::MIN));
+    unsigned!(gt_epu64, gt_epi64, _mm_set1_epi64x(i64::MIN));
+
+    /// Both 32-bit halves are equal.
+    #[inline(always)]
+    pub fn eq_epi64(a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let eq = _mm_cmpeq_epi32(a.into(), b.into());
+            _mm_and_si128(eq, _mm_shuffle_epi32::<0b10_11_00_01>(eq)).into()
+        }
+    }
+
+    /// The high halves decide unless they are equal, in which case `b - a`
+    /// borrows into its high half exactly when the low halves have
+    /// `a > b` unsigned. The high half of each lane is then copied to the
+    /// low one.
+    #[inline(always)]
+    pub fn gt_epi64(a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let (a, b): (__m128i, __m128i) = (a.into(), b.into());
+            let borrow = _mm_and_si128(_mm_cmpeq_epi32(a, b), _mm_sub_epi64(b, a));
+            let gt = _mm_or_si128(borrow, _mm_cmpgt_epi32(a, b));
+            _mm_shuffle_epi32::<0b11_11_01_01>(gt).into()
+        }
+    }
+
+    /// `cmpps` only has the first eight predicates. The others are their
+    /// negations with the operands swapped, or combine them.
+    macro_rules! float_predicates {
+        ($vec:ident, $eq:ident, $lt:ident, $le:ident, $unord:ident, $neq:ident, $nlt:ident, $nle:ident, $ord:ident,
+         $ge:ident, $gt:ident, $nge:ident, $ngt:ident, $and:ident, $or:ident, $zero:ident) => {
+            #[inline(always)]
+            pub fn compare<const P: i32>(a: Xmm, b: Xmm) -> Xmm {
+                unsafe {
+                    let (a, b) = ($vec::from(a), $vec::from(b));
+                    let r = match P & 0x0f {
+                        0x00 => $eq(a, b),
+                        0x01 => $lt(a, b),
+                        0x02 => $le(a, b),
+                        0x03 => $unord(a, b),
+                        0x04 => $neq(a, b),
+                        0x05 => $nlt(a, b),
+                        0x06 => $nle(a, b),
+                        0x07 => $ord(a, b),
+                        0x08 => $or($eq(a, b), $unord(a, b)),
+                        0x09 => $nge(a, b),
+                        0x0a => $ngt(a, b),
+                        0x0b => $zero(),
+                        0x0c => $and($neq(a, b), $ord(a, b)),
+                        0x0d => $ge(a, b),
+                        0x0e => $gt(a, b),
+                        _ => return Xmm::splat_u8(0xFF),
+                    };
+                    r.into()
+                }
+            }
+        };
+    }
+
+    pub mod ps {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        float_predicates!(
+            __m128, _mm_cmpeq_ps, _mm_cmplt_ps, _mm_cmple_ps, _mm_cmpunord_ps, _mm_cmpneq_ps, _mm_cmpnlt_ps,
+            _mm_cmpnle_ps, _mm_cmpord_ps, _mm_cmpge_ps, _mm_cmpgt_ps, _mm_cmpnge_ps, _mm_cmpngt_ps, _mm_and_ps,
+            _mm_or_ps, _mm_setzero_ps
+        );
+    }
+
+    pub mod pd {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        float_predicates!(
+            __m128d, _mm_cmpeq_pd, _mm_cmplt_pd, _mm_cmple_pd, _mm_cmpunord_pd, _mm_cmpneq_pd, _mm_cmpnlt_pd,
+            _mm_cmpnle_pd, _mm_cmpord_pd, _mm_cmpge_pd, _mm_cmpgt_pd, _mm_cmpnge_pd, _mm_cmpngt_pd, _mm_and_pd,
+            _mm_or_pd, _mm_setzero_pd
+        );
+    }
+}
+
+/// SSE4.1 kernels over [`Xmm`] for 64-bit lanes, falling back to [`sse2`]
+/// when the dispatch level is lower. Kernels without an SSE4.1 form are
+/// re-exported from [`sse2`].
+#[cfg(simd_x86)]
+mod sse41 {
+    pub use super::sse2::*;
+
+    use crate::dispatch::has_sse41;
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    #[inline(always)]
+    pub fn eq_epi64(a: Xmm, b: Xmm) -> Xmm {
+        if has_sse41() {
+            unsafe { native_eq_epi64(a, b) }
+        } else {
+            super::sse2::eq_epi64(a, b)
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn native_eq_epi64(a: Xmm, b: Xmm) -> Xmm {
+        _mm_cmpeq_epi64(a.into(), b.into()).into()
+    }
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic(a.into(), b.into()).into()
+            }
+        };
+    }
+
+    macro_rules! unsigned {
+        ($name:ident, $gt:ident, $sign:expr) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                let sign = $sign;
+                $gt(_mm256_xor_si256(a.into(), sign), _mm256_xor_si256(b.into(), sign)).into()
+            }
+        };
+    }
+
+    binary!(eq_epi8, _mm256_cmpeq_epi8);
+    binary!(eq_epi16, _mm256_cmpeq_epi16);
+    binary!(eq_epi32, _mm256_cmpeq_epi32);
+    binary!(eq_epi64, _mm256_cmpeq_epi64);
+    binary!(gt_epi8, _mm256_cmpgt_epi8);
+    binary!(gt_epi16, _mm256_cmpgt_epi16);
+    binary!(gt_epi32, _mm256_cmpgt_epi32);
+    binary!(gt_epi64, _mm256_cmpgt_epi64);
+    unsigned!(gt_epu8, _mm256_cmpgt_epi8, _mm256_set1_epi8(i8::MIN));
+    unsigned!(gt_epu16, _mm256_cmpgt_epi16, _mm256_set1_epi16(i16::MIN));
+    unsigned!(gt_epu32, _mm256_cmpgt_epi32, _mm256_set1_epi32(i32::MIN));
+    unsigned!(gt_epu64, _mm256_cmpgt_epi64, _mm256_set1_epi64x(i64::MIN));
+}
+
+/// AVX float kernels over [`Ymm`]. AVX is only enabled with
+/// AVX2, so callers must check [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx {
+    pub mod ps {
+        use crate::ymm::Ymm;
+        use std::arch::x86_64::*;
+
+        #[inline]
+        #[target_feature(enable = "avx")]
+        pub unsafe fn compare<const P: i32>(a: Ymm, b: Ymm) -> Ymm {
+            _mm256_cmp_ps::<P>(a.into(), b.into()).into()
+        }
+    }
+
+    pub mod pd {
+        use crate::ymm::Ymm;
+        use std::arch::x86_64::*;
+
+        #[inline]
+        #[target_feature(enable = "avx")]
+        pub unsafe fn compare<const P: i32>(a: Ymm, b: Ymm) -> Ymm {
+            _mm256_cmp_pd::<P>(a.into(), b.into()).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::distributions::{Distribution, Standard};
+    use rand::Rng;
+
+    /// Random lanes, often equal to the other operand's or at the extremes
+    /// where sign-flip tricks go wrong.
+    fn operand<T: Lane>(rng: &mut impl Rng, other: T, min: T, max: T) -> T
+    where
+        Standard: Distribution<T>,
+    {
+        match rng.gen_range(0..6) {
+            0 => other,
+            1 => min,
+            2 => max,
+            _ => rng.gen(),
+        }
+    }
+
+    macro_rules! check_int {
+        ($reg:ident, $t:ty) => {{
+            let mut rng = rand::thread_rng();
+            for _ in 0..256 {
+                let a = $reg::from_fn::<$t>(|_| operand(&mut rng, 0, <$t>::MIN, <$t>::MAX));
+                let b = $reg::from_fn::<$t>(|i| operand(&mut rng, a.lanes::<$t>()[i], <$t>::MIN, <$t>::MAX));
+                let (x, y) = (a.lanes::<$t>(), b.lanes::<$t>());
+                let expect = |f: fn(&$t, &$t) -> bool| Mask::<$reg, $t>::from_fn(|i| f(&x[i], &y[i]));
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        let at = format!("{} {} at {}", stringify!($reg), stringify!($t), isa);
+                        assert_eq!(a.cmp_eq::<$t>(b), expect(<$t>::eq), "eq {}", at);
+                        assert_eq!(a.cmp_ne::<$t>(b), expect(<$t>::ne), "ne {}", at);
+                        assert_eq!(a.cmp_lt::<$t>(b), expect(<$t>::lt), "lt {}", at);
+                        assert_eq!(a.cmp_le::<$t>(b), expect(<$t>::le), "le {}", at);
+                        assert_eq!(a.cmp_gt::<$t>(b), expect(<$t>::gt), "gt {}", at);
+                        assert_eq!(a.cmp_ge::<$t>(b), expect(<$t>::ge), "ge {}", at);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xmm_int_compares() {
+        check_int!(Xmm, i8);
+        check_int!(Xmm, i16);
+        check_int!(Xmm, i32);
+        check_int!(Xmm, i64);
+        check_int!(Xmm, u8);
+        check_int!(Xmm, u16);
+        check_int!(Xmm, u32);
+        check_int!(Xmm, u64);
+    }
+
+    #[test]
+    fn test_ymm_int_compares() {
+        check_int!(Ymm, i8);
+        check_int!(Ymm, i16);
+        check_int!(Ymm, i32);
+        check_int!(Ymm, i64);
+        check_int!(Ymm, u8);
+        check_int!(Ymm, u16);
+        check_int!(Ymm, u32);
+        check_int!(Ymm, u64);
+    }
+
+    macro_rules! check_predicates {
+        ($a:expr, $b:expr, $t:ty, $expect:expr, $isa:expr; $($p:ident),*) => {
+            $(assert_eq!($a.compare::<$t, $p>($b), $expect($p), "{} {} at {}", stringify!($p), stringify!($t), $isa);)*
+        };
+    }
+
+    /// Checks every predicate against [`predicate`], which is checked against
+    /// the operators by the convenience methods.
+    macro_rules! check_float {
+        ($reg:ident, $t:ty) => {{
+            let special = [0.0, -0.0, 1.0, -1.0, <$t>::INFINITY, <$t>::NEG_INFINITY, <$t>::NAN];
+            let mut rng = rand::thread_rng();
+            for _ in 0..64 {
+                let a = $reg::from_fn::<$t>(|_| special[rng.gen_range(0..special.len())]);
+                let b = $reg::from_fn::<$t>(|_| special[rng.gen_range(0..special.len())]);
+                let (x, y) = (a.lanes::<$t>(), b.lanes::<$t>());
+                let expect = |p: i32| Mask::<$reg, $t>::from_fn(|i| predicate(p, x[i], y[i]));
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        check_predicates!(
+                            a, b, $t, expect, isa;
+                            EQ_OQ, LT_OS, LE_OS, UNORD_Q, NEQ_UQ, NLT_US, NLE_US, ORD_Q, EQ_UQ, NGE_US, NGT_US, FALSE_OQ,
+                            NEQ_OQ, GE_OS, GT_OS, TRUE_UQ, EQ_OS, LT_OQ, LE_OQ, UNORD_S, NEQ_US, NLT_UQ, NLE_UQ, ORD_S,
+                            EQ_US, NGE_UQ, NGT_UQ, FALSE_OS, NEQ_OS, GE_OQ, GT_OQ, TRUE_US
+                        );
+                        let ops = |f: fn(&$t, &$t) -> bool| Mask::<$reg, $t>::from_fn(|i| f(&x[i], &y[i]));
+                        assert_eq!(a.cmp_eq::<$t>(b), ops(<$t>::eq), "eq at {}", isa);
+                        assert_eq!(a.cmp_ne::<$t>(b), ops(<$t>::ne), "ne at {}", isa);
+                        assert_eq!(a.cmp_lt::<$t>(b), ops(<$t>::lt), "lt at {}", isa);
+                        assert_eq!(a.cmp_le::<$t>(b), ops(<$t>::le), "le at {}", isa);
+                        assert_eq!(a.cmp_gt::<$t>(b), ops(<$t>::gt), "gt at {}", isa);
+                        assert_eq!(a.cmp_ge::<$t>(b), ops(<$t>::ge), "ge at {}", isa);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_float_compares() {
+        check_float!(Xmm, f32);
+        check_float!(Xmm, f64);
+        check_float!(Ymm, f32);
+        check_float!(Ymm, f64);
+    }
+
+    #[test]
+    fn test_predicate() {
+        assert!(predicate(NEQ_UQ, f32::NAN, 1.0) && !predicate(NEQ_OQ, f32::NAN, 1.0));
+        assert!(predicate(EQ_UQ, f64::NAN, f64::NAN) && !predicate(EQ_OQ, f64::NAN, f64::NAN));
+        assert!(predicate(NLT_UQ, 2, 1) && predicate(GE_OQ, -0.0, 0.0));
+    }
+}
@@ -0,0 +1,240 @@
+//! Runtime selection of the instruction set used by the kernels.
+//!
+//! The level is detected once with `is_x86_feature_detected!` and cached. It
+//! can be lowered for the whole process with the `SIMD_FORCE_ISA` environment
+//! variable, read on first use, and further for the current thread with
+//! [`with_isa`], which never goes above the process level:
+//!
+//! ```sh
+//! SIMD_FORCE_ISA=sse2 cargo test
+//! ```
+//!
+//! Accepted names are `scalar`, `sse2`, `sse4.1`, `avx2` and `avx512`. A level
+//! the CPU does not support is clamped to the detected one, since running its
+//! kernels would be undefined behaviour.
+//!
+//! Every kernel picks the best implementation at or below [`level`]. Kernels
+//! without a dedicated version for a level use the next lower one.
+//!
+//! At [`Isa::Scalar`] the portable kernels run instead of the x86 ones. They
+//! are the only kernels compiled on other targets and with the `scalar` cargo
+//! feature, where [`Isa::detect`] always returns [`Isa::Scalar`].
+
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Instruction set levels, ordered from least to most capable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Isa {
+    Scalar,
+    Sse2,
+    Sse41,
+    Avx2,
+    /// AVX-512F on top of AVX2.
+    Avx512,
+}
+
+impl Isa {
+    /// All levels, from least to most capable.
+    pub const ALL: [Isa; 5] = [Isa::Scalar, Isa::Sse2, Isa::Sse41, Isa::Avx2, Isa::Avx512];
+
+    /// Returns the most capable level the running CPU supports and the crate
+    /// has kernels for.
+    pub fn detect() -> Isa {
+        #[cfg(simd_x86)]
+        {
+            if is_x86_feature_detected!("avx512f") && is_x86_feature_detected!("avx2") {
+                Isa::Avx512
+            } else if is_x86_feature_detected!("avx2") {
+                Isa::Avx2
+            } else if is_x86_feature_detected!("sse4.1") {
+                Isa::Sse41
+            } else {
+                Isa::Sse2
+            }
+        }
+        #[cfg(not(simd_x86))]
+        {
+            Isa::Scalar
+        }
+    }
+
+    /// Returns true if the running CPU supports this level and
+    /// `SIMD_FORCE_ISA` does not force a lower one.
+    pub fn is_supported(self) -> bool {
+        self <= process_level()
+    }
+
+    /// Returns the supported levels, from least to most capable.
+    pub fn supported() -> impl Iterator<Item = Isa> {
+        Isa::ALL.into_iter().filter(|isa| isa.is_supported())
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Isa::Scalar => "scalar",
+            Isa::Sse2 => "sse2",
+            Isa::Sse41 => "sse4.1",
+            Isa::Avx2 => "avx2",
+            Isa::Avx512 => "avx512",
+        }
+    }
+}
+
+impl fmt::Display for Isa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned when parsing an unknown ISA name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseIsaError(String);
+
+impl fmt::Display for ParseIsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown ISA `{}`, expected one of scalar, sse2, sse4.1, avx2, avx512", self.0)
+    }
+}
+
+impl std::error::Error for ParseIsaError {}
+
+impl FromStr for Isa {
+    type Err = ParseIsaError;
+
+    /// Parses a level name, ignoring case, dots and dashes: `SSE4.1`, `sse41`
+    /// and `avx-512` are all accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s.trim().chars().filter(|c| !matches!(c, '.' | '-' | '_')).collect();
+        match name.to_ascii_lowercase().as_str() {
+            "scalar" => Ok(Isa::Scalar),
+            "sse2" => Ok(Isa::Sse2),
+            "sse41" => Ok(Isa::Sse41),
+            "avx2" => Ok(Isa::Avx2),
+            "avx512" | "avx512f" => Ok(Isa::Avx512),
+            _ => Err(ParseIsaError(s.to_string())),
+        }
+    }
+}
+
+/// Name of the environment variable that forces a level.
+pub const FORCE_ISA_VAR: &str = "SIMD_FORCE_ISA";
+
+fn detected() -> Isa {
+    static DETECTED: OnceLock<Isa> = OnceLock::new();
+    *DETECTED.get_or_init(Isa::detect)
+}
+
+fn process_level() -> Isa {
+    static LEVEL: OnceLock<Isa> = OnceLock::new();
+    *LEVEL.get_or_init(|| {
+        let detected = detected();
+        let Ok(forced) = std::env::var(FORCE_ISA_VAR) else {
+            return detected;
+        };
+        match forced.parse::<Isa>() {
+            Ok(isa) if isa <= detected => isa,
+            Ok(isa) => {
+                eprintln!("{}={}: not supported by this CPU, using {}", FORCE_ISA_VAR, isa, detected);
+                detected
+            }
+            Err(e) => {
+                eprintln!("{}: {}, using {}", FORCE_ISA_VAR, e, detected);
+                detected
+            }
+        }
+    })
+}
+
+thread_local! {
+    static OVERRIDE: Cell<Option<Isa>> = const { Cell::new(None) };
+}
+
+/// Returns the level kernels on this thread dispatch to.
+#[inline]
+pub fn level() -> Isa {
+    OVERRIDE.with(Cell::get).unwrap_or_else(process_level)
+}
+
+/// Runs `f` with the level of the current thread set to `isa`, clamped to the
+/// process level, and restores the previous level afterwards, even if `f`
+/// panics.
+///
+/// ```
+/// use simd::dispatch::{level, with_isa, Isa};
+///
+/// with_isa(Isa::Sse2, || assert!(level() <= Isa::Sse2));
+/// ```
+pub fn with_isa<R>(isa: Isa, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Isa>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OVERRIDE.with(|o| o.set(self.0));
+        }
+    }
+
+    let _restore = Restore(OVERRIDE.with(|o| o.replace(Some(isa.min(process_level())))));
+    f()
+}
+
+/// Returns true if kernels may use SSE4.1.
+#[inline]
+pub fn has_sse41() -> bool {
+    level() >= Isa::Sse41
+}
+
+/// Evaluates the first arm whose level is at most [`level`], or else the `_`
+/// arm, which must be portable. Without the x86 kernels only the `_` arm is
+/// compiled.
+///
+/// ```ignore
+/// select!(Avx2 => unsafe { avx2::add(a, b) }, Sse2 => sse2::add(a, b), _ => scalar::add(a, b))
+/// ```
+macro_rules! select {
+    ($($isa:ident => $x86:expr,)+ _ => $portable:expr $(,)?) => {{
+        #[cfg(simd_x86)]
+        {
+            let level = $crate::dispatch::level();
+            $(if level >= $crate::dispatch::Isa::$isa { $x86 } else)+ { $portable }
+        }
+        #[cfg(not(simd_x86))]
+        {
+            $portable
+        }
+    }};
+}
+
+pub(crate) use select;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_isa() {
+        for isa in Isa::ALL {
+            assert_eq!(isa.name().parse(), Ok(isa));
+        }
+        assert_eq!("SSE41".parse(), Ok(Isa::Sse41));
+        assert_eq!("avx-512".parse(), Ok(Isa::Avx512));
+        let err = "neon".parse::<Isa>().unwrap_err();
+        assert!(err.to_string().contains("unknown ISA `neon`"));
+    }
+
+    #[test]
+    fn test_with_isa_overrides_and_restores() {
+        let outer = level();
+        with_isa(Isa::Scalar, || {
+            assert_eq!(level(), Isa::Scalar);
+            with_isa(Isa::Avx512, || assert_eq!(level(), process_level()));
+            assert_eq!(level(), Isa::Scalar);
+        });
+        assert_eq!(level(), outer);
+
+        let _ = std::panic::catch_unwind(|| with_isa(Isa::Sse2, || panic!()));
+        assert_eq!(level(), outer);
+    }
+}
@@ -0,0 +1,329 @@
+//! Reading and replacing one lane whose index is known at compile time.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//! use simd::ymm::Ymm;
+//!
+//! let a = Xmm::from_i32([10, -20, 30, -40]);
+//! assert_eq!(a.extract::<i32, 1>(), -20);
+//! assert_eq!(a.extract::<u16, 2>(), 0xFFEC);
+//! assert_eq!(a.insert::<i32, 3>(7), Xmm::from_i32([10, -20, 30, 7]));
+//!
+//! let b = Ymm::from_fn::<f64>(|i| i as f64);
+//! assert_eq!(b.extract::<f64, 3>(), 3.0);
+//! assert_eq!(b.insert::<u8, 31>(0xAB).lane_u8(31), 0xAB);
+//! ```
+//!
+//! The index is checked when the method is instantiated, so it cannot be out
+//! of range:
+//!
+//! ```compile_fail
+//! simd::xmm::Xmm::default().extract::<u32, 4>();
+//! ```
+//!
+//! Unlike the [byte shifts](crate::shift#byte-shifts), the index of a
+//! [`Ymm`] lane runs across both 128-bit halves: lane `I` is element `I` of
+//! [`Ymm::lanes`]. The AVX2 forms pick the half and use the 128-bit
+//! instruction on it.
+//!
+//! SSE4.1 has `pextrb`, `pextrd` and `pextrq` and the matching inserts.
+//! SSE2 only has them for 16-bit lanes, and otherwise goes through the lane
+//! views of the register, as does a [`Ymm`] without AVX2. Floats go through the
+//! integer lanes of their width.
+
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::xmm::Xmm;
+use crate::ymm::Ymm;
+
+/// Lane types that can be extracted and inserted by index.
+pub trait ExtractLane: Lane {
+    #[doc(hidden)]
+    fn extract_xmm<const I: i32>(a: Xmm) -> Self;
+    #[doc(hidden)]
+    fn insert_xmm<const I: i32>(a: Xmm, v: Self) -> Xmm;
+    #[doc(hidden)]
+    fn extract_ymm<const I: i32>(a: Ymm) -> Self;
+    #[doc(hidden)]
+    fn insert_ymm<const I: i32>(a: Ymm, v: Self) -> Ymm;
+}
+
+/// Implements [`ExtractLane`] with the kernels of the integer type `$int` of
+/// the same width, converting from it with `$from` and to it with `$to`.
+macro_rules! impl_extract_lane {
+    ($($t:ty: $int:ty, $x:ident => $from:expr, $v:ident => $to:expr;)*) => {$(
+        impl ExtractLane for $t {
+            #[inline(always)]
+            fn extract_xmm<const I: i32>(a: Xmm) -> Self {
+                let $x = <$int as Kernels>::extract_xmm::<I>(a);
+                $from
+            }
+
+            #[inline(always)]
+            fn insert_xmm<const I: i32>(a: Xmm, $v: Self) -> Xmm {
+                <$int as Kernels>::insert_xmm::<I>(a, $to)
+            }
+
+            #[inline(always)]
+            fn extract_ymm<const I: i32>(a: Ymm) -> Self {
+                let $x = <$int as Kernels>::extract_ymm::<I>(a);
+                $from
+            }
+
+            #[inline(always)]
+            fn insert_ymm<const I: i32>(a: Ymm, $v: Self) -> Ymm {
+                <$int as Kernels>::insert_ymm::<I>(a, $to)
+            }
+        }
+    )*};
+}
+
+impl_extract_lane! {
+    i8: i8, x => x, v => v;
+    i16: i16, x => x, v => v;
+    i32: i32, x => x, v => v;
+    i64: i64, x => x, v => v;
+    u8: i8, x => x as u8, v => v as i8;
+    u16: i16, x => x as u16, v => v as i16;
+    u32: i32, x => x as u32, v => v as i32;
+    u64: i64, x => x as u64, v => v as i64;
+    f32: i32, x => f32::from_bits(x as u32), v => v.to_bits() as i32;
+    f64: i64, x => f64::from_bits(x as u64), v => v.to_bits() as i64;
+}
+
+/// The dispatched kernels of the signed integer lanes.
+trait Kernels: Lane {
+    fn extract_xmm<const I: i32>(a: Xmm) -> Self;
+    fn insert_xmm<const I: i32>(a: Xmm, v: Self) -> Xmm;
+    fn extract_ymm<const I: i32>(a: Ymm) -> Self;
+    fn insert_ymm<const I: i32>(a: Ymm, v: Self) -> Ymm;
+}
+
+/// Implements [`Kernels`] for `$t` with the SSE4.1 and AVX2 kernels named
+/// after the lane width.
+macro_rules! impl_kernels {
+    ($($t:ty: $extract:ident, $insert:ident;)*) => {$(
+        impl Kernels for $t {
+            #[inline(always)]
+            fn extract_xmm<const I: i32>(a: Xmm) -> Self {
+                select!(Sse41 => unsafe { sse41::$extract::<I>(a) }, _ => scalar::extract(a, I))
+            }
+
+            #[inline(always)]
+            fn insert_xmm<const I: i32>(a: Xmm, v: Self) -> Xmm {
+                select!(Sse41 => unsafe { sse41::$insert::<I>(a, v) }, _ => scalar::insert(a, I, v))
+            }
+
+            impl_kernels!(ymm $extract, $insert);
+        }
+    )*};
+    (ymm $extract:ident, $insert:ident) => {
+        #[inline(always)]
+        fn extract_ymm<const I: i32>(a: Ymm) -> Self {
+            select!(Avx2 => unsafe { avx2::$extract::<I>(a) }, _ => scalar::extract(a, I))
+        }
+
+        #[inline(always)]
+        fn insert_ymm<const I: i32>(a: Ymm, v: Self) -> Ymm {
+            select!(Avx2 => unsafe { avx2::$insert::<I>(a, v) }, _ => scalar::insert(a, I, v))
+        }
+    };
+}
+
+impl_kernels! {
+    i8: extract_epi8, insert_epi8;
+    i32: extract_epi32, insert_epi32;
+    i64: extract_epi64, insert_epi64;
+}
+
+/// 16-bit lanes have `pextrw` and `pinsrw` from SSE2.
+impl Kernels for i16 {
+    #[inline(always)]
+    fn extract_xmm<const I: i32>(a: Xmm) -> Self {
+        select!(Sse2 => sse2::extract_epi16::<I>(a), _ => scalar::extract(a, I))
+    }
+
+    #[inline(always)]
+    fn insert_xmm<const I: i32>(a: Xmm, v: Self) -> Xmm {
+        select!(Sse2 => sse2::insert_epi16::<I>(a, v), _ => scalar::insert(a, I, v))
+    }
+
+    impl_kernels!(ymm extract_epi16, insert_epi16);
+}
+
+macro_rules! impl_extract_methods {
+    ($reg:ident, $extract:ident, $insert:ident) => {
+        impl $reg {
+            /// Returns the `T` lane `I`.
+            #[inline(always)]
+            pub fn extract<T: ExtractLane, const I: i32>(self) -> T {
+                const { assert!(0 <= I && (I as usize) < std::mem::size_of::<$reg>() / std::mem::size_of::<T>(), "lane index out of range") };
+                T::$extract::<I>(self)
+            }
+
+            /// Returns the register with its `T` lane `I` replaced by `v`.
+            #[inline(always)]
+            pub fn insert<T: ExtractLane, const I: i32>(self, v: T) -> $reg {
+                const { assert!(0 <= I && (I as usize) < std::mem::size_of::<$reg>() / std::mem::size_of::<T>(), "lane index out of range") };
+                T::$insert::<I>(self, v)
+            }
+        }
+    };
+}
+
+impl_extract_methods!(Xmm, extract_xmm, insert_xmm);
+impl_extract_methods!(Ymm, extract_ymm, insert_ymm);
+
+/// Portable kernels over any register.
+mod scalar {
+    use crate::chunks::Register;
+    use crate::lane::Lane;
+
+    #[inline]
+    pub fn extract<T: Lane, R: Register>(a: R, i: i32) -> T {
+        a.lanes::<T>()[i as usize]
+    }
+
+    #[inline]
+    pub fn insert<T: Lane, R: Register>(mut a: R, i: i32, v: T) -> R {
+        a.lanes_mut::<T>()[i as usize] = v;
+        a
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    #[inline(always)]
+    pub fn extract_epi16<const I: i32>(a: Xmm) -> i16 {
+        unsafe { _mm_extract_epi16::<I>(a.into()) as i16 }
+    }
+
+    #[inline(always)]
+    pub fn insert_epi16<const I: i32>(a: Xmm, v: i16) -> Xmm {
+        unsafe { _mm_insert_epi16::<I>(a.into(), v as i32) }.into()
+    }
+}
+
+/// SSE4.1 kernels over [`Xmm`]. Callers must check
+/// [`has_sse41`](crate::dispatch::has_sse41) first.
+#[cfg(simd_x86)]
+mod sse41 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! lanes {
+        ($($t:ty: $extract:ident => $extract_intrinsic:ident, $insert:ident => $insert_intrinsic:ident;)*) => {$(
+            #[inline]
+            #[target_feature(enable = "sse4.1")]
+            pub unsafe fn $extract<const I: i32>(a: Xmm) -> $t {
+                $extract_intrinsic::<I>(a.into()) as $t
+            }
+
+            #[inline]
+            #[target_feature(enable = "sse4.1")]
+            pub unsafe fn $insert<const I: i32>(a: Xmm, v: $t) -> Xmm {
+                $insert_intrinsic::<I>(a.into(), v as _).into()
+            }
+        )*};
+    }
+
+    lanes! {
+        i8: extract_epi8 => _mm_extract_epi8, insert_epi8 => _mm_insert_epi8;
+        i32: extract_epi32 => _mm_extract_epi32, insert_epi32 => _mm_insert_epi32;
+        i64: extract_epi64 => _mm_extract_epi64, insert_epi64 => _mm_insert_epi64;
+    }
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! lanes {
+        ($($t:ty: $extract:ident => $extract_intrinsic:ident, $insert:ident => $insert_intrinsic:ident;)*) => {$(
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $extract<const I: i32>(a: Ymm) -> $t {
+                $extract_intrinsic::<I>(a.into()) as $t
+            }
+
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $insert<const I: i32>(a: Ymm, v: $t) -> Ymm {
+                $insert_intrinsic::<I>(a.into(), v).into()
+            }
+        )*};
+    }
+
+    lanes! {
+        i8: extract_epi8 => _mm256_extract_epi8, insert_epi8 => _mm256_insert_epi8;
+        i16: extract_epi16 => _mm256_extract_epi16, insert_epi16 => _mm256_insert_epi16;
+        i32: extract_epi32 => _mm256_extract_epi32, insert_epi32 => _mm256_insert_epi32;
+        i64: extract_epi64 => _mm256_extract_epi64, insert_epi64 => _mm256_insert_epi64;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    macro_rules! check_indices {
+        ($reg:ident, $a:expr, $t:ty, $at:expr; $($i:literal),*) => {$(
+            let v: $t = rand::thread_rng().gen();
+            let mut expect = $a;
+            expect.lanes_mut::<$t>()[$i] = v;
+            assert_eq!($a.extract::<$t, $i>().to_ne_bytes(), $a.lanes::<$t>()[$i].to_ne_bytes(), "extract {} {}", $i, $at);
+            assert_eq!($a.insert::<$t, $i>(v), expect, "insert {} {}", $i, $at);
+        )*};
+    }
+
+    macro_rules! check_type {
+        ($t:ty; $($xmm:literal),*; $($ymm:literal),*) => {{
+            let mut rng = rand::thread_rng();
+            let a = Xmm::from_fn::<u8>(|_| rng.gen());
+            let b = Ymm::from_fn::<u8>(|_| rng.gen());
+            for isa in Isa::supported() {
+                with_isa(isa, || {
+                    let at = format!("{} at {}", stringify!($t), isa);
+                    check_indices!(Xmm, a, $t, at; $($xmm),*);
+                    check_indices!(Ymm, b, $t, at; $($ymm),*);
+                });
+            }
+        }};
+    }
+
+    #[test]
+    fn test_extract_insert() {
+        check_type!(i8; 0, 1, 7, 8, 15; 0, 1, 15, 16, 17, 31);
+        check_type!(u8; 0, 5, 15; 0, 16, 31);
+        check_type!(i16; 0, 1, 4, 7; 0, 7, 8, 15);
+        check_type!(u16; 0, 3, 7; 0, 8, 15);
+        check_type!(i32; 0, 1, 2, 3; 0, 3, 4, 7);
+        check_type!(u32; 0, 3; 0, 4, 7);
+        check_type!(f32; 0, 1, 2, 3; 0, 3, 4, 7);
+        check_type!(i64; 0, 1; 0, 1, 2, 3);
+        check_type!(u64; 0, 1; 0, 2, 3);
+        check_type!(f64; 0, 1; 0, 1, 2, 3);
+    }
+
+    #[test]
+    fn test_ymm_indices_cross_halves() {
+        let a = Ymm::from_i32([0, 1, 2, 3, 4, 5, 6, 7]);
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                assert_eq!(a.extract::<i32, 5>(), 5, "at {}", isa);
+                assert_eq!(a.extract::<u8, 16>(), 4, "at {}", isa);
+                assert_eq!(a.insert::<i64, 2>(-1), Ymm::from_i32([0, 1, 2, 3, -1, -1, 6, 7]), "at {}", isa);
+                assert_eq!(a.insert::<f32, 4>(1.0).halves().0, a.halves().0, "at {}", isa);
+            });
+        }
+    }
+}
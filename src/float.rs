@@ -0,0 +1,800 @@
+//! Packed `f32` and `f64` arithmetic: the four basic operations, square root,
+//! absolute value, negation, minimum and maximum, rounding to an integer and
+//! reciprocal and reciprocal square root approximations.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//!
+//! let a = Xmm::from_f32([2.5, -0.5, 9.0, f32::NAN]);
+//! let b = Xmm::splat_f32(1.0);
+//! assert_eq!(a.add_float::<f32>(b).as_f32()[..3], [3.5, 0.5, 10.0]);
+//! assert_eq!(a.round_float::<f32>().as_f32()[..3], [2.0, -0.0, 9.0]);
+//! assert_eq!(a.max_float::<f32>(b).as_f32(), &[2.5, 1.0, 9.0, 1.0]);
+//! ```
+//!
+//! The 128-bit kernels use SSE2, and SSE4.1 for rounding, which SSE2
+//! emulates. [`Ymm`] uses AVX and otherwise each half.
+//!
+//! # NaN and signed zeros
+//!
+//! `add_float`, `sub_float`, `mul_float`, `div_float` and `sqrt_float` follow
+//! IEEE 754: a NaN operand gives NaN, as do `inf - inf`, `0 * inf`, `0 / 0`
+//! and the square root of a negative number, and `sqrt(-0.0)` is `-0.0`. The
+//! NaN payloads are not specified and may differ between levels.
+//!
+//! `min_float` and `max_float` behave like `_mm_min_ps` and `_mm_max_ps` at
+//! every level: `min(a, b)` is `if a < b { a } else { b }`. The second
+//! operand wins when either lane is NaN and when both are zeros, so
+//! `min(-0.0, 0.0)` is `0.0`. Unlike [`f32::min`], a NaN is only ignored when
+//! it is the first operand; pass it second to propagate it.
+//!
+//! `abs_float` and `neg_float` only clear or flip the sign bit, so they are
+//! exact for zeros, infinities and NaNs.
+//!
+//! `floor_float`, `ceil_float` and `round_float` return NaN and infinities
+//! unchanged and keep the sign of zero and of results that round to zero:
+//! `ceil(-0.5)` is `-0.0`. `round_float` goes to the nearest integer with
+//! ties to even, like [`f32::round_ties_even`], not away from zero like
+//! [`f32::round`]. The SSE2 emulation assumes the default rounding mode of
+//! the MXCSR register.
+//!
+//! `recip_float` and `rsqrt_float` are approximations for `f32` with a
+//! relative error of at most `1.5 × 2⁻¹²`, from `rcpps` and `rsqrtps`.
+//! Subnormal inputs may be treated as zero. There are no such instructions
+//! for `f64` before AVX-512, so `f64` divides, as do the portable kernels for
+//! both types. Both give `±inf` for `±0.0`, and `rsqrt_float` of a negative
+//! number is NaN.
+
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::lanes::{F32x4, F32x8, F64x2, F64x4};
+use crate::xmm::Xmm;
+use crate::ymm::{by_halves, map_halves, Ymm};
+
+/// Float lane types supported by the packed arithmetic.
+pub trait FloatLane: Lane {
+    #[doc(hidden)]
+    fn add_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn sub_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn mul_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn div_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn min_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn max_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn sqrt_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn abs_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn neg_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn floor_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn ceil_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn round_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn recip_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn rsqrt_xmm(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn add_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn sub_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn mul_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn div_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn min_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn max_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn sqrt_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn abs_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn neg_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn floor_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn ceil_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn round_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn recip_ymm(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn rsqrt_ymm(a: Ymm) -> Ymm;
+}
+
+/// Implements [`FloatLane`] with the kernels in the `$k` module (`ps` or
+/// `pd`) of each level.
+macro_rules! impl_float_lane {
+    ($t:ty, $k:ident) => {
+        impl FloatLane for $t {
+            float_kernels!($k, binary: add_xmm, add_ymm => add);
+            float_kernels!($k, binary: sub_xmm, sub_ymm => sub);
+            float_kernels!($k, binary: mul_xmm, mul_ymm => mul);
+            float_kernels!($k, binary: div_xmm, div_ymm => div);
+            float_kernels!($k, binary: min_xmm, min_ymm => min);
+            float_kernels!($k, binary: max_xmm, max_ymm => max);
+            float_kernels!($k, unary: sqrt_xmm, sqrt_ymm => sqrt);
+            float_kernels!($k, unary: abs_xmm, abs_ymm => abs);
+            float_kernels!($k, unary: neg_xmm, neg_ymm => neg);
+            float_kernels!($k, rounding: floor_xmm, floor_ymm => floor);
+            float_kernels!($k, rounding: ceil_xmm, ceil_ymm => ceil);
+            float_kernels!($k, rounding: round_xmm, round_ymm => round);
+            float_kernels!($k, unary: recip_xmm, recip_ymm => recip);
+            float_kernels!($k, unary: rsqrt_xmm, rsqrt_ymm => rsqrt);
+        }
+    };
+}
+
+macro_rules! float_kernels {
+    ($k:ident, binary: $xmm:ident, $ymm:ident => $f:ident) => {
+        #[inline(always)]
+        fn $xmm(a: Xmm, b: Xmm) -> Xmm {
+            select!(Sse2 => sse2::$k::$f(a, b), _ => scalar::$k::$f(a, b))
+        }
+
+        #[inline(always)]
+        fn $ymm(a: Ymm, b: Ymm) -> Ymm {
+            select!(Avx2 => unsafe { avx::$k::$f(a, b) }, _ => by_halves(a, b, Self::$xmm))
+        }
+    };
+    ($k:ident, unary: $xmm:ident, $ymm:ident => $f:ident) => {
+        #[inline(always)]
+        fn $xmm(a: Xmm) -> Xmm {
+            select!(Sse2 => sse2::$k::$f(a), _ => scalar::$k::$f(a))
+        }
+
+        #[inline(always)]
+        fn $ymm(a: Ymm) -> Ymm {
+            select!(Avx2 => unsafe { avx::$k::$f(a) }, _ => map_halves(a, Self::$xmm))
+        }
+    };
+    ($k:ident, rounding: $xmm:ident, $ymm:ident => $f:ident) => {
+        #[inline(always)]
+        fn $xmm(a: Xmm) -> Xmm {
+            select!(
+                Sse41 => unsafe { sse41::$k::$f(a) },
+                Sse2 => sse2::$k::$f(a),
+                _ => scalar::$k::$f(a),
+            )
+        }
+
+        #[inline(always)]
+        fn $ymm(a: Ymm) -> Ymm {
+            select!(Avx2 => unsafe { avx::$k::$f(a) }, _ => map_halves(a, Self::$xmm))
+        }
+    };
+}
+
+impl_float_lane!(f32, ps);
+impl_float_lane!(f64, pd);
+
+macro_rules! impl_float_methods {
+    ($reg:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $min:ident, $max:ident, $sqrt:ident, $abs:ident,
+     $neg:ident, $floor:ident, $ceil:ident, $round:ident, $recip:ident, $rsqrt:ident) => {
+        impl $reg {
+            /// Adds packed `T` lanes.
+            #[inline(always)]
+            pub fn add_float<T: FloatLane>(self, rhs: $reg) -> $reg {
+                T::$add(self, rhs)
+            }
+
+            /// Subtracts packed `T` lanes.
+            #[inline(always)]
+            pub fn sub_float<T: FloatLane>(self, rhs: $reg) -> $reg {
+                T::$sub(self, rhs)
+            }
+
+            /// Multiplies packed `T` lanes.
+            #[inline(always)]
+            pub fn mul_float<T: FloatLane>(self, rhs: $reg) -> $reg {
+                T::$mul(self, rhs)
+            }
+
+            /// Divides packed `T` lanes.
+            #[inline(always)]
+            pub fn div_float<T: FloatLane>(self, rhs: $reg) -> $reg {
+                T::$div(self, rhs)
+            }
+
+            /// Lane-wise `if self < rhs { self } else { rhs }`, so `rhs` wins
+            /// for NaNs and equal zeros.
+            #[inline(always)]
+            pub fn min_float<T: FloatLane>(self, rhs: $reg) -> $reg {
+                T::$min(self, rhs)
+            }
+
+            /// Lane-wise `if self > rhs { self } else { rhs }`, so `rhs` wins
+            /// for NaNs and equal zeros.
+            #[inline(always)]
+            pub fn max_float<T: FloatLane>(self, rhs: $reg) -> $reg {
+                T::$max(self, rhs)
+            }
+
+            /// Square root of packed `T` lanes, correctly rounded.
+            #[inline(always)]
+            pub fn sqrt_float<T: FloatLane>(self) -> $reg {
+                T::$sqrt(self)
+            }
+
+            /// Clears the sign bit of packed `T` lanes.
+            #[inline(always)]
+            pub fn abs_float<T: FloatLane>(self) -> $reg {
+                T::$abs(self)
+            }
+
+            /// Flips the sign bit of packed `T` lanes.
+            #[inline(always)]
+            pub fn neg_float<T: FloatLane>(self) -> $reg {
+                T::$neg(self)
+            }
+
+            /// Rounds packed `T` lanes towards negative infinity.
+            #[inline(always)]
+            pub fn floor_float<T: FloatLane>(self) -> $reg {
+                T::$floor(self)
+            }
+
+            /// Rounds packed `T` lanes towards positive infinity.
+            #[inline(always)]
+            pub fn ceil_float<T: FloatLane>(self) -> $reg {
+                T::$ceil(self)
+            }
+
+            /// Rounds packed `T` lanes to the nearest integer, ties to even.
+            #[inline(always)]
+            pub fn round_float<T: FloatLane>(self) -> $reg {
+                T::$round(self)
+            }
+
+            /// Approximates `1 / x` for packed `T` lanes. See the
+            /// [module documentation](crate::float) for the accuracy.
+            #[inline(always)]
+            pub fn recip_float<T: FloatLane>(self) -> $reg {
+                T::$recip(self)
+            }
+
+            /// Approximates `1 / sqrt(x)` for packed `T` lanes. See the
+            /// [module documentation](crate::float) for the accuracy.
+            #[inline(always)]
+            pub fn rsqrt_float<T: FloatLane>(self) -> $reg {
+                T::$rsqrt(self)
+            }
+        }
+    };
+}
+
+impl_float_methods!(
+    Xmm, add_xmm, sub_xmm, mul_xmm, div_xmm, min_xmm, max_xmm, sqrt_xmm, abs_xmm, neg_xmm, floor_xmm, ceil_xmm,
+    round_xmm, recip_xmm, rsqrt_xmm
+);
+impl_float_methods!(
+    Ymm, add_ymm, sub_ymm, mul_ymm, div_ymm, min_ymm, max_ymm, sqrt_ymm, abs_ymm, neg_ymm, floor_ymm, ceil_ymm,
+    round_ymm, recip_ymm, rsqrt_ymm
+);
+
+/// The operations without an operator on the [`lanes`](crate::lanes)
+/// wrappers.
+macro_rules! impl_wrapper_methods {
+    ($name:ident, $reg:ident, $t:ty) => {
+        impl $name {
+            /// See [`Xmm::min_float`].
+            #[inline]
+            pub fn min(self, rhs: Self) -> Self {
+                $reg::from(self).min_float::<$t>(rhs.into()).into()
+            }
+
+            /// See [`Xmm::max_float`].
+            #[inline]
+            pub fn max(self, rhs: Self) -> Self {
+                $reg::from(self).max_float::<$t>(rhs.into()).into()
+            }
+
+            #[inline]
+            pub fn sqrt(self) -> Self {
+                $reg::from(self).sqrt_float::<$t>().into()
+            }
+
+            #[inline]
+            pub fn abs(self) -> Self {
+                $reg::from(self).abs_float::<$t>().into()
+            }
+
+            #[inline]
+            pub fn floor(self) -> Self {
+                $reg::from(self).floor_float::<$t>().into()
+            }
+
+            #[inline]
+            pub fn ceil(self) -> Self {
+                $reg::from(self).ceil_float::<$t>().into()
+            }
+
+            /// Rounds to the nearest integer, ties to even.
+            #[inline]
+            pub fn round(self) -> Self {
+                $reg::from(self).round_float::<$t>().into()
+            }
+
+            /// See [`Xmm::recip_float`].
+            #[inline]
+            pub fn recip(self) -> Self {
+                $reg::from(self).recip_float::<$t>().into()
+            }
+
+            /// See [`Xmm::rsqrt_float`].
+            #[inline]
+            pub fn rsqrt(self) -> Self {
+                $reg::from(self).rsqrt_float::<$t>().into()
+            }
+        }
+    };
+}
+
+impl_wrapper_methods!(F32x4, Xmm, f32);
+impl_wrapper_methods!(F64x2, Xmm, f64);
+impl_wrapper_methods!(F32x8, Ymm, f32);
+impl_wrapper_methods!(F64x4, Ymm, f64);
+
+/// Portable kernels over [`Xmm`].
+mod scalar {
+    macro_rules! kernels {
+        ($k:ident, $t:ty) => {
+            pub mod $k {
+                use crate::scalar::{map, zip};
+                use crate::xmm::Xmm;
+
+                pub fn add(a: Xmm, b: Xmm) -> Xmm {
+                    zip(a, b, |x: $t, y| x + y)
+                }
+
+                pub fn sub(a: Xmm, b: Xmm) -> Xmm {
+                    zip(a, b, |x: $t, y| x - y)
+                }
+
+                pub fn mul(a: Xmm, b: Xmm) -> Xmm {
+                    zip(a, b, |x: $t, y| x * y)
+                }
+
+                pub fn div(a: Xmm, b: Xmm) -> Xmm {
+                    zip(a, b, |x: $t, y| x / y)
+                }
+
+                pub fn min(a: Xmm, b: Xmm) -> Xmm {
+                    zip(a, b, |x: $t, y| if x < y { x } else { y })
+                }
+
+                pub fn max(a: Xmm, b: Xmm) -> Xmm {
+                    zip(a, b, |x: $t, y| if x > y { x } else { y })
+                }
+
+                pub fn sqrt(a: Xmm) -> Xmm {
+                    map(a, <$t>::sqrt)
+                }
+
+                pub fn abs(a: Xmm) -> Xmm {
+                    map(a, <$t>::abs)
+                }
+
+                pub fn neg(a: Xmm) -> Xmm {
+                    map(a, |x: $t| -x)
+                }
+
+                pub fn floor(a: Xmm) -> Xmm {
+                    map(a, <$t>::floor)
+                }
+
+                pub fn ceil(a: Xmm) -> Xmm {
+                    map(a, <$t>::ceil)
+                }
+
+                pub fn round(a: Xmm) -> Xmm {
+                    map(a, <$t>::round_ties_even)
+                }
+
+                pub fn recip(a: Xmm) -> Xmm {
+                    map(a, |x: $t| 1.0 / x)
+                }
+
+                pub fn rsqrt(a: Xmm) -> Xmm {
+                    map(a, |x: $t| 1.0 / x.sqrt())
+                }
+            }
+        };
+    }
+
+    kernels!(ps, f32);
+    kernels!(pd, f64);
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic($vec::from(a), $vec::from(b)) }.into()
+            }
+        };
+    }
+
+    macro_rules! unary {
+        ($name:ident, $vec:ident, |$x:ident| $body:expr) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm) -> Xmm {
+                let $x = $vec::from(a);
+                unsafe { $body }.into()
+            }
+        };
+    }
+
+    /// Rounding without `roundps`. Below `2^(mantissa bits)` adding and
+    /// subtracting that power of two rounds the magnitude to the nearest
+    /// integer, ties to even; floor and ceil then step back by one where that
+    /// went the wrong way. Larger magnitudes, infinities and NaNs are already
+    /// integral and are kept, and every result takes the sign of its input so
+    /// that zeros keep theirs.
+    macro_rules! emulated_rounding {
+        (
+            $vec:ident, $magic:expr,
+            add: $add:ident, sub: $sub:ident, and: $and:ident, andnot: $andnot:ident, or: $or:ident,
+            cmplt: $cmplt:ident, cmpgt: $cmpgt:ident, set1: $set1:ident
+        ) => {
+            #[inline(always)]
+            unsafe fn nearest(x: $vec) -> $vec {
+                let sign = $set1(-0.0);
+                let magic = $set1($magic);
+                $or($sub($add($andnot(sign, x), magic), magic), $and(x, sign))
+            }
+
+            /// `r` with the sign of `x` where `x` is small enough to round,
+            /// else `x`.
+            #[inline(always)]
+            unsafe fn finish(x: $vec, r: $vec) -> $vec {
+                let sign = $set1(-0.0);
+                let small = $cmplt($andnot(sign, x), $set1($magic));
+                let r = $or($andnot(sign, r), $and(x, sign));
+                $or($and(small, r), $andnot(small, x))
+            }
+
+            #[inline(always)]
+            pub fn round(a: Xmm) -> Xmm {
+                unsafe {
+                    let x = $vec::from(a);
+                    finish(x, nearest(x)).into()
+                }
+            }
+
+            #[inline(always)]
+            pub fn floor(a: Xmm) -> Xmm {
+                unsafe {
+                    let x = $vec::from(a);
+                    let r = nearest(x);
+                    finish(x, $sub(r, $and($cmpgt(r, x), $set1(1.0)))).into()
+                }
+            }
+
+            #[inline(always)]
+            pub fn ceil(a: Xmm) -> Xmm {
+                unsafe {
+                    let x = $vec::from(a);
+                    let r = nearest(x);
+                    finish(x, $add(r, $and($cmplt(r, x), $set1(1.0)))).into()
+                }
+            }
+        };
+    }
+
+    pub mod ps {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        binary!(add, __m128, _mm_add_ps);
+        binary!(sub, __m128, _mm_sub_ps);
+        binary!(mul, __m128, _mm_mul_ps);
+        binary!(div, __m128, _mm_div_ps);
+        binary!(min, __m128, _mm_min_ps);
+        binary!(max, __m128, _mm_max_ps);
+        unary!(sqrt, __m128, |x| _mm_sqrt_ps(x));
+        unary!(abs, __m128, |x| _mm_andnot_ps(_mm_set1_ps(-0.0), x));
+        unary!(neg, __m128, |x| _mm_xor_ps(x, _mm_set1_ps(-0.0)));
+        unary!(recip, __m128, |x| _mm_rcp_ps(x));
+        unary!(rsqrt, __m128, |x| _mm_rsqrt_ps(x));
+
+        emulated_rounding!(
+            __m128, 8388608.0,
+            add: _mm_add_ps, sub: _mm_sub_ps, and: _mm_and_ps, andnot: _mm_andnot_ps, or: _mm_or_ps,
+            cmplt: _mm_cmplt_ps, cmpgt: _mm_cmpgt_ps, set1: _mm_set1_ps
+        );
+    }
+
+    pub mod pd {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        binary!(add, __m128d, _mm_add_pd);
+        binary!(sub, __m128d, _mm_sub_pd);
+        binary!(mul, __m128d, _mm_mul_pd);
+        binary!(div, __m128d, _mm_div_pd);
+        binary!(min, __m128d, _mm_min_pd);
+        binary!(max, __m128d, _mm_max_pd);
+        unary!(sqrt, __m128d, |x| _mm_sqrt_pd(x));
+        unary!(abs, __m128d, |x| _mm_andnot_pd(_mm_set1_pd(-0.0), x));
+        unary!(neg, __m128d, |x| _mm_xor_pd(x, _mm_set1_pd(-0.0)));
+        unary!(recip, __m128d, |x| _mm_div_pd(_mm_set1_pd(1.0), x));
+        unary!(rsqrt, __m128d, |x| _mm_div_pd(_mm_set1_pd(1.0), _mm_sqrt_pd(x)));
+
+        emulated_rounding!(
+            __m128d, 4503599627370496.0,
+            add: _mm_add_pd, sub: _mm_sub_pd, and: _mm_and_pd, andnot: _mm_andnot_pd, or: _mm_or_pd,
+            cmplt: _mm_cmplt_pd, cmpgt: _mm_cmpgt_pd, set1: _mm_set1_pd
+        );
+    }
+}
+
+/// SSE4.1 rounding kernels over [`Xmm`]. Callers must check
+/// [`has_sse41`](crate::dispatch::has_sse41) first.
+#[cfg(simd_x86)]
+mod sse41 {
+    macro_rules! rounding {
+        ($vec:ident, $floor:ident, $ceil:ident, $round:ident) => {
+            #[inline]
+            #[target_feature(enable = "sse4.1")]
+            pub unsafe fn floor(a: Xmm) -> Xmm {
+                $floor($vec::from(a)).into()
+            }
+
+            #[inline]
+            #[target_feature(enable = "sse4.1")]
+            pub unsafe fn ceil(a: Xmm) -> Xmm {
+                $ceil($vec::from(a)).into()
+            }
+
+            #[inline]
+            #[target_feature(enable = "sse4.1")]
+            pub unsafe fn round(a: Xmm) -> Xmm {
+                $round::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>($vec::from(a)).into()
+            }
+        };
+    }
+
+    pub mod ps {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        rounding!(__m128, _mm_floor_ps, _mm_ceil_ps, _mm_round_ps);
+    }
+
+    pub mod pd {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        rounding!(__m128d, _mm_floor_pd, _mm_ceil_pd, _mm_round_pd);
+    }
+}
+
+/// AVX kernels over [`Ymm`]. AVX is only enabled with
+/// AVX2, so callers must check [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx {
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic($vec::from(a), $vec::from(b)).into()
+            }
+        };
+    }
+
+    macro_rules! unary {
+        ($name:ident, $vec:ident, |$x:ident| $body:expr) => {
+            #[inline]
+            #[target_feature(enable = "avx")]
+            pub unsafe fn $name(a: Ymm) -> Ymm {
+                let $x = $vec::from(a);
+                $body.into()
+            }
+        };
+    }
+
+    pub mod ps {
+        use crate::ymm::Ymm;
+        use std::arch::x86_64::*;
+
+        binary!(add, __m256, _mm256_add_ps);
+        binary!(sub, __m256, _mm256_sub_ps);
+        binary!(mul, __m256, _mm256_mul_ps);
+        binary!(div, __m256, _mm256_div_ps);
+        binary!(min, __m256, _mm256_min_ps);
+        binary!(max, __m256, _mm256_max_ps);
+        unary!(sqrt, __m256, |x| _mm256_sqrt_ps(x));
+        unary!(abs, __m256, |x| _mm256_andnot_ps(_mm256_set1_ps(-0.0), x));
+        unary!(neg, __m256, |x| _mm256_xor_ps(x, _mm256_set1_ps(-0.0)));
+        unary!(floor, __m256, |x| _mm256_floor_ps(x));
+        unary!(ceil, __m256, |x| _mm256_ceil_ps(x));
+        unary!(round, __m256, |x| _mm256_round_ps::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>(x));
+        unary!(recip, __m256, |x| _mm256_rcp_ps(x));
+        unary!(rsqrt, __m256, |x| _mm256_rsqrt_ps(x));
+    }
+
+    pub mod pd {
+        use crate::ymm::Ymm;
+        use std::arch::x86_64::*;
+
+        binary!(add, __m256d, _mm256_add_pd);
+        binary!(sub, __m256d, _mm256_sub_pd);
+        binary!(mul, __m256d, _mm256_mul_pd);
+        binary!(div, __m256d, _mm256_div_pd);
+        binary!(min, __m256d, _mm256_min_pd);
+        binary!(max, __m256d, _mm256_max_pd);
+        unary!(sqrt, __m256d, |x| _mm256_sqrt_pd(x));
+        unary!(abs, __m256d, |x| _mm256_andnot_pd(_mm256_set1_pd(-0.0), x));
+        unary!(neg, __m256d, |x| _mm256_xor_pd(x, _mm256_set1_pd(-0.0)));
+        unary!(floor, __m256d, |x| _mm256_floor_pd(x));
+        unary!(ceil, __m256d, |x| _mm256_ceil_pd(x));
+        unary!(round, __m256d, |x| _mm256_round_pd::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>(x));
+        unary!(recip, __m256d, |x| _mm256_div_pd(_mm256_set1_pd(1.0), x));
+        unary!(rsqrt, __m256d, |x| _mm256_div_pd(_mm256_set1_pd(1.0), _mm256_sqrt_pd(x)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    /// Values where the kernels are most likely to disagree with the scalar
+    /// functions: signed zeros, halves, the edge of the exactly representable
+    /// integers, infinities and NaN.
+    macro_rules! special {
+        ($t:ty, $magic:expr) => {
+            [
+                0.0, -0.0, 0.5, -0.5, 1.5, -1.5, 2.5, -2.5, 0.49999997, -0.7, 1.0, -1.0, $magic - 0.5, -$magic + 0.5,
+                $magic, $magic + 1.0, <$t>::MAX, <$t>::MIN, <$t>::MIN_POSITIVE, <$t>::INFINITY, <$t>::NEG_INFINITY,
+                <$t>::NAN, -<$t>::NAN,
+            ]
+        };
+    }
+
+    /// Compares bit patterns, treating any two NaNs as equal.
+    macro_rules! assert_lanes_eq {
+        ($got:expr, $expect:expr, $t:ty, $($msg:tt)+) => {{
+            let (got, expect) = ($got, $expect);
+            let (got, expect) = (got.lanes::<$t>(), expect.lanes::<$t>());
+            for (g, e) in got.iter().zip(expect) {
+                assert!(g.to_bits() == e.to_bits() || (g.is_nan() && e.is_nan()), "{:?} != {:?}: {}", got, expect, format!($($msg)+));
+            }
+        }};
+    }
+
+    macro_rules! check_against_scalar {
+        ($reg:ident, $t:ty, $magic:expr) => {{
+            let mut rng = rand::thread_rng();
+            let special = special!($t, $magic);
+            let mut operand = || -> $t {
+                match rng.gen_range(0..4) {
+                    0 => special[rng.gen_range(0..special.len())],
+                    1 => rng.gen_range(-4.0..4.0),
+                    _ => rng.gen_range(-1e6..1e6),
+                }
+            };
+            for _ in 0..256 {
+                let a = $reg::from_fn::<$t>(|_| operand());
+                let b = $reg::from_fn::<$t>(|_| operand());
+                let (x, y) = (a.lanes::<$t>(), b.lanes::<$t>());
+                let zip = |f: fn($t, $t) -> $t| $reg::from_fn::<$t>(|i| f(x[i], y[i]));
+                let map = |f: fn($t) -> $t| $reg::from_fn::<$t>(|i| f(x[i]));
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        let t = stringify!($t);
+                        assert_lanes_eq!(a.add_float::<$t>(b), zip(|x, y| x + y), $t, "{} add at {}", t, isa);
+                        assert_lanes_eq!(a.sub_float::<$t>(b), zip(|x, y| x - y), $t, "{} sub at {}", t, isa);
+                        assert_lanes_eq!(a.mul_float::<$t>(b), zip(|x, y| x * y), $t, "{} mul at {}", t, isa);
+                        assert_lanes_eq!(a.div_float::<$t>(b), zip(|x, y| x / y), $t, "{} div at {}", t, isa);
+                        assert_lanes_eq!(a.min_float::<$t>(b), zip(|x, y| if x < y { x } else { y }), $t, "{} min at {}", t, isa);
+                        assert_lanes_eq!(a.max_float::<$t>(b), zip(|x, y| if x > y { x } else { y }), $t, "{} max at {}", t, isa);
+                        assert_lanes_eq!(a.sqrt_float::<$t>(), map(<$t>::sqrt), $t, "{} sqrt at {}", t, isa);
+                        assert_lanes_eq!(a.abs_float::<$t>(), map(<$t>::abs), $t, "{} abs at {}", t, isa);
+                        assert_lanes_eq!(a.neg_float::<$t>(), map(|x| -x), $t, "{} neg at {}", t, isa);
+                        assert_lanes_eq!(a.floor_float::<$t>(), map(<$t>::floor), $t, "{} floor at {}", t, isa);
+                        assert_lanes_eq!(a.ceil_float::<$t>(), map(<$t>::ceil), $t, "{} ceil at {}", t, isa);
+                        assert_lanes_eq!(a.round_float::<$t>(), map(<$t>::round_ties_even), $t, "{} round at {}", t, isa);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xmm_float_matches_scalar() {
+        check_against_scalar!(Xmm, f32, 8388608.0f32);
+        check_against_scalar!(Xmm, f64, 4503599627370496.0f64);
+    }
+
+    #[test]
+    fn test_ymm_float_matches_scalar() {
+        check_against_scalar!(Ymm, f32, 8388608.0f32);
+        check_against_scalar!(Ymm, f64, 4503599627370496.0f64);
+    }
+
+    macro_rules! check_approximations {
+        ($reg:ident, $t:ty, $tolerance:expr) => {{
+            let mut rng = rand::thread_rng();
+            for _ in 0..256 {
+                let a = $reg::from_fn::<$t>(|_| rng.gen_range(1e-30..1e30));
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        let recip = a.recip_float::<$t>();
+                        let rsqrt = a.rsqrt_float::<$t>();
+                        for (i, &x) in a.lanes::<$t>().iter().enumerate() {
+                            let error = |got: $t, expect: $t| ((got - expect) / expect).abs();
+                            assert!(error(recip.lanes::<$t>()[i], 1.0 / x) <= $tolerance, "recip({}) at {}", x, isa);
+                            assert!(error(rsqrt.lanes::<$t>()[i], 1.0 / x.sqrt()) <= $tolerance, "rsqrt({}) at {}", x, isa);
+                        }
+                    });
+                }
+            }
+
+            let zeros = $reg::from_fn::<$t>(|i| [0.0, -0.0][i % 2]);
+            let others = $reg::from_fn::<$t>(|i| [<$t>::INFINITY, -1.0][i % 2]);
+            for isa in Isa::supported() {
+                with_isa(isa, || {
+                    let infinities = [<$t>::INFINITY, <$t>::NEG_INFINITY];
+                    assert_eq!(zeros.recip_float::<$t>().lanes::<$t>()[..2], infinities, "at {}", isa);
+                    assert_eq!(zeros.rsqrt_float::<$t>().lanes::<$t>()[..2], infinities, "at {}", isa);
+                    assert_eq!(others.recip_float::<$t>().lanes::<$t>()[0], 0.0, "at {}", isa);
+                    assert_eq!(others.rsqrt_float::<$t>().lanes::<$t>()[0], 0.0, "at {}", isa);
+                    assert!(others.rsqrt_float::<$t>().lanes::<$t>()[1].is_nan(), "at {}", isa);
+                });
+            }
+        }};
+    }
+
+    #[test]
+    fn test_approximations() {
+        check_approximations!(Xmm, f32, 1.5 / 4096.0);
+        check_approximations!(Ymm, f32, 1.5 / 4096.0);
+        check_approximations!(Xmm, f64, f64::EPSILON);
+        check_approximations!(Ymm, f64, f64::EPSILON);
+    }
+
+    #[test]
+    fn test_signed_zeros_and_nan() {
+        let a = Xmm::from_f32([-0.0, 0.0, f32::NAN, 1.0]);
+        let b = Xmm::from_f32([0.0, -0.0, 1.0, f32::NAN]);
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                let min = a.min_float::<f32>(b);
+                assert_eq!(min.as_f32().map(f32::to_bits)[..3], [0.0f32, -0.0, 1.0].map(f32::to_bits), "at {}", isa);
+                assert!(min.lane_f32(3).is_nan(), "at {}", isa);
+                let c = Xmm::from_f32([-0.5, -0.7, 0.3, -0.0]);
+                assert_eq!(c.ceil_float::<f32>().as_f32().map(f32::to_bits), [-0.0f32, -0.0, 1.0, -0.0].map(f32::to_bits));
+                assert_eq!(c.floor_float::<f32>().as_f32(), &[-1.0, -1.0, 0.0, 0.0]);
+                assert_eq!(c.round_float::<f32>().as_f32().map(f32::to_bits), [-0.0f32, -1.0, 0.0, -0.0].map(f32::to_bits));
+                assert_eq!(c.sqrt_float::<f32>().lane_f32(3).to_bits(), (-0.0f32).to_bits());
+            });
+        }
+    }
+
+    #[test]
+    fn test_wrappers() {
+        let a = F32x8::new([1.5, -2.5, 4.0, -0.0, 9.0, 0.5, -7.25, 16.0]);
+        assert_eq!(a.round().to_array(), [2.0, -2.0, 4.0, -0.0, 9.0, 0.0, -7.0, 16.0]);
+        assert_eq!(a.abs().max(F32x8::splat(2.0)).to_array(), [2.0, 2.5, 4.0, 2.0, 9.0, 2.0, 7.25, 16.0]);
+        assert_eq!(F64x2::new([4.0, 2.25]).sqrt().to_array(), [2.0, 1.5]);
+        assert_eq!(F64x4::new([0.5, -0.5, 1.5, -1.5]).floor().to_array(), [0.0, -1.0, 1.0, -2.0]);
+        assert_eq!(F32x4::new([0.5, -0.5, 1.5, -1.5]).ceil().min(F32x4::splat(1.0)).to_array(), [1.0, -0.0, 1.0, -1.0]);
+    }
+}
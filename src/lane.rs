@@ -0,0 +1,51 @@
+//! Element types that can fill the lanes of a register.
+
+use std::fmt;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A primitive type that a register can be viewed as an array of.
+///
+/// Every bit pattern is a valid value of every lane type, which is what makes
+/// the safe lane views on [`Xmm`](crate::xmm::Xmm) and friends sound. The trait
+/// is sealed and implemented for `i8`…`u64`, `f32` and `f64`.
+pub trait Lane:
+    sealed::Sealed + Copy + Default + PartialEq + PartialOrd + fmt::Debug + fmt::Display + Send + Sync + 'static
+{
+    /// Width of the lane in bits.
+    const BITS: u32;
+    /// Rust name of the type, e.g. `"i16"`.
+    const NAME: &'static str;
+    const SIGNED: bool;
+    const FLOAT: bool;
+}
+
+macro_rules! impl_lane {
+    ($($t:ty => $signed:literal, $float:literal;)*) => {
+        $(
+            impl sealed::Sealed for $t {}
+
+            impl Lane for $t {
+                const BITS: u32 = (std::mem::size_of::<$t>() * 8) as u32;
+                const NAME: &'static str = stringify!($t);
+                const SIGNED: bool = $signed;
+                const FLOAT: bool = $float;
+            }
+        )*
+    };
+}
+
+impl_lane! {
+    i8 => true, false;
+    i16 => true, false;
+    i32 => true, false;
+    i64 => true, false;
+    u8 => false, false;
+    u16 => false, false;
+    u32 => false, false;
+    u64 => false, false;
+    f32 => true, true;
+    f64 => true, true;
+}
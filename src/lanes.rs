@@ -0,0 +1,756 @@
+//! Strongly typed lane wrappers over [`Xmm`] and [`Ymm`].
+//!
+//! Each wrapper fixes the lane type of the register, so `I16x8 + I16x8` always
+//! lowers to `_mm_add_epi16` and can never be mixed up with `_mm_add_epi32`.
+//! Integer arithmetic wraps around. Shifts by a count of at least the lane
+//! width give zero, or all sign bits for `>>` on signed lanes.
+//!
+//! 128-bit wrappers use SSE2. 256-bit wrappers use AVX2 (AVX for floats) when
+//! the CPU supports it and the 128-bit version on each half otherwise. At
+//! [`Isa::Scalar`](crate::dispatch::Isa::Scalar) every operation runs lane by
+//! lane.
+
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign, Mul,
+    MulAssign, Neg, Not, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+use crate::dispatch::select;
+use crate::xmm::Xmm;
+use crate::ymm::Ymm;
+
+macro_rules! define_vector {
+    ($name:ident, $reg:ident, $t:ty, $n:literal, $as:ident, $from:ident, $splat:ident) => {
+        #[doc = concat!("`", stringify!($n), "` lanes of `", stringify!($t), "` in a [`", stringify!($reg), "`].")]
+        #[derive(Clone, Copy, Default)]
+        #[repr(transparent)]
+        pub struct $name($reg);
+
+        impl $name {
+            pub const LANES: usize = $n;
+
+            #[inline(always)]
+            pub const fn new(lanes: [$t; $n]) -> Self {
+                Self($reg::$from(lanes))
+            }
+
+            #[inline(always)]
+            pub const fn splat(x: $t) -> Self {
+                Self($reg::$splat(x))
+            }
+
+            #[inline(always)]
+            pub fn as_array(&self) -> &[$t; $n] {
+                self.0.$as()
+            }
+
+            #[inline(always)]
+            pub fn to_array(self) -> [$t; $n] {
+                *self.0.$as()
+            }
+        }
+
+        impl From<$reg> for $name {
+            #[inline(always)]
+            fn from(reg: $reg) -> Self {
+                Self(reg)
+            }
+        }
+
+        impl From<$name> for $reg {
+            #[inline(always)]
+            fn from(v: $name) -> Self {
+                v.0
+            }
+        }
+
+        impl From<[$t; $n]> for $name {
+            #[inline(always)]
+            fn from(lanes: [$t; $n]) -> Self {
+                Self::new(lanes)
+            }
+        }
+
+        /// Compares lane by lane, so float lanes follow IEEE 754: `0.0 == -0.0`
+        /// and NaN equals nothing.
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.as_array() == other.as_array()
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(self.as_array()).finish()
+            }
+        }
+    };
+}
+
+define_vector!(I8x16, Xmm, i8, 16, as_i8, from_i8, splat_i8);
+define_vector!(I16x8, Xmm, i16, 8, as_i16, from_i16, splat_i16);
+define_vector!(I32x4, Xmm, i32, 4, as_i32, from_i32, splat_i32);
+define_vector!(I64x2, Xmm, i64, 2, as_i64, from_i64, splat_i64);
+define_vector!(U8x16, Xmm, u8, 16, as_u8, from_u8, splat_u8);
+define_vector!(U16x8, Xmm, u16, 8, as_u16, from_u16, splat_u16);
+define_vector!(U32x4, Xmm, u32, 4, as_u32, from_u32, splat_u32);
+define_vector!(U64x2, Xmm, u64, 2, as_u64, from_u64, splat_u64);
+define_vector!(F32x4, Xmm, f32, 4, as_f32, from_f32, splat_f32);
+define_vector!(F64x2, Xmm, f64, 2, as_f64, from_f64, splat_f64);
+
+define_vector!(I8x32, Ymm, i8, 32, as_i8, from_i8, splat_i8);
+define_vector!(I16x16, Ymm, i16, 16, as_i16, from_i16, splat_i16);
+define_vector!(I32x8, Ymm, i32, 8, as_i32, from_i32, splat_i32);
+define_vector!(I64x4, Ymm, i64, 4, as_i64, from_i64, splat_i64);
+define_vector!(U8x32, Ymm, u8, 32, as_u8, from_u8, splat_u8);
+define_vector!(U16x16, Ymm, u16, 16, as_u16, from_u16, splat_u16);
+define_vector!(U32x8, Ymm, u32, 8, as_u32, from_u32, splat_u32);
+define_vector!(U64x4, Ymm, u64, 4, as_u64, from_u64, splat_u64);
+define_vector!(F32x8, Ymm, f32, 8, as_f32, from_f32, splat_f32);
+define_vector!(F64x4, Ymm, f64, 4, as_f64, from_f64, splat_f64);
+
+macro_rules! impl_eq {
+    ($($name:ident),*) => {
+        $(impl Eq for $name {})*
+    };
+}
+
+impl_eq!(I8x16, I16x8, I32x4, I64x2, U8x16, U16x8, U32x4, U64x2);
+impl_eq!(I8x32, I16x16, I32x8, I64x4, U8x32, U16x16, U32x8, U64x4);
+
+/// Implements a binary operator (and its assigning form) by calling `$f` on
+/// the underlying registers.
+macro_rules! binop {
+    ($name:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $f:expr) => {
+        impl $trait for $name {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, rhs: Self) -> Self {
+                Self($f(self.0, rhs.0))
+            }
+        }
+
+        impl $assign_trait for $name {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+/// Implements a binary operator on a 256-bit wrapper, falling back to the
+/// `$half` wrapper on each 128-bit half without AVX2.
+macro_rules! ymm_binop {
+    ($name:ident, $half:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $f:path) => {
+        impl $trait for $name {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, rhs: Self) -> Self {
+                select!(Avx2 => Self(unsafe { $f(self.0, rhs.0) }), _ => {
+                    let (a_lo, a_hi) = self.0.halves();
+                    let (b_lo, b_hi) = rhs.0.halves();
+                    let lo = $trait::$method($half(a_lo), $half(b_lo));
+                    let hi = $trait::$method($half(a_hi), $half(b_hi));
+                    Self(Ymm::from_halves(lo.0, hi.0))
+                })
+            }
+        }
+
+        impl $assign_trait for $name {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+macro_rules! xmm_shift {
+    ($name:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $f:path) => {
+        impl $trait<u32> for $name {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, count: u32) -> Self {
+                Self($f(self.0, count))
+            }
+        }
+
+        impl $assign_trait<u32> for $name {
+            #[inline]
+            fn $assign_method(&mut self, count: u32) {
+                *self = $trait::$method(*self, count);
+            }
+        }
+    };
+}
+
+macro_rules! ymm_shift {
+    ($name:ident, $half:ident, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $f:path) => {
+        impl $trait<u32> for $name {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, count: u32) -> Self {
+                select!(Avx2 => Self(unsafe { $f(self.0, count) }), _ => {
+                    let (lo, hi) = self.0.halves();
+                    Self(Ymm::from_halves($trait::$method($half(lo), count).0, $trait::$method($half(hi), count).0))
+                })
+            }
+        }
+
+        impl $assign_trait<u32> for $name {
+            #[inline]
+            fn $assign_method(&mut self, count: u32) {
+                *self = $trait::$method(*self, count);
+            }
+        }
+    };
+}
+
+macro_rules! xmm_int_ops {
+    ($name:ident, $t:ty, $shl:ident, $shr:ident) => {
+        binop!($name, Add, add, AddAssign, add_assign, Xmm::add_wrapping::<$t>);
+        binop!($name, Sub, sub, SubAssign, sub_assign, Xmm::sub_wrapping::<$t>);
+        binop!($name, Mul, mul, MulAssign, mul_assign, Xmm::mul_lo::<$t>);
+        binop!($name, BitAnd, bitand, BitAndAssign, bitand_assign, kernels::and);
+        binop!($name, BitOr, bitor, BitOrAssign, bitor_assign, kernels::or);
+        binop!($name, BitXor, bitxor, BitXorAssign, bitxor_assign, kernels::xor);
+        xmm_shift!($name, Shl, shl, ShlAssign, shl_assign, kernels::$shl);
+        xmm_shift!($name, Shr, shr, ShrAssign, shr_assign, kernels::$shr);
+
+        impl Not for $name {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                Self(kernels::not(self.0))
+            }
+        }
+    };
+}
+
+macro_rules! ymm_int_ops {
+    ($name:ident, $half:ident, $t:ty, $shl:ident, $shr:ident) => {
+        binop!($name, Add, add, AddAssign, add_assign, Ymm::add_wrapping::<$t>);
+        binop!($name, Sub, sub, SubAssign, sub_assign, Ymm::sub_wrapping::<$t>);
+        binop!($name, Mul, mul, MulAssign, mul_assign, Ymm::mul_lo::<$t>);
+        ymm_binop!($name, $half, BitAnd, bitand, BitAndAssign, bitand_assign, avx2::and);
+        ymm_binop!($name, $half, BitOr, bitor, BitOrAssign, bitor_assign, avx2::or);
+        ymm_binop!($name, $half, BitXor, bitxor, BitXorAssign, bitxor_assign, avx2::xor);
+        ymm_shift!($name, $half, Shl, shl, ShlAssign, shl_assign, avx2::$shl);
+        ymm_shift!($name, $half, Shr, shr, ShrAssign, shr_assign, avx2::$shr);
+
+        impl Not for $name {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                self ^ Self(Ymm::splat_u8(0xFF))
+            }
+        }
+    };
+}
+
+xmm_int_ops!(I8x16, i8, sll_epi8, sra_epi8);
+xmm_int_ops!(I16x8, i16, sll_epi16, sra_epi16);
+xmm_int_ops!(I32x4, i32, sll_epi32, sra_epi32);
+xmm_int_ops!(I64x2, i64, sll_epi64, sra_epi64);
+xmm_int_ops!(U8x16, u8, sll_epi8, srl_epi8);
+xmm_int_ops!(U16x8, u16, sll_epi16, srl_epi16);
+xmm_int_ops!(U32x4, u32, sll_epi32, srl_epi32);
+xmm_int_ops!(U64x2, u64, sll_epi64, srl_epi64);
+
+ymm_int_ops!(I8x32, I8x16, i8, sll_epi8, sra_epi8);
+ymm_int_ops!(I16x16, I16x8, i16, sll_epi16, sra_epi16);
+ymm_int_ops!(I32x8, I32x4, i32, sll_epi32, sra_epi32);
+ymm_int_ops!(I64x4, I64x2, i64, sll_epi64, sra_epi64);
+ymm_int_ops!(U8x32, U8x16, u8, sll_epi8, srl_epi8);
+ymm_int_ops!(U16x16, U16x8, u16, sll_epi16, srl_epi16);
+ymm_int_ops!(U32x8, U32x4, u32, sll_epi32, srl_epi32);
+ymm_int_ops!(U64x4, U64x2, u64, sll_epi64, srl_epi64);
+
+macro_rules! xmm_float_ops {
+    ($name:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $sign:expr) => {
+        binop!($name, Add, add, AddAssign, add_assign, kernels::$add);
+        binop!($name, Sub, sub, SubAssign, sub_assign, kernels::$sub);
+        binop!($name, Mul, mul, MulAssign, mul_assign, kernels::$mul);
+        binop!($name, Div, div, DivAssign, div_assign, kernels::$div);
+        binop!($name, BitAnd, bitand, BitAndAssign, bitand_assign, kernels::and);
+        binop!($name, BitOr, bitor, BitOrAssign, bitor_assign, kernels::or);
+        binop!($name, BitXor, bitxor, BitXorAssign, bitxor_assign, kernels::xor);
+
+        impl Not for $name {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                Self(kernels::not(self.0))
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            /// Flips the sign bit of every lane, so `-0.0` and `0.0` swap.
+            #[inline]
+            fn neg(self) -> Self {
+                self ^ Self::splat($sign)
+            }
+        }
+    };
+}
+
+macro_rules! ymm_float_ops {
+    ($name:ident, $half:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $and:ident, $or:ident, $xor:ident, $sign:expr) => {
+        ymm_binop!($name, $half, Add, add, AddAssign, add_assign, avx::$add);
+        ymm_binop!($name, $half, Sub, sub, SubAssign, sub_assign, avx::$sub);
+        ymm_binop!($name, $half, Mul, mul, MulAssign, mul_assign, avx::$mul);
+        ymm_binop!($name, $half, Div, div, DivAssign, div_assign, avx::$div);
+        ymm_binop!($name, $half, BitAnd, bitand, BitAndAssign, bitand_assign, avx::$and);
+        ymm_binop!($name, $half, BitOr, bitor, BitOrAssign, bitor_assign, avx::$or);
+        ymm_binop!($name, $half, BitXor, bitxor, BitXorAssign, bitxor_assign, avx::$xor);
+
+        impl Not for $name {
+            type Output = Self;
+
+            #[inline]
+            fn not(self) -> Self {
+                self ^ Self(Ymm::splat_u8(0xFF))
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                self ^ Self::splat($sign)
+            }
+        }
+    };
+}
+
+xmm_float_ops!(F32x4, add_ps, sub_ps, mul_ps, div_ps, -0.0f32);
+xmm_float_ops!(F64x2, add_pd, sub_pd, mul_pd, div_pd, -0.0f64);
+ymm_float_ops!(F32x8, F32x4, add_ps, sub_ps, mul_ps, div_ps, and_ps, or_ps, xor_ps, -0.0f32);
+ymm_float_ops!(F64x4, F64x2, add_pd, sub_pd, mul_pd, div_pd, and_pd, or_pd, xor_pd, -0.0f64);
+
+/// [`Xmm`] kernels that run on SSE2, or lane by lane at
+/// [`Isa::Scalar`](crate::dispatch::Isa::Scalar).
+mod kernels {
+    use super::select;
+    use crate::xmm::Xmm;
+
+    macro_rules! dispatched {
+        ($($name:ident($($arg:ident: $ty:ty),*);)*) => {
+            $(
+                #[inline(always)]
+                pub fn $name($($arg: $ty),*) -> Xmm {
+                    select!(Sse2 => super::sse2::$name($($arg),*), _ => super::scalar::$name($($arg),*))
+                }
+            )*
+        };
+    }
+
+    dispatched! {
+        and(a: Xmm, b: Xmm);
+        or(a: Xmm, b: Xmm);
+        xor(a: Xmm, b: Xmm);
+        not(a: Xmm);
+        add_ps(a: Xmm, b: Xmm);
+        sub_ps(a: Xmm, b: Xmm);
+        mul_ps(a: Xmm, b: Xmm);
+        div_ps(a: Xmm, b: Xmm);
+        add_pd(a: Xmm, b: Xmm);
+        sub_pd(a: Xmm, b: Xmm);
+        mul_pd(a: Xmm, b: Xmm);
+        div_pd(a: Xmm, b: Xmm);
+        sll_epi8(a: Xmm, count: u32);
+        sll_epi16(a: Xmm, count: u32);
+        sll_epi32(a: Xmm, count: u32);
+        sll_epi64(a: Xmm, count: u32);
+        srl_epi8(a: Xmm, count: u32);
+        srl_epi16(a: Xmm, count: u32);
+        srl_epi32(a: Xmm, count: u32);
+        srl_epi64(a: Xmm, count: u32);
+        sra_epi8(a: Xmm, count: u32);
+        sra_epi16(a: Xmm, count: u32);
+        sra_epi32(a: Xmm, count: u32);
+        sra_epi64(a: Xmm, count: u32);
+    }
+}
+
+/// Portable kernels over [`Xmm`], named after their SSE2 counterparts.
+mod scalar {
+    use crate::scalar::{map, zip};
+    use crate::xmm::Xmm;
+
+    macro_rules! binary {
+        ($name:ident, $t:ty, |$x:ident, $y:ident| $op:expr) => {
+            #[inline]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                zip(a, b, |$x: $t, $y| $op)
+            }
+        };
+    }
+
+    /// Counts of at least the lane width give zero for logical shifts and
+    /// fill arithmetic shifts with the sign bit, like SSE2.
+    macro_rules! shifts {
+        ($($u:ty, $i:ty => $sll:ident, $srl:ident, $sra:ident;)*) => {
+            $(
+                #[inline]
+                pub fn $sll(a: Xmm, count: u32) -> Xmm {
+                    map(a, |x: $u| x.checked_shl(count).unwrap_or(0))
+                }
+
+                #[inline]
+                pub fn $srl(a: Xmm, count: u32) -> Xmm {
+                    map(a, |x: $u| x.checked_shr(count).unwrap_or(0))
+                }
+
+                #[inline]
+                pub fn $sra(a: Xmm, count: u32) -> Xmm {
+                    map(a, |x: $i| x >> count.min(<$i>::BITS - 1))
+                }
+            )*
+        };
+    }
+
+    binary!(and, u64, |x, y| x & y);
+    binary!(or, u64, |x, y| x | y);
+    binary!(xor, u64, |x, y| x ^ y);
+    binary!(add_ps, f32, |x, y| x + y);
+    binary!(sub_ps, f32, |x, y| x - y);
+    binary!(mul_ps, f32, |x, y| x * y);
+    binary!(div_ps, f32, |x, y| x / y);
+    binary!(add_pd, f64, |x, y| x + y);
+    binary!(sub_pd, f64, |x, y| x - y);
+    binary!(mul_pd, f64, |x, y| x * y);
+    binary!(div_pd, f64, |x, y| x / y);
+
+    shifts! {
+        u8, i8 => sll_epi8, srl_epi8, sra_epi8;
+        u16, i16 => sll_epi16, srl_epi16, sra_epi16;
+        u32, i32 => sll_epi32, srl_epi32, sra_epi32;
+        u64, i64 => sll_epi64, srl_epi64, sra_epi64;
+    }
+
+    #[inline]
+    pub fn not(a: Xmm) -> Xmm {
+        map(a, |x: u64| !x)
+    }
+}
+
+/// SSE2 kernels over [`Xmm`]. SSE2 is part of the x86_64 baseline.
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic($vec::from(a), $vec::from(b)) }.into()
+            }
+        };
+    }
+
+    macro_rules! shift {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, count: u32) -> Xmm {
+                unsafe { $intrinsic(a.into(), _mm_cvtsi32_si128(count as i32)) }.into()
+            }
+        };
+    }
+
+    binary!(sub_epi8, __m128i, _mm_sub_epi8);
+    binary!(sub_epi64, __m128i, _mm_sub_epi64);
+    binary!(and, __m128i, _mm_and_si128);
+    binary!(or, __m128i, _mm_or_si128);
+    binary!(xor, __m128i, _mm_xor_si128);
+    binary!(add_ps, __m128, _mm_add_ps);
+    binary!(sub_ps, __m128, _mm_sub_ps);
+    binary!(mul_ps, __m128, _mm_mul_ps);
+    binary!(div_ps, __m128, _mm_div_ps);
+    binary!(add_pd, __m128d, _mm_add_pd);
+    binary!(sub_pd, __m128d, _mm_sub_pd);
+    binary!(mul_pd, __m128d, _mm_mul_pd);
+    binary!(div_pd, __m128d, _mm_div_pd);
+
+    shift!(sll_epi16, _mm_sll_epi16);
+    shift!(sll_epi32, _mm_sll_epi32);
+    shift!(sll_epi64, _mm_sll_epi64);
+    shift!(srl_epi16, _mm_srl_epi16);
+    shift!(srl_epi32, _mm_srl_epi32);
+    shift!(srl_epi64, _mm_srl_epi64);
+    shift!(sra_epi16, _mm_sra_epi16);
+    shift!(sra_epi32, _mm_sra_epi32);
+
+    #[inline(always)]
+    pub fn not(a: Xmm) -> Xmm {
+        xor(a, Xmm::splat_u8(0xFF))
+    }
+
+    #[inline(always)]
+    pub fn sll_epi8(a: Xmm, count: u32) -> Xmm {
+        if count >= 8 {
+            return Xmm::default();
+        }
+        and(sll_epi16(a, count), Xmm::splat_u8(0xFF << count))
+    }
+
+    #[inline(always)]
+    pub fn srl_epi8(a: Xmm, count: u32) -> Xmm {
+        if count >= 8 {
+            return Xmm::default();
+        }
+        and(srl_epi16(a, count), Xmm::splat_u8(0xFF >> count))
+    }
+
+    /// Logical shift, then sign-extend from the shifted sign bit `m`:
+    /// `(x ^ m) - m`.
+    #[inline(always)]
+    pub fn sra_epi8(a: Xmm, count: u32) -> Xmm {
+        let count = count.min(7);
+        let m = Xmm::splat_u8(0x80 >> count);
+        sub_epi8(xor(srl_epi8(a, count), m), m)
+    }
+
+    #[inline(always)]
+    pub fn sra_epi64(a: Xmm, count: u32) -> Xmm {
+        let count = count.min(63);
+        let m = Xmm::splat_u64(1 << (63 - count));
+        sub_epi64(xor(srl_epi64(a, count), m), m)
+    }
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic(__m256i::from(a), __m256i::from(b)).into()
+            }
+        };
+    }
+
+    macro_rules! shift {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, count: u32) -> Ymm {
+                $intrinsic(a.into(), _mm_cvtsi32_si128(count as i32)).into()
+            }
+        };
+    }
+
+    binary!(sub_epi8, _mm256_sub_epi8);
+    binary!(sub_epi64, _mm256_sub_epi64);
+    binary!(and, _mm256_and_si256);
+    binary!(or, _mm256_or_si256);
+    binary!(xor, _mm256_xor_si256);
+
+    shift!(sll_epi16, _mm256_sll_epi16);
+    shift!(sll_epi32, _mm256_sll_epi32);
+    shift!(sll_epi64, _mm256_sll_epi64);
+    shift!(srl_epi16, _mm256_srl_epi16);
+    shift!(srl_epi32, _mm256_srl_epi32);
+    shift!(srl_epi64, _mm256_srl_epi64);
+    shift!(sra_epi16, _mm256_sra_epi16);
+    shift!(sra_epi32, _mm256_sra_epi32);
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sll_epi8(a: Ymm, count: u32) -> Ymm {
+        if count >= 8 {
+            return Ymm::default();
+        }
+        and(sll_epi16(a, count), Ymm::splat_u8(0xFF << count))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srl_epi8(a: Ymm, count: u32) -> Ymm {
+        if count >= 8 {
+            return Ymm::default();
+        }
+        and(srl_epi16(a, count), Ymm::splat_u8(0xFF >> count))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sra_epi8(a: Ymm, count: u32) -> Ymm {
+        let count = count.min(7);
+        let m = Ymm::splat_u8(0x80 >> count);
+        sub_epi8(xor(srl_epi8(a, count), m), m)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sra_epi64(a: Ymm, count: u32) -> Ymm {
+        let count = count.min(63);
+        let m = Ymm::splat_u64(1 << (63 - count));
+        sub_epi64(xor(srl_epi64(a, count), m), m)
+    }
+}
+
+/// AVX kernels over [`Ymm`] floats. AVX is only enabled with
+/// AVX2, so callers must check [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic($vec::from(a), $vec::from(b)).into()
+            }
+        };
+    }
+
+    binary!(add_ps, __m256, _mm256_add_ps);
+    binary!(sub_ps, __m256, _mm256_sub_ps);
+    binary!(mul_ps, __m256, _mm256_mul_ps);
+    binary!(div_ps, __m256, _mm256_div_ps);
+    binary!(and_ps, __m256, _mm256_and_ps);
+    binary!(or_ps, __m256, _mm256_or_ps);
+    binary!(xor_ps, __m256, _mm256_xor_ps);
+    binary!(add_pd, __m256d, _mm256_add_pd);
+    binary!(sub_pd, __m256d, _mm256_sub_pd);
+    binary!(mul_pd, __m256d, _mm256_mul_pd);
+    binary!(div_pd, __m256d, _mm256_div_pd);
+    binary!(and_pd, __m256d, _mm256_and_pd);
+    binary!(or_pd, __m256d, _mm256_or_pd);
+    binary!(xor_pd, __m256d, _mm256_xor_pd);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    /// Checks every integer operator of `$name` against scalar lane arithmetic.
+    macro_rules! check_int_ops {
+        ($name:ident, $t:ty, $n:literal) => {{
+            let mut rng = rand::thread_rng();
+            for _ in 0..64 {
+                let a: [$t; $n] = std::array::from_fn(|_| rng.gen());
+                let b: [$t; $n] = std::array::from_fn(|_| rng.gen());
+                let count = rng.gen_range(0..<$t>::BITS + 2);
+                let (va, vb) = ($name::new(a), $name::new(b));
+
+                let expect = |f: &dyn Fn($t, $t) -> $t| -> [$t; $n] { std::array::from_fn(|i| f(a[i], b[i])) };
+                assert_eq!((va + vb).to_array(), expect(&|x, y| x.wrapping_add(y)));
+                assert_eq!((va - vb).to_array(), expect(&|x, y| x.wrapping_sub(y)));
+                assert_eq!((va * vb).to_array(), expect(&|x, y| x.wrapping_mul(y)));
+                assert_eq!((va & vb).to_array(), expect(&|x, y| x & y));
+                assert_eq!((va | vb).to_array(), expect(&|x, y| x | y));
+                assert_eq!((va ^ vb).to_array(), expect(&|x, y| x ^ y));
+                assert_eq!((!va).to_array(), expect(&|x, _| !x));
+                assert_eq!((va << count).to_array(), expect(&|x, _| x.checked_shl(count).unwrap_or(0)));
+                let shr = |x: $t| -> $t {
+                    match x.checked_shr(count) {
+                        Some(v) => v,
+                        // signed lanes fill with the sign bit, unsigned lanes with zero
+                        None => (x >> (<$t>::BITS - 1)) >> 1,
+                    }
+                };
+                assert_eq!((va >> count).to_array(), std::array::from_fn(|i| shr(a[i])));
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xmm_int_ops_match_scalar() {
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                check_int_ops!(I8x16, i8, 16);
+                check_int_ops!(I16x8, i16, 8);
+                check_int_ops!(I32x4, i32, 4);
+                check_int_ops!(I64x2, i64, 2);
+                check_int_ops!(U8x16, u8, 16);
+                check_int_ops!(U16x8, u16, 8);
+                check_int_ops!(U32x4, u32, 4);
+                check_int_ops!(U64x2, u64, 2);
+            });
+        }
+    }
+
+    #[test]
+    fn test_ymm_int_ops_match_scalar() {
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                check_int_ops!(I8x32, i8, 32);
+                check_int_ops!(I16x16, i16, 16);
+                check_int_ops!(I32x8, i32, 8);
+                check_int_ops!(I64x4, i64, 4);
+                check_int_ops!(U8x32, u8, 32);
+                check_int_ops!(U16x16, u16, 16);
+                check_int_ops!(U32x8, u32, 8);
+                check_int_ops!(U64x4, u64, 4);
+            });
+        }
+    }
+
+    #[test]
+    fn test_float_ops() {
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                let a = F32x8::new([1.5, -2.0, 3.25, 0.0, -0.0, 100.0, 1e-3, 7.0]);
+                let b = F32x8::new([0.5, 4.0, -1.0, 2.0, 1.0, -0.5, 1e3, 7.0]);
+                let (a4, b4) = (F32x4::new([1.5, -2.0, 3.25, 0.0]), F32x4::new([0.5, 4.0, -1.0, 2.0]));
+                let (aa, ba) = (a.to_array(), b.to_array());
+                assert_eq!((a + b).to_array(), std::array::from_fn(|i| aa[i] + ba[i]));
+                assert_eq!((a - b).to_array(), std::array::from_fn(|i| aa[i] - ba[i]));
+                assert_eq!((a * b).to_array(), std::array::from_fn(|i| aa[i] * ba[i]));
+                assert_eq!((a / b).to_array(), std::array::from_fn(|i| aa[i] / ba[i]));
+                assert_eq!((a4 * b4).to_array(), [0.75, -8.0, -3.25, 0.0]);
+                assert_eq!((-a4).to_array().map(f32::to_bits), [-1.5f32, 2.0, -3.25, -0.0].map(f32::to_bits));
+
+                let c = F64x4::new([1.0, -2.0, 0.25, 1e300]);
+                let d = F64x4::new([3.0, 0.5, -4.0, 1e300]);
+                assert_eq!((c + d).to_array(), [4.0, -1.5, -3.75, 2e300]);
+                assert_eq!((c / d).to_array(), [1.0 / 3.0, -4.0, -0.0625, 1.0]);
+                assert_eq!((F64x2::new([1.0, 2.0]) - F64x2::splat(0.5)).to_array(), [0.5, 1.5]);
+                assert_eq!((-c).to_array(), [-1.0, 2.0, -0.25, -1e300]);
+            });
+        }
+    }
+
+    #[test]
+    fn test_float_eq_is_lane_wise() {
+        assert_eq!(F32x4::splat(0.0), F32x4::splat(-0.0));
+        assert_eq!(F64x4::splat(0.0), F64x4::splat(-0.0));
+        assert_ne!(F32x8::splat(f32::NAN), F32x8::splat(f32::NAN));
+        assert_ne!(F64x2::new([1.0, f64::NAN]), F64x2::new([1.0, f64::NAN]));
+        assert_ne!(I32x4::new([1, 2, 3, 4]), I32x4::new([1, 2, 3, 5]));
+    }
+
+    #[test]
+    fn test_raw_conversions() {
+        let raw = Xmm::from_i16([10, 200, 30, -32766, 50, 60, 32000, -32000]);
+        let v = I16x8::from(raw);
+        assert_eq!(Xmm::from(v + v), Xmm::from_i16([20, 400, 60, 4, 100, 120, -1536, 1536]));
+        assert_eq!(format!("{:?}", U8x16::splat(1)), format!("U8x16({:?})", [1u8; 16]));
+    }
+}
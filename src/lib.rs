@@ -1,12 +1,133 @@
 use std::fmt;
 
+/// Implements safe typed views over the lanes of a register union.
+///
+/// Every bit pattern is valid for every lane type, so reading any view is sound.
+macro_rules! impl_lane_accessors {
+    ($reg:ident { $($field:ident: [$t:ty; $n:literal] => $as:ident, $as_mut:ident, $lane:ident, $set_lane:ident, $from:ident, $splat:ident;)* }) => {
+        impl $reg {
+            /// Views the register as a slice of `T` lanes.
+            #[inline(always)]
+            pub fn lanes<T: $crate::lane::Lane>(&self) -> &[T] {
+                unsafe {
+                    std::slice::from_raw_parts(
+                        self as *const $reg as *const T,
+                        std::mem::size_of::<$reg>() / std::mem::size_of::<T>(),
+                    )
+                }
+            }
+
+            /// Mutably views the register as a slice of `T` lanes.
+            #[inline(always)]
+            pub fn lanes_mut<T: $crate::lane::Lane>(&mut self) -> &mut [T] {
+                unsafe {
+                    std::slice::from_raw_parts_mut(
+                        self as *mut $reg as *mut T,
+                        std::mem::size_of::<$reg>() / std::mem::size_of::<T>(),
+                    )
+                }
+            }
+
+            /// Creates a register whose `T` lanes are `f(0)`, `f(1)`, …
+            #[inline]
+            pub fn from_fn<T: $crate::lane::Lane>(mut f: impl FnMut(usize) -> T) -> Self {
+                let mut reg = Self::default();
+                for (i, lane) in reg.lanes_mut::<T>().iter_mut().enumerate() {
+                    *lane = f(i);
+                }
+                reg
+            }
+
+            $(
+                #[doc = concat!("Views the register as `[", stringify!($t), "; ", stringify!($n), "]`.")]
+                #[inline(always)]
+                pub fn $as(&self) -> &[$t; $n] {
+                    unsafe { &self.$field }
+                }
+
+                #[doc = concat!("Mutably views the register as `[", stringify!($t), "; ", stringify!($n), "]`.")]
+                #[inline(always)]
+                pub fn $as_mut(&mut self) -> &mut [$t; $n] {
+                    unsafe { &mut self.$field }
+                }
+
+                #[doc = concat!("Returns the `", stringify!($t), "` lane `i`. Panics if `i >= ", stringify!($n), "`.")]
+                #[inline(always)]
+                pub fn $lane(&self, i: usize) -> $t {
+                    self.$as()[i]
+                }
+
+                #[doc = concat!("Sets the `", stringify!($t), "` lane `i` to `v`. Panics if `i >= ", stringify!($n), "`.")]
+                #[inline(always)]
+                pub fn $set_lane(&mut self, i: usize, v: $t) {
+                    self.$as_mut()[i] = v;
+                }
+
+                #[doc = concat!("Creates a register from ", stringify!($n), " `", stringify!($t), "` lanes.")]
+                #[inline(always)]
+                pub const fn $from(lanes: [$t; $n]) -> Self {
+                    $reg { $field: lanes }
+                }
+
+                #[doc = concat!("Creates a register with every `", stringify!($t), "` lane set to `x`.")]
+                #[inline(always)]
+                pub const fn $splat(x: $t) -> Self {
+                    $reg { $field: [x; $n] }
+                }
+            )*
+        }
+    };
+}
+
+/// Implements zero-cost `From` conversions between a register union and the
+/// `core::arch` vector types of the same width.
+macro_rules! impl_arch_conversions {
+    ($reg:ident: $($vec:ident),*) => {
+        $(
+            impl From<$vec> for $reg {
+                #[inline(always)]
+                fn from(v: $vec) -> Self {
+                    unsafe { std::mem::transmute::<$vec, $reg>(v) }
+                }
+            }
+
+            impl From<$reg> for $vec {
+                #[inline(always)]
+                fn from(v: $reg) -> Self {
+                    unsafe { std::mem::transmute::<$reg, $vec>(v) }
+                }
+            }
+        )*
+    };
+}
+
 pub mod xmm;
 pub mod ymm;
+pub mod zmm;
+pub mod aligned_vec;
+pub mod arg;
+pub mod arith;
 pub mod array;
+pub mod bench;
+pub mod chunks;
+pub mod cmp;
+pub mod dispatch;
+pub mod extract;
+pub mod float;
+pub mod lane;
+pub mod lanes;
+pub mod mask;
+pub mod mul;
+pub mod parse;
+pub mod reduce;
+pub mod shift;
+pub mod shuffle;
+
+mod scalar;
 
 pub(crate) fn fmt_as_simd<T: fmt::Display>(f: &mut String, a: &[T], n: usize, w: usize) -> fmt::Result {
-    for i in 0..a.len() {
-        fmt::write(f, format_args!("{:w$}", a[i], w = w))?;
+    for (i, v) in a.iter().enumerate() {
+        fmt::write(f, format_args!("{:w$}", v, w = w))?;
         if i + 1 == n / 2 {
             fmt::write(f, format_args!("    |"))?;
         }
@@ -15,11 +136,24 @@ pub(crate) fn fmt_as_simd<T: fmt::Display>(f: &mut String, a: &[T], n: usize, w:
 }
 
 pub(crate) fn fmt_as_simd_hex<T: fmt::Display + fmt::UpperHex>(f: &mut String, a: &[T], n: usize, w: usize) -> fmt::Result {
-    for i in 0..a.len() {
-        fmt::write(f, format_args!("{:#0w$X} ", a[i], w = w))?;
+    for (i, v) in a.iter().enumerate() {
+        fmt::write(f, format_args!("{:#0w$X} ", v, w = w))?;
         if i + 1 == n / 2 {
             fmt::write(f, format_args!(" |  "))?;
         }
     }
     fmt::write(f, format_args!("\n"))
-}
\ No newline at end of file
+}
+
+/// Debug helper printing a byte slice as a list of hex values.
+pub(crate) struct HexBytes<'a>(pub(crate) &'a [u8]);
+
+impl fmt::Debug for HexBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for b in self.0 {
+            list.entry(&format_args!("{:#04x}", b));
+        }
+        list.finish()
+    }
+}
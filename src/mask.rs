@@ -0,0 +1,434 @@
+//! Lane masks, the result of the [comparisons](crate::cmp).
+//!
+//! A [`Mask<R, T>`] is a register `R` whose `T` lanes are all ones where a
+//! condition holds and all zeros elsewhere. It can be queried as a whole,
+//! turned into one bit per lane like `movemask`, combined with `&`, `|`, `^`
+//! and `!`, and used to pick lanes from two registers.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//!
+//! let a = Xmm::from_i16([1, -2, 3, -4, 5, -6, 7, -8]);
+//! let negative = a.cmp_lt::<i16>(Xmm::splat_i16(0));
+//! assert_eq!(negative.to_bitmask(), 0b1010_1010);
+//! assert_eq!(negative.count(), 4);
+//! assert_eq!(negative.first_set(), Some(1));
+//! assert!(negative.any() && !negative.all());
+//! let abs = negative.select(Xmm::splat_i16(0).sub_wrapping::<i16>(a), a);
+//! assert_eq!(abs, Xmm::from_i16([1, 2, 3, 4, 5, 6, 7, 8]));
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::chunks::Register;
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::lanes::{U64x2, U64x4};
+use crate::xmm::Xmm;
+use crate::ymm::Ymm;
+
+/// Registers a [`Mask`] can live in.
+pub trait MaskRegister: Register {
+    /// Packs the top bit of every `T` lane into an integer, lane 0 in bit 0.
+    #[doc(hidden)]
+    fn movemask<T: Lane>(self) -> u32;
+    /// The bytes of `a` where `self` has its top bit set, else those of `b`.
+    #[doc(hidden)]
+    fn blend(self, a: Self, b: Self) -> Self;
+    #[doc(hidden)]
+    fn and(self, rhs: Self) -> Self;
+    #[doc(hidden)]
+    fn or(self, rhs: Self) -> Self;
+    #[doc(hidden)]
+    fn xor(self, rhs: Self) -> Self;
+    #[doc(hidden)]
+    fn not(self) -> Self;
+}
+
+impl MaskRegister for Xmm {
+    #[inline(always)]
+    fn movemask<T: Lane>(self) -> u32 {
+        select!(Sse2 => sse2::movemask(self, T::BITS), _ => scalar::movemask::<T, _>(self))
+    }
+
+    #[inline(always)]
+    fn blend(self, a: Xmm, b: Xmm) -> Xmm {
+        select!(
+            Sse41 => unsafe { sse41::blend(self, a, b) },
+            Sse2 => sse2::blend(self, a, b),
+            _ => scalar::blend(self, a, b),
+        )
+    }
+
+    #[inline(always)]
+    fn and(self, rhs: Xmm) -> Xmm {
+        (U64x2::from(self) & U64x2::from(rhs)).into()
+    }
+
+    #[inline(always)]
+    fn or(self, rhs: Xmm) -> Xmm {
+        (U64x2::from(self) | U64x2::from(rhs)).into()
+    }
+
+    #[inline(always)]
+    fn xor(self, rhs: Xmm) -> Xmm {
+        (U64x2::from(self) ^ U64x2::from(rhs)).into()
+    }
+
+    #[inline(always)]
+    fn not(self) -> Xmm {
+        (!U64x2::from(self)).into()
+    }
+}
+
+impl MaskRegister for Ymm {
+    #[inline(always)]
+    fn movemask<T: Lane>(self) -> u32 {
+        select!(Avx2 => unsafe { avx2::movemask(self, T::BITS) }, _ => {
+            let (lo, hi) = self.halves();
+            lo.movemask::<T>() | hi.movemask::<T>() << Xmm::lane_count::<T>()
+        })
+    }
+
+    #[inline(always)]
+    fn blend(self, a: Ymm, b: Ymm) -> Ymm {
+        select!(Avx2 => unsafe { avx2::blend(self, a, b) }, _ => {
+            let ((m_lo, m_hi), (a_lo, a_hi), (b_lo, b_hi)) = (self.halves(), a.halves(), b.halves());
+            Ymm::from_halves(m_lo.blend(a_lo, b_lo), m_hi.blend(a_hi, b_hi))
+        })
+    }
+
+    #[inline(always)]
+    fn and(self, rhs: Ymm) -> Ymm {
+        (U64x4::from(self) & U64x4::from(rhs)).into()
+    }
+
+    #[inline(always)]
+    fn or(self, rhs: Ymm) -> Ymm {
+        (U64x4::from(self) | U64x4::from(rhs)).into()
+    }
+
+    #[inline(always)]
+    fn xor(self, rhs: Ymm) -> Ymm {
+        (U64x4::from(self) ^ U64x4::from(rhs)).into()
+    }
+
+    #[inline(always)]
+    fn not(self) -> Ymm {
+        (!U64x4::from(self)).into()
+    }
+}
+
+/// A condition for each `T` lane of a register `R`, see the
+/// [module documentation](self).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Mask<R, T> {
+    reg: R,
+    lane: PhantomData<T>,
+}
+
+impl<R: MaskRegister, T: Lane> Mask<R, T> {
+    /// Wraps a register whose `T` lanes are each all ones or all zeros. For
+    /// other registers the queries only look at the top bit of each lane and
+    /// [`select`](Self::select) at the top bit of each byte.
+    #[inline(always)]
+    pub fn from_register(reg: R) -> Self {
+        Mask { reg, lane: PhantomData }
+    }
+
+    /// Returns the register, with all ones in the set lanes.
+    #[inline(always)]
+    pub fn to_register(self) -> R {
+        self.reg
+    }
+
+    /// A mask with every lane set to `value`.
+    #[inline]
+    pub fn splat(value: bool) -> Self {
+        Self::from_register(R::splat(if value { 0xFFu8 } else { 0 }))
+    }
+
+    /// A mask whose lane `i` is set if `f(i)` is true.
+    #[inline]
+    pub fn from_fn(mut f: impl FnMut(usize) -> bool) -> Self {
+        let mut reg = R::default();
+        let size = std::mem::size_of::<T>();
+        for (i, lane) in reg.lanes_mut::<u8>().chunks_exact_mut(size).enumerate() {
+            lane.fill(if f(i) { 0xFF } else { 0 });
+        }
+        Self::from_register(reg)
+    }
+
+    /// Number of lanes.
+    #[inline(always)]
+    pub fn lane_count(self) -> usize {
+        R::lane_count::<T>()
+    }
+
+    /// Returns true if lane `i` is set. Panics if `i >= self.lane_count()`.
+    #[inline]
+    pub fn test(self, i: usize) -> bool {
+        assert!(i < self.lane_count(), "lane {} out of range for {} {} lanes", i, self.lane_count(), T::NAME);
+        self.to_bitmask() >> i & 1 != 0
+    }
+
+    /// One bit per lane, lane 0 in bit 0, like `_mm_movemask_epi8`,
+    /// `_mm_movemask_ps` and `_mm_movemask_pd`.
+    #[inline(always)]
+    pub fn to_bitmask(self) -> u32 {
+        self.reg.movemask::<T>()
+    }
+
+    /// Returns true if any lane is set.
+    #[inline(always)]
+    pub fn any(self) -> bool {
+        self.to_bitmask() != 0
+    }
+
+    /// Returns true if every lane is set.
+    #[inline(always)]
+    pub fn all(self) -> bool {
+        self.to_bitmask().count_ones() as usize == self.lane_count()
+    }
+
+    /// Number of set lanes.
+    #[inline(always)]
+    pub fn count(self) -> usize {
+        self.to_bitmask().count_ones() as usize
+    }
+
+    /// Index of the lowest set lane.
+    #[inline(always)]
+    pub fn first_set(self) -> Option<usize> {
+        match self.to_bitmask() {
+            0 => None,
+            bits => Some(bits.trailing_zeros() as usize),
+        }
+    }
+
+    /// Takes the lanes of `a` where the mask is set and those of `b`
+    /// elsewhere, like `_mm_blendv_epi8`.
+    #[inline(always)]
+    pub fn select(self, a: R, b: R) -> R {
+        self.reg.blend(a, b)
+    }
+}
+
+impl<T: Lane> From<Mask<Xmm, T>> for Xmm {
+    #[inline(always)]
+    fn from(mask: Mask<Xmm, T>) -> Xmm {
+        mask.reg
+    }
+}
+
+impl<T: Lane> From<Mask<Ymm, T>> for Ymm {
+    #[inline(always)]
+    fn from(mask: Mask<Ymm, T>) -> Ymm {
+        mask.reg
+    }
+}
+
+macro_rules! mask_binop {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $f:ident) => {
+        impl<R: MaskRegister, T: Lane> $trait for Mask<R, T> {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, rhs: Self) -> Self {
+                Self::from_register(self.reg.$f(rhs.reg))
+            }
+        }
+
+        impl<R: MaskRegister, T: Lane> $assign_trait for Mask<R, T> {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $trait::$method(*self, rhs);
+            }
+        }
+    };
+}
+
+mask_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, and);
+mask_binop!(BitOr, bitor, BitOrAssign, bitor_assign, or);
+mask_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, xor);
+
+impl<R: MaskRegister, T: Lane> Not for Mask<R, T> {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        Self::from_register(self.reg.not())
+    }
+}
+
+impl<R: MaskRegister, T: Lane> fmt::Debug for Mask<R, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = self.to_bitmask();
+        write!(f, "Mask<{}>", T::NAME)?;
+        f.debug_list().entries((0..self.lane_count()).map(|i| bits >> i & 1 != 0)).finish()
+    }
+}
+
+/// Portable kernels over any register.
+mod scalar {
+    use crate::chunks::Register;
+    use crate::lane::Lane;
+
+    #[inline]
+    pub fn movemask<T: Lane, R: Register>(m: R) -> u32 {
+        let size = std::mem::size_of::<T>();
+        let bytes = m.lanes::<u8>();
+        (0..R::lane_count::<T>()).map(|i| ((bytes[i * size + size - 1] >> 7) as u32) << i).sum()
+    }
+
+    #[inline]
+    pub fn blend<R: Register>(mut m: R, a: R, b: R) -> R {
+        for ((m, &a), &b) in m.lanes_mut::<u8>().iter_mut().zip(a.lanes::<u8>()).zip(b.lanes::<u8>()) {
+            *m = if *m & 0x80 != 0 { a } else { b };
+        }
+        m
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    /// Signed saturation keeps 16-bit lanes of all ones or zeros as such
+    /// when packing them to bytes.
+    #[inline(always)]
+    pub fn movemask(m: Xmm, bits: u32) -> u32 {
+        unsafe {
+            let m = __m128i::from(m);
+            (match bits {
+                8 => _mm_movemask_epi8(m),
+                16 => _mm_movemask_epi8(_mm_packs_epi16(m, _mm_setzero_si128())),
+                32 => _mm_movemask_ps(_mm_castsi128_ps(m)),
+                _ => _mm_movemask_pd(_mm_castsi128_pd(m)),
+            }) as u32
+        }
+    }
+
+    /// Extends the top bit of every byte of the mask, then merges bitwise.
+    #[inline(always)]
+    pub fn blend(m: Xmm, a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let m = _mm_cmplt_epi8(m.into(), _mm_setzero_si128());
+            _mm_or_si128(_mm_and_si128(m, a.into()), _mm_andnot_si128(m, b.into())).into()
+        }
+    }
+}
+
+/// SSE4.1 kernels over [`Xmm`]. Callers must check
+/// [`has_sse41`](crate::dispatch::has_sse41) first.
+#[cfg(simd_x86)]
+mod sse41 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    pub unsafe fn blend(m: Xmm, a: Xmm, b: Xmm) -> Xmm {
+        _mm_blendv_epi8(b.into(), a.into(), m.into()).into()
+    }
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    /// Packing works within each 128-bit half, so for 16-bit lanes the bits
+    /// of the high half land at 16..24 and are moved down to 8..16.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn movemask(m: Ymm, bits: u32) -> u32 {
+        let m = __m256i::from(m);
+        match bits {
+            8 => _mm256_movemask_epi8(m) as u32,
+            16 => {
+                let bytes = _mm256_movemask_epi8(_mm256_packs_epi16(m, _mm256_setzero_si256())) as u32;
+                bytes & 0xFF | bytes >> 8 & 0xFF00
+            }
+            32 => _mm256_movemask_ps(_mm256_castsi256_ps(m)) as u32,
+            _ => _mm256_movemask_pd(_mm256_castsi256_pd(m)) as u32,
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn blend(m: Ymm, a: Ymm, b: Ymm) -> Ymm {
+        _mm256_blendv_epi8(b.into(), a.into(), m.into()).into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    macro_rules! check_mask {
+        ($reg:ident, $t:ty) => {{
+            let mut rng = rand::thread_rng();
+            let n = $reg::lane_count::<$t>();
+            for _ in 0..64 {
+                let set: Vec<bool> = (0..n).map(|_| rng.gen_bool(0.3)).collect();
+                let other: Vec<bool> = (0..n).map(|_| rng.gen()).collect();
+                let bits: u32 = set.iter().enumerate().map(|(i, &s)| (s as u32) << i).sum();
+                let a = $reg::from_fn::<u8>(|_| rng.gen());
+                let b = $reg::from_fn::<u8>(|_| rng.gen());
+                let picked = $reg::from_fn::<$t>(|i| if set[i] { a.lanes::<$t>()[i] } else { b.lanes::<$t>()[i] });
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        let m = Mask::<$reg, $t>::from_fn(|i| set[i]);
+                        let o = Mask::<$reg, $t>::from_fn(|i| other[i]);
+                        let at = format!("{} {} at {}", stringify!($reg), stringify!($t), isa);
+                        assert_eq!(m.to_bitmask(), bits, "{}", at);
+                        assert_eq!(m.count(), bits.count_ones() as usize, "{}", at);
+                        assert_eq!(m.any(), bits != 0, "{}", at);
+                        assert_eq!(m.all(), bits.count_ones() as usize == n, "{}", at);
+                        assert_eq!(m.first_set(), set.iter().position(|&s| s), "{}", at);
+                        assert_eq!(m.select(a, b), picked, "{}", at);
+                        assert_eq!((m & o).to_bitmask(), bits & o.to_bitmask(), "{}", at);
+                        assert_eq!((m | o).to_bitmask(), bits | o.to_bitmask(), "{}", at);
+                        assert_eq!((m ^ o).to_bitmask(), bits ^ o.to_bitmask(), "{}", at);
+                        assert_eq!((!m).to_bitmask(), !bits & (u64::MAX >> (64 - n)) as u32, "{}", at);
+                        assert!(Mask::<$reg, $t>::splat(true).all() && !Mask::<$reg, $t>::splat(false).any(), "{}", at);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xmm_mask() {
+        check_mask!(Xmm, u8);
+        check_mask!(Xmm, i16);
+        check_mask!(Xmm, u32);
+        check_mask!(Xmm, f64);
+    }
+
+    #[test]
+    fn test_ymm_mask() {
+        check_mask!(Ymm, i8);
+        check_mask!(Ymm, u16);
+        check_mask!(Ymm, f32);
+        check_mask!(Ymm, i64);
+    }
+
+    #[test]
+    fn test_mask_debug() {
+        let m = Mask::<Xmm, f64>::from_fn(|i| i == 1);
+        assert_eq!(format!("{:?}", m), "Mask<f64>[false, true]");
+        assert!(m.test(1) && !m.test(0));
+        assert_eq!(Xmm::from(m), Xmm::from_u64([0, u64::MAX]));
+    }
+}
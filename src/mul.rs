@@ -0,0 +1,771 @@
+//! Low, high and widening integer multiplication for every lane width.
+//!
+//! The full product of two `N`-bit lanes is `2N` bits wide:
+//!
+//! - [`mul_lo`](Xmm::mul_lo) keeps the low `N` bits of every product (the
+//!   wrapping product),
+//! - [`mul_hi`](Xmm::mul_hi) keeps the high `N` bits,
+//! - [`widening_mul`](Xmm::widening_mul) keeps all `2N` bits and returns two
+//!   registers: the first holds the products of the lower half of the lanes,
+//!   the second those of the upper half, in lane order. 64-bit lanes widen to
+//!   a `[low, high]` pair of 64-bit lanes per product.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//!
+//! let a = Xmm::from_i16([10, 3000, -2000, 42, -5000, 8, 10000, -60]);
+//! let b = Xmm::from_i16([-5, 100, -9000, 1000, 25000, 16384, 3500, 6000]);
+//! let (lo, hi) = a.widening_mul::<i16>(b);
+//! assert_eq!(lo.as_i32(), &[-50, 300_000, 18_000_000, 42_000]);
+//! assert_eq!(hi.as_i32(), &[-125_000_000, 131_072, 35_000_000, -360_000]);
+//! ```
+//!
+//! Neither SSE2 nor AVX2 multiplies 8-bit lanes, so those are widened to 16
+//! bits first. SSE2 has no signed 32-bit or any 64-bit multiply, so those are
+//! built from the unsigned 32×32→64 `_mm_mul_epu32`: signed high halves are
+//! corrected with `hi(a * b) = hi_u(a * b) - (a < 0 ? b : 0) - (b < 0 ? a : 0)`,
+//! and 64×64→128 products are assembled from four 32-bit partial products.
+//! With SSE4.1, 32-bit lanes use the native `_mm_mullo_epi32` and signed
+//! `_mm_mul_epi32` instead. The portable kernels multiply lane by lane in the
+//! double-width type.
+
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::xmm::Xmm;
+use crate::ymm::{by_halves, Ymm};
+
+/// Integer lane types supported by the multiplication API.
+pub trait MulLane: Lane {
+    /// Scalar reference: the full product of `a` and `b` split into its low
+    /// and high halves, each reinterpreted as `Self`.
+    fn mul_full(a: Self, b: Self) -> (Self, Self);
+
+    #[doc(hidden)]
+    fn mul_lo_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn mul_hi_xmm(a: Xmm, b: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn widening_mul_xmm(a: Xmm, b: Xmm) -> (Xmm, Xmm);
+    #[doc(hidden)]
+    fn mul_lo_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn mul_hi_ymm(a: Ymm, b: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn widening_mul_ymm(a: Ymm, b: Ymm) -> (Ymm, Ymm);
+}
+
+/// `$xmm` is the module of x86 [`Xmm`] kernels for the type. [`Ymm`] runs
+/// them on each half without AVX2.
+macro_rules! impl_mul_lane {
+    ($t:ty, $wide:ty, $xmm:ident, $lo:ident, $hi:ident, $widening:ident) => {
+        impl MulLane for $t {
+            #[inline]
+            fn mul_full(a: Self, b: Self) -> (Self, Self) {
+                let p = (a as $wide) * (b as $wide);
+                (p as Self, (p >> <$t>::BITS) as Self)
+            }
+
+            #[inline(always)]
+            fn mul_lo_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => $xmm::$lo(a, b), _ => scalar::mul_lo::<$t, _>(a, b))
+            }
+
+            #[inline(always)]
+            fn mul_hi_xmm(a: Xmm, b: Xmm) -> Xmm {
+                select!(Sse2 => $xmm::$hi(a, b), _ => scalar::mul_hi::<$t, _>(a, b))
+            }
+
+            #[inline(always)]
+            fn widening_mul_xmm(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+                select!(Sse2 => $xmm::$widening(a, b), _ => scalar::widening_mul::<$t, _>(a, b))
+            }
+
+            #[inline(always)]
+            fn mul_lo_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$lo(a, b) }, _ => by_halves(a, b, Self::mul_lo_xmm))
+            }
+
+            #[inline(always)]
+            fn mul_hi_ymm(a: Ymm, b: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$hi(a, b) }, _ => by_halves(a, b, Self::mul_hi_xmm))
+            }
+
+            #[inline(always)]
+            fn widening_mul_ymm(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+                select!(Avx2 => unsafe { avx2::$widening(a, b) }, _ => {
+                    let (a_lo, a_hi) = a.halves();
+                    let (b_lo, b_hi) = b.halves();
+                    let (p0, p1) = Self::widening_mul_xmm(a_lo, b_lo);
+                    let (p2, p3) = Self::widening_mul_xmm(a_hi, b_hi);
+                    (Ymm::from_halves(p0, p1), Ymm::from_halves(p2, p3))
+                })
+            }
+        }
+    };
+}
+
+impl_mul_lane!(i8, i16, sse2, mullo_epi8, mulhi_epi8, widening_mul_epi8);
+impl_mul_lane!(u8, u16, sse2, mullo_epi8, mulhi_epu8, widening_mul_epu8);
+impl_mul_lane!(i16, i32, sse2, mullo_epi16, mulhi_epi16, widening_mul_epi16);
+impl_mul_lane!(u16, u32, sse2, mullo_epi16, mulhi_epu16, widening_mul_epu16);
+impl_mul_lane!(i32, i64, sse41, mullo_epi32, mulhi_epi32, widening_mul_epi32);
+impl_mul_lane!(u32, u64, sse41, mullo_epi32, mulhi_epu32, widening_mul_epu32);
+impl_mul_lane!(i64, i128, sse2, mullo_epi64, mulhi_epi64, widening_mul_epi64);
+impl_mul_lane!(u64, u128, sse2, mullo_epi64, mulhi_epu64, widening_mul_epu64);
+
+macro_rules! impl_mul_methods {
+    ($reg:ident, $lo:ident, $hi:ident, $widening:ident) => {
+        impl $reg {
+            /// Multiplies packed `T` lanes and keeps the low half of each product.
+            #[inline(always)]
+            pub fn mul_lo<T: MulLane>(self, rhs: $reg) -> $reg {
+                T::$lo(self, rhs)
+            }
+
+            /// Multiplies packed `T` lanes and keeps the high half of each product.
+            #[inline(always)]
+            pub fn mul_hi<T: MulLane>(self, rhs: $reg) -> $reg {
+                T::$hi(self, rhs)
+            }
+
+            /// Multiplies packed `T` lanes into double-width products, see the
+            /// [module documentation](crate::mul) for the lane order.
+            #[inline(always)]
+            pub fn widening_mul<T: MulLane>(self, rhs: $reg) -> ($reg, $reg) {
+                T::$widening(self, rhs)
+            }
+        }
+    };
+}
+
+impl_mul_methods!(Xmm, mul_lo_xmm, mul_hi_xmm, widening_mul_xmm);
+impl_mul_methods!(Ymm, mul_lo_ymm, mul_hi_ymm, widening_mul_ymm);
+
+/// Portable kernels over any register, computed lane by lane from
+/// [`MulLane::mul_full`].
+mod scalar {
+    use super::MulLane;
+    use crate::chunks::Register;
+    use crate::scalar::zip;
+
+    #[inline]
+    pub fn mul_lo<T: MulLane, R: Register>(a: R, b: R) -> R {
+        zip(a, b, |x: T, y| T::mul_full(x, y).0)
+    }
+
+    #[inline]
+    pub fn mul_hi<T: MulLane, R: Register>(a: R, b: R) -> R {
+        zip(a, b, |x: T, y| T::mul_full(x, y).1)
+    }
+
+    /// Product `i` goes to lanes `2i` (low half) and `2i + 1` (high half) of
+    /// the first register for the lower half of the lanes, of the second one
+    /// for the upper half.
+    #[inline]
+    pub fn widening_mul<T: MulLane, R: Register>(a: R, b: R) -> (R, R) {
+        let (x, y) = (a.lanes::<T>(), b.lanes::<T>());
+        let half = x.len() / 2;
+        let mut out = [R::default(); 2];
+        for (i, (&x, &y)) in x.iter().zip(y).enumerate() {
+            let (lo, hi) = T::mul_full(x, y);
+            let lanes = out[i / half].lanes_mut::<T>();
+            lanes[2 * (i % half)] = lo;
+            lanes[2 * (i % half) + 1] = hi;
+        }
+        (out[0], out[1])
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic(a.into(), b.into()) }.into()
+            }
+        };
+    }
+
+    binary!(mullo_epi16, _mm_mullo_epi16);
+    binary!(mulhi_epi16, _mm_mulhi_epi16);
+    binary!(mulhi_epu16, _mm_mulhi_epu16);
+
+    /// Multiplies the even and odd bytes as 16-bit lanes and merges the low
+    /// bytes of the products.
+    #[inline(always)]
+    pub fn mullo_epi8(a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let (a, b): (__m128i, __m128i) = (a.into(), b.into());
+            let even = _mm_mullo_epi16(a, b);
+            let odd = _mm_mullo_epi16(_mm_srli_epi16::<8>(a), _mm_srli_epi16::<8>(b));
+            _mm_or_si128(_mm_and_si128(even, _mm_set1_epi16(0x00FF)), _mm_slli_epi16::<8>(odd)).into()
+        }
+    }
+
+    /// Sign-extends bytes to 16 bits by unpacking each byte into the high
+    /// byte of a 16-bit lane and shifting it back down arithmetically.
+    #[inline(always)]
+    pub fn widening_mul_epi8(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let (a, b): (__m128i, __m128i) = (a.into(), b.into());
+            let a_lo = _mm_srai_epi16::<8>(_mm_unpacklo_epi8(a, a));
+            let a_hi = _mm_srai_epi16::<8>(_mm_unpackhi_epi8(a, a));
+            let b_lo = _mm_srai_epi16::<8>(_mm_unpacklo_epi8(b, b));
+            let b_hi = _mm_srai_epi16::<8>(_mm_unpackhi_epi8(b, b));
+            (_mm_mullo_epi16(a_lo, b_lo).into(), _mm_mullo_epi16(a_hi, b_hi).into())
+        }
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epu8(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let (a, b): (__m128i, __m128i) = (a.into(), b.into());
+            let zero = _mm_setzero_si128();
+            let lo = _mm_mullo_epi16(_mm_unpacklo_epi8(a, zero), _mm_unpacklo_epi8(b, zero));
+            let hi = _mm_mullo_epi16(_mm_unpackhi_epi8(a, zero), _mm_unpackhi_epi8(b, zero));
+            (lo.into(), hi.into())
+        }
+    }
+
+    /// The high bytes of `i8 * i8` always fit in `i8`, so a signed pack is exact.
+    #[inline(always)]
+    pub fn mulhi_epi8(a: Xmm, b: Xmm) -> Xmm {
+        let (lo, hi) = widening_mul_epi8(a, b);
+        unsafe { _mm_packs_epi16(_mm_srai_epi16::<8>(lo.into()), _mm_srai_epi16::<8>(hi.into())) }.into()
+    }
+
+    #[inline(always)]
+    pub fn mulhi_epu8(a: Xmm, b: Xmm) -> Xmm {
+        let (lo, hi) = widening_mul_epu8(a, b);
+        unsafe { _mm_packus_epi16(_mm_srli_epi16::<8>(lo.into()), _mm_srli_epi16::<8>(hi.into())) }.into()
+    }
+
+    #[inline(always)]
+    fn widening_mul_epx16(lo: __m128i, hi: __m128i) -> (Xmm, Xmm) {
+        unsafe { (_mm_unpacklo_epi16(lo, hi).into(), _mm_unpackhi_epi16(lo, hi).into()) }
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epi16(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe { widening_mul_epx16(_mm_mullo_epi16(a.into(), b.into()), _mm_mulhi_epi16(a.into(), b.into())) }
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epu16(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe { widening_mul_epx16(_mm_mullo_epi16(a.into(), b.into()), _mm_mulhi_epu16(a.into(), b.into())) }
+    }
+
+    /// `_mm_mullo_epi32` needs SSE4.1: multiply the even and odd lanes into
+    /// 64-bit products with `_mm_mul_epu32` and gather the low halves.
+    #[inline(always)]
+    pub fn mullo_epi32(a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let (even, odd) = products_epu32(a.into(), b.into());
+            _mm_unpacklo_epi32(_mm_shuffle_epi32::<0b00_00_10_00>(even), _mm_shuffle_epi32::<0b00_00_10_00>(odd)).into()
+        }
+    }
+
+    /// Unsigned 64-bit products of the even lanes `[p0, p2]` and the odd
+    /// lanes `[p1, p3]`.
+    #[inline(always)]
+    unsafe fn products_epu32(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+        let even = _mm_mul_epu32(a, b);
+        let odd = _mm_mul_epu32(_mm_srli_epi64::<32>(a), _mm_srli_epi64::<32>(b));
+        (even, odd)
+    }
+
+    /// Signed products from the unsigned ones: subtract `(a < 0 ? b : 0) +
+    /// (b < 0 ? a : 0)` from the high half of every product.
+    #[inline(always)]
+    unsafe fn products_epi32(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+        let (even, odd) = products_epu32(a, b);
+        let fix = _mm_add_epi32(
+            _mm_and_si128(_mm_srai_epi32::<31>(a), b),
+            _mm_and_si128(_mm_srai_epi32::<31>(b), a),
+        );
+        let even = _mm_sub_epi64(even, _mm_slli_epi64::<32>(fix));
+        let odd = _mm_sub_epi64(odd, _mm_and_si128(fix, _mm_set1_epi64x(0xFFFF_FFFF_0000_0000u64 as i64)));
+        (even, odd)
+    }
+
+    #[inline(always)]
+    pub(super) fn high_halves_epx32(even: __m128i, odd: __m128i) -> Xmm {
+        unsafe {
+            _mm_or_si128(
+                _mm_srli_epi64::<32>(even),
+                _mm_and_si128(odd, _mm_set1_epi64x(0xFFFF_FFFF_0000_0000u64 as i64)),
+            )
+            .into()
+        }
+    }
+
+    #[inline(always)]
+    pub fn mulhi_epi32(a: Xmm, b: Xmm) -> Xmm {
+        let (even, odd) = unsafe { products_epi32(a.into(), b.into()) };
+        high_halves_epx32(even, odd)
+    }
+
+    #[inline(always)]
+    pub fn mulhi_epu32(a: Xmm, b: Xmm) -> Xmm {
+        let (even, odd) = unsafe { products_epu32(a.into(), b.into()) };
+        high_halves_epx32(even, odd)
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epi32(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let (even, odd) = products_epi32(a.into(), b.into());
+            (_mm_unpacklo_epi64(even, odd).into(), _mm_unpackhi_epi64(even, odd).into())
+        }
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epu32(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let (even, odd) = products_epu32(a.into(), b.into());
+            (_mm_unpacklo_epi64(even, odd).into(), _mm_unpackhi_epi64(even, odd).into())
+        }
+    }
+
+    /// `lo(a) * lo(b) + ((hi(a) * lo(b) + lo(a) * hi(b)) << 32)` on 32-bit halves.
+    #[inline(always)]
+    pub fn mullo_epi64(a: Xmm, b: Xmm) -> Xmm {
+        unsafe {
+            let (a, b): (__m128i, __m128i) = (a.into(), b.into());
+            let cross = _mm_add_epi64(
+                _mm_mul_epu32(_mm_srli_epi64::<32>(a), b),
+                _mm_mul_epu32(a, _mm_srli_epi64::<32>(b)),
+            );
+            _mm_add_epi64(_mm_mul_epu32(a, b), _mm_slli_epi64::<32>(cross)).into()
+        }
+    }
+
+    /// Full unsigned 64×64→128 products `(low, high)` from four 32×32→64
+    /// partial products. The middle column sums three values below 2^32 each,
+    /// so it cannot overflow 64 bits.
+    #[inline(always)]
+    unsafe fn products_epu64(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+        let mask = _mm_set1_epi64x(0xFFFF_FFFF);
+        let a_hi = _mm_srli_epi64::<32>(a);
+        let b_hi = _mm_srli_epi64::<32>(b);
+        let ll = _mm_mul_epu32(a, b);
+        let lh = _mm_mul_epu32(a, b_hi);
+        let hl = _mm_mul_epu32(a_hi, b);
+        let hh = _mm_mul_epu32(a_hi, b_hi);
+
+        let mid = _mm_add_epi64(
+            _mm_srli_epi64::<32>(ll),
+            _mm_add_epi64(_mm_and_si128(lh, mask), _mm_and_si128(hl, mask)),
+        );
+        let hi = _mm_add_epi64(
+            _mm_add_epi64(hh, _mm_srli_epi64::<32>(mid)),
+            _mm_add_epi64(_mm_srli_epi64::<32>(lh), _mm_srli_epi64::<32>(hl)),
+        );
+        let lo = _mm_or_si128(_mm_slli_epi64::<32>(mid), _mm_and_si128(ll, mask));
+        (lo, hi)
+    }
+
+    #[inline(always)]
+    unsafe fn products_epi64(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+        let (lo, hi) = products_epu64(a, b);
+        let zero = _mm_setzero_si128();
+        let a_neg = _mm_sub_epi64(zero, _mm_srli_epi64::<63>(a));
+        let b_neg = _mm_sub_epi64(zero, _mm_srli_epi64::<63>(b));
+        let hi = _mm_sub_epi64(_mm_sub_epi64(hi, _mm_and_si128(a_neg, b)), _mm_and_si128(b_neg, a));
+        (lo, hi)
+    }
+
+    #[inline(always)]
+    pub fn mulhi_epi64(a: Xmm, b: Xmm) -> Xmm {
+        unsafe { products_epi64(a.into(), b.into()).1.into() }
+    }
+
+    #[inline(always)]
+    pub fn mulhi_epu64(a: Xmm, b: Xmm) -> Xmm {
+        unsafe { products_epu64(a.into(), b.into()).1.into() }
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epi64(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let (lo, hi) = products_epi64(a.into(), b.into());
+            (_mm_unpacklo_epi64(lo, hi).into(), _mm_unpackhi_epi64(lo, hi).into())
+        }
+    }
+
+    #[inline(always)]
+    pub fn widening_mul_epu64(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let (lo, hi) = products_epu64(a.into(), b.into());
+            (_mm_unpacklo_epi64(lo, hi).into(), _mm_unpackhi_epi64(lo, hi).into())
+        }
+    }
+}
+
+/// SSE4.1 kernels over [`Xmm`] for 32-bit lanes, falling back to [`sse2`]
+/// when the dispatch level is lower. Kernels without an SSE4.1 form are
+/// re-exported from [`sse2`].
+#[cfg(simd_x86)]
+mod sse41 {
+    pub use super::sse2::*;
+
+    use crate::dispatch::has_sse41;
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! dispatched {
+        ($name:ident -> $out:ty) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> $out {
+                if has_sse41() {
+                    unsafe { native::$name(a, b) }
+                } else {
+                    super::sse2::$name(a, b)
+                }
+            }
+        };
+    }
+
+    dispatched!(mullo_epi32 -> Xmm);
+    dispatched!(mulhi_epi32 -> Xmm);
+    dispatched!(widening_mul_epi32 -> (Xmm, Xmm));
+
+    mod native {
+        use super::*;
+
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        pub unsafe fn mullo_epi32(a: Xmm, b: Xmm) -> Xmm {
+            _mm_mullo_epi32(a.into(), b.into()).into()
+        }
+
+        /// Signed 64-bit products of the even lanes `[p0, p2]` and the odd
+        /// lanes `[p1, p3]`. `_mm_mul_epi32` reads the low 32 bits of each
+        /// 64-bit lane, so shifting the odd lanes down logically is enough.
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        unsafe fn products_epi32(a: __m128i, b: __m128i) -> (__m128i, __m128i) {
+            let even = _mm_mul_epi32(a, b);
+            let odd = _mm_mul_epi32(_mm_srli_epi64::<32>(a), _mm_srli_epi64::<32>(b));
+            (even, odd)
+        }
+
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        pub unsafe fn mulhi_epi32(a: Xmm, b: Xmm) -> Xmm {
+            let (even, odd) = products_epi32(a.into(), b.into());
+            super::super::sse2::high_halves_epx32(even, odd)
+        }
+
+        #[inline]
+        #[target_feature(enable = "sse4.1")]
+        pub unsafe fn widening_mul_epi32(a: Xmm, b: Xmm) -> (Xmm, Xmm) {
+            let (even, odd) = products_epi32(a.into(), b.into());
+            (_mm_unpacklo_epi64(even, odd).into(), _mm_unpackhi_epi64(even, odd).into())
+        }
+    }
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+///
+/// Unpack and pack instructions work within each 128-bit half, so widening
+/// results are put back in lane order with `_mm256_permute2x128_si256` or
+/// `_mm256_permute4x64_epi64`.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic(a.into(), b.into()).into()
+            }
+        };
+    }
+
+    binary!(mullo_epi16, _mm256_mullo_epi16);
+    binary!(mulhi_epi16, _mm256_mulhi_epi16);
+    binary!(mulhi_epu16, _mm256_mulhi_epu16);
+    binary!(mullo_epi32, _mm256_mullo_epi32);
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn in_lane_order(x: __m256i, y: __m256i) -> (Ymm, Ymm) {
+        (_mm256_permute2x128_si256::<0x20>(x, y).into(), _mm256_permute2x128_si256::<0x31>(x, y).into())
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mullo_epi8(a: Ymm, b: Ymm) -> Ymm {
+        let (a, b): (__m256i, __m256i) = (a.into(), b.into());
+        let even = _mm256_mullo_epi16(a, b);
+        let odd = _mm256_mullo_epi16(_mm256_srli_epi16::<8>(a), _mm256_srli_epi16::<8>(b));
+        _mm256_or_si256(_mm256_and_si256(even, _mm256_set1_epi16(0x00FF)), _mm256_slli_epi16::<8>(odd)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epi8(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let ((a_lo, a_hi), (b_lo, b_hi)) = (a.halves(), b.halves());
+        let lo = _mm256_mullo_epi16(_mm256_cvtepi8_epi16(a_lo.into()), _mm256_cvtepi8_epi16(b_lo.into()));
+        let hi = _mm256_mullo_epi16(_mm256_cvtepi8_epi16(a_hi.into()), _mm256_cvtepi8_epi16(b_hi.into()));
+        (lo.into(), hi.into())
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epu8(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let ((a_lo, a_hi), (b_lo, b_hi)) = (a.halves(), b.halves());
+        let lo = _mm256_mullo_epi16(_mm256_cvtepu8_epi16(a_lo.into()), _mm256_cvtepu8_epi16(b_lo.into()));
+        let hi = _mm256_mullo_epi16(_mm256_cvtepu8_epi16(a_hi.into()), _mm256_cvtepu8_epi16(b_hi.into()));
+        (lo.into(), hi.into())
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mulhi_epi8(a: Ymm, b: Ymm) -> Ymm {
+        let (lo, hi) = widening_mul_epi8(a, b);
+        let packed = _mm256_packs_epi16(_mm256_srai_epi16::<8>(lo.into()), _mm256_srai_epi16::<8>(hi.into()));
+        _mm256_permute4x64_epi64::<0b11_01_10_00>(packed).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mulhi_epu8(a: Ymm, b: Ymm) -> Ymm {
+        let (lo, hi) = widening_mul_epu8(a, b);
+        let packed = _mm256_packus_epi16(_mm256_srli_epi16::<8>(lo.into()), _mm256_srli_epi16::<8>(hi.into()));
+        _mm256_permute4x64_epi64::<0b11_01_10_00>(packed).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epi16(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let (a, b): (__m256i, __m256i) = (a.into(), b.into());
+        let (lo, hi) = (_mm256_mullo_epi16(a, b), _mm256_mulhi_epi16(a, b));
+        in_lane_order(_mm256_unpacklo_epi16(lo, hi), _mm256_unpackhi_epi16(lo, hi))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epu16(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let (a, b): (__m256i, __m256i) = (a.into(), b.into());
+        let (lo, hi) = (_mm256_mullo_epi16(a, b), _mm256_mulhi_epu16(a, b));
+        in_lane_order(_mm256_unpacklo_epi16(lo, hi), _mm256_unpackhi_epi16(lo, hi))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn high_halves_epx32(even: __m256i, odd: __m256i) -> Ymm {
+        let mask = _mm256_set1_epi64x(0xFFFF_FFFF_0000_0000u64 as i64);
+        _mm256_or_si256(_mm256_srli_epi64::<32>(even), _mm256_and_si256(odd, mask)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn products_epi32(a: Ymm, b: Ymm) -> (__m256i, __m256i) {
+        let (a, b): (__m256i, __m256i) = (a.into(), b.into());
+        let even = _mm256_mul_epi32(a, b);
+        let odd = _mm256_mul_epi32(_mm256_srli_epi64::<32>(a), _mm256_srli_epi64::<32>(b));
+        (even, odd)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn products_epu32(a: Ymm, b: Ymm) -> (__m256i, __m256i) {
+        let (a, b): (__m256i, __m256i) = (a.into(), b.into());
+        let even = _mm256_mul_epu32(a, b);
+        let odd = _mm256_mul_epu32(_mm256_srli_epi64::<32>(a), _mm256_srli_epi64::<32>(b));
+        (even, odd)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mulhi_epi32(a: Ymm, b: Ymm) -> Ymm {
+        let (even, odd) = products_epi32(a, b);
+        high_halves_epx32(even, odd)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mulhi_epu32(a: Ymm, b: Ymm) -> Ymm {
+        let (even, odd) = products_epu32(a, b);
+        high_halves_epx32(even, odd)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epi32(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let (even, odd) = products_epi32(a, b);
+        in_lane_order(_mm256_unpacklo_epi64(even, odd), _mm256_unpackhi_epi64(even, odd))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epu32(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let (even, odd) = products_epu32(a, b);
+        in_lane_order(_mm256_unpacklo_epi64(even, odd), _mm256_unpackhi_epi64(even, odd))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mullo_epi64(a: Ymm, b: Ymm) -> Ymm {
+        let (a, b): (__m256i, __m256i) = (a.into(), b.into());
+        let cross = _mm256_add_epi64(
+            _mm256_mul_epu32(_mm256_srli_epi64::<32>(a), b),
+            _mm256_mul_epu32(a, _mm256_srli_epi64::<32>(b)),
+        );
+        _mm256_add_epi64(_mm256_mul_epu32(a, b), _mm256_slli_epi64::<32>(cross)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn products_epu64(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+        let mask = _mm256_set1_epi64x(0xFFFF_FFFF);
+        let a_hi = _mm256_srli_epi64::<32>(a);
+        let b_hi = _mm256_srli_epi64::<32>(b);
+        let ll = _mm256_mul_epu32(a, b);
+        let lh = _mm256_mul_epu32(a, b_hi);
+        let hl = _mm256_mul_epu32(a_hi, b);
+        let hh = _mm256_mul_epu32(a_hi, b_hi);
+
+        let mid = _mm256_add_epi64(
+            _mm256_srli_epi64::<32>(ll),
+            _mm256_add_epi64(_mm256_and_si256(lh, mask), _mm256_and_si256(hl, mask)),
+        );
+        let hi = _mm256_add_epi64(
+            _mm256_add_epi64(hh, _mm256_srli_epi64::<32>(mid)),
+            _mm256_add_epi64(_mm256_srli_epi64::<32>(lh), _mm256_srli_epi64::<32>(hl)),
+        );
+        let lo = _mm256_or_si256(_mm256_slli_epi64::<32>(mid), _mm256_and_si256(ll, mask));
+        (lo, hi)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn products_epi64(a: __m256i, b: __m256i) -> (__m256i, __m256i) {
+        let (lo, hi) = products_epu64(a, b);
+        let zero = _mm256_setzero_si256();
+        let a_neg = _mm256_cmpgt_epi64(zero, a);
+        let b_neg = _mm256_cmpgt_epi64(zero, b);
+        let hi = _mm256_sub_epi64(_mm256_sub_epi64(hi, _mm256_and_si256(a_neg, b)), _mm256_and_si256(b_neg, a));
+        (lo, hi)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mulhi_epi64(a: Ymm, b: Ymm) -> Ymm {
+        products_epi64(a.into(), b.into()).1.into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn mulhi_epu64(a: Ymm, b: Ymm) -> Ymm {
+        products_epu64(a.into(), b.into()).1.into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epi64(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let (lo, hi) = products_epi64(a.into(), b.into());
+        in_lane_order(_mm256_unpacklo_epi64(lo, hi), _mm256_unpackhi_epi64(lo, hi))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn widening_mul_epu64(a: Ymm, b: Ymm) -> (Ymm, Ymm) {
+        let (lo, hi) = products_epu64(a.into(), b.into());
+        in_lane_order(_mm256_unpacklo_epi64(lo, hi), _mm256_unpackhi_epi64(lo, hi))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    /// Builds the expected widening result from [`MulLane::mul_full`]: lane
+    /// `i` of the `2N`-bit view is `hi:lo` of product `i`.
+    macro_rules! check_against_scalar {
+        ($reg:ident, $t:ty) => {{
+            let mut rng = rand::thread_rng();
+            let extremes = [<$t>::MIN, <$t>::MAX, 0, 1, <$t>::MIN.wrapping_add(1)];
+            for round in 0..256 {
+                let pick = |rng: &mut rand::rngs::ThreadRng| {
+                    if round % 4 == 0 { extremes[rng.gen_range(0..extremes.len())] } else { rng.gen::<$t>() }
+                };
+                let a = $reg::from_fn::<$t>(|_| pick(&mut rng));
+                let b = $reg::from_fn::<$t>(|_| pick(&mut rng));
+                let (x, y) = (a.lanes::<$t>(), b.lanes::<$t>());
+                let n = x.len();
+
+                let lo = $reg::from_fn::<$t>(|i| <$t>::mul_full(x[i], y[i]).0);
+                let hi = $reg::from_fn::<$t>(|i| <$t>::mul_full(x[i], y[i]).1);
+                assert_eq!(a.mul_lo::<$t>(b), lo, "{} mul_lo", stringify!($t));
+                assert_eq!(a.mul_hi::<$t>(b), hi, "{} mul_hi", stringify!($t));
+
+                let interleaved = |first: usize| {
+                    $reg::from_fn::<$t>(|i| {
+                        let (l, h) = <$t>::mul_full(x[first + i / 2], y[first + i / 2]);
+                        if i % 2 == 0 { l } else { h }
+                    })
+                };
+                let (w0, w1) = a.widening_mul::<$t>(b);
+                assert_eq!(w0, interleaved(0), "{} widening_mul (low lanes)", stringify!($t));
+                assert_eq!(w1, interleaved(n / 2), "{} widening_mul (high lanes)", stringify!($t));
+            }
+        }};
+    }
+
+    #[test]
+    fn test_mul_full_scalar_reference() {
+        assert_eq!(i8::mul_full(-128, -128), (0, 0x40));
+        assert_eq!(u16::mul_full(0xFFFF, 0xFFFF), (1, 0xFFFE));
+        assert_eq!(i32::mul_full(-1, 1), (-1, -1));
+        assert_eq!(u64::mul_full(u64::MAX, 2), (u64::MAX - 1, 1));
+        assert_eq!(i64::mul_full(i64::MIN, -1), (i64::MIN, 0));
+    }
+
+    /// Runs at every dispatch level, so the SSE2 and SSE4.1 kernels and the
+    /// per-half fallback of [`Ymm`] are covered on any CPU that supports AVX2.
+    #[test]
+    fn test_xmm_mul_matches_scalar() {
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                check_against_scalar!(Xmm, i8);
+                check_against_scalar!(Xmm, u8);
+                check_against_scalar!(Xmm, i16);
+                check_against_scalar!(Xmm, u16);
+                check_against_scalar!(Xmm, i32);
+                check_against_scalar!(Xmm, u32);
+                check_against_scalar!(Xmm, i64);
+                check_against_scalar!(Xmm, u64);
+            });
+        }
+    }
+
+    #[test]
+    fn test_ymm_mul_matches_scalar() {
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                check_against_scalar!(Ymm, i8);
+                check_against_scalar!(Ymm, u8);
+                check_against_scalar!(Ymm, i16);
+                check_against_scalar!(Ymm, u16);
+                check_against_scalar!(Ymm, i32);
+                check_against_scalar!(Ymm, u32);
+                check_against_scalar!(Ymm, i64);
+                check_against_scalar!(Ymm, u64);
+            });
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Parsing registers from text.
+//!
+//! [`Xmm`] and [`Ymm`] implement [`FromStr`] for two notations:
+//!
+//! - A lane list with a type prefix, such as
+//!   `i16:[10,200,30,-32766,50,60,32000,-32000]`, from lane 0 up, with exactly
+//!   one value per lane. A type followed by `x`, as in `u16x:[0x1234,…]`, reads
+//!   hex lanes with or without `0x`. Hex floats are bit patterns, so
+//!   `f32x:[3F800000,…]` starts with `1.0`. Integers in a decimal list may
+//!   also be written in `0x` hex, which may set the sign bit, or as `-0x`
+//!   followed by a magnitude that fits the type.
+//! - The whole register as `0x` and 32 or 64 hex digits, most significant byte
+//!   first like an integer literal, so the last two digits are lane 0 of the
+//!   `u8` view. `_` may separate digits.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//! use simd::ymm::Ymm;
+//!
+//! let a: Xmm = "i16:[10, 200, 30, -32766, 50, 60, 32000, -32000]".parse().unwrap();
+//! assert_eq!(a, Xmm::from_i16([10, 200, 30, -32766, 50, 60, 32000, -32000]));
+//!
+//! let b: Xmm = "0x0007_0006_0005_0004_0003_0002_0001_0000".parse().unwrap();
+//! assert_eq!(b, Xmm::from_fn::<u16>(|i| i as u16));
+//!
+//! let err = "u8:[1, 2, 3, 4]".parse::<Xmm>().unwrap_err();
+//! assert_eq!(err.to_string(), "expected 16 u8 lanes, got 4");
+//! let err = "u8x:[FF, 100]".parse::<Ymm>().unwrap_err();
+//! assert_eq!(err.to_string(), "lane 1: `100` is out of range for u8");
+//! ```
+
+use std::fmt;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
+use crate::chunks::Register;
+use crate::lane::Lane;
+use crate::xmm::Xmm;
+use crate::ymm::Ymm;
+
+/// Error returned when parsing a register or a lane fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRegisterError {
+    /// The text is neither a typed lane list nor a hex string.
+    Syntax(String),
+    UnknownType(String),
+    LaneCount { ty: &'static str, expected: usize, found: usize },
+    /// A lane value is malformed or does not fit the lane type. `lane` is its
+    /// position in a list.
+    InvalidLane { ty: &'static str, lane: Option<usize>, value: String, out_of_range: bool },
+    HexDigits { expected: usize, found: usize },
+}
+
+impl ParseRegisterError {
+    fn at_lane(self, i: usize) -> Self {
+        match self {
+            ParseRegisterError::InvalidLane { ty, value, out_of_range, .. } => {
+                ParseRegisterError::InvalidLane { ty, lane: Some(i), value, out_of_range }
+            }
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for ParseRegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRegisterError::Syntax(s) => write!(f, "expected `type:[lanes]` or a 0x hex string, got `{}`", s),
+            ParseRegisterError::UnknownType(ty) => write!(
+                f,
+                "unknown lane type `{}`, expected i8, i16, i32, i64, u8, u16, u32, u64, f32 or f64, \
+                 optionally followed by x for hex lanes",
+                ty
+            ),
+            ParseRegisterError::LaneCount { ty, expected, found } => {
+                write!(f, "expected {} {} lanes, got {}", expected, ty, found)
+            }
+            ParseRegisterError::InvalidLane { ty, lane, value, out_of_range } => {
+                if let Some(i) = lane {
+                    write!(f, "lane {}: ", i)?;
+                }
+                if *out_of_range {
+                    write!(f, "`{}` is out of range for {}", value, ty)
+                } else {
+                    write!(f, "`{}` is not a valid {}", value, ty)
+                }
+            }
+            ParseRegisterError::HexDigits { expected, found } => {
+                write!(f, "expected {} hex digits, got {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseRegisterError {}
+
+/// A lane type that can be parsed from text.
+pub trait ParseLane: Lane {
+    /// Parses one lane, in hex with or without `0x` if `hex` is set, else in
+    /// decimal or `0x` hex.
+    fn parse_lane(s: &str, hex: bool) -> Result<Self, ParseRegisterError>;
+}
+
+fn invalid<T: Lane>(s: &str, out_of_range: bool) -> ParseRegisterError {
+    ParseRegisterError::InvalidLane { ty: T::NAME, lane: None, value: s.to_string(), out_of_range }
+}
+
+/// Returns the digits of `s` if it is hex, by prefix or by `hex`. Callers
+/// reject a sign in the digits, which `from_str_radix` would accept.
+fn hex_digits(s: &str, hex: bool) -> Option<&str> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(digits) => Some(digits),
+        None if hex => Some(s),
+        None => None,
+    }
+}
+
+/// Parses the digits after the `-` of a negative integer, in decimal or in
+/// hex like [`hex_digits`]. The error is whether the number is well formed
+/// but does not fit `T`, which is always the case for unsigned `T` but zero.
+fn parse_negative<T: TryFrom<i128>>(magnitude: &str, hex: bool) -> Result<T, bool> {
+    let (digits, radix) = match hex_digits(magnitude, hex) {
+        Some(digits) => (digits, 16),
+        None => (magnitude, 10),
+    };
+    if digits.starts_with('+') {
+        return Err(false);
+    }
+    let magnitude = u128::from_str_radix(digits, radix).map_err(|e| matches!(e.kind(), IntErrorKind::PosOverflow))?;
+    let value = 0i128.checked_sub_unsigned(magnitude).ok_or(true)?;
+    T::try_from(value).map_err(|_| true)
+}
+
+/// `$bits` is the unsigned type of the same width, which hex is read as, so
+/// `0xFF` is `-1` for `i8`. A negated hex number must fit the type instead.
+macro_rules! impl_parse_int {
+    ($($t:ty => $bits:ty),*) => {
+        $(
+            impl ParseLane for $t {
+                fn parse_lane(s: &str, hex: bool) -> Result<Self, ParseRegisterError> {
+                    if let Some(magnitude) = s.strip_prefix('-') {
+                        return parse_negative(magnitude, hex).map_err(|out_of_range| invalid::<$t>(s, out_of_range));
+                    }
+                    let parsed = match hex_digits(s, hex) {
+                        Some(digits) if digits.starts_with(['+', '-']) => return Err(invalid::<$t>(s, false)),
+                        Some(digits) => <$bits>::from_str_radix(digits, 16).map(|x| x as $t),
+                        None => s.parse::<$t>(),
+                    };
+                    parsed.map_err(|e| {
+                        invalid::<$t>(s, matches!(e.kind(), IntErrorKind::PosOverflow | IntErrorKind::NegOverflow))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_int!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, u8 => u8, u16 => u16, u32 => u32, u64 => u64);
+
+macro_rules! impl_parse_float {
+    ($($t:ty => $bits:ty),*) => {
+        $(
+            impl ParseLane for $t {
+                fn parse_lane(s: &str, hex: bool) -> Result<Self, ParseRegisterError> {
+                    match hex_digits(s, hex) {
+                        Some(digits) if digits.starts_with(['+', '-']) => Err(invalid::<$t>(s, false)),
+                        Some(digits) => <$bits>::from_str_radix(digits, 16).map(<$t>::from_bits).map_err(|e| {
+                            invalid::<$t>(s, matches!(e.kind(), IntErrorKind::PosOverflow))
+                        }),
+                        None => s.parse().map_err(|_| invalid::<$t>(s, false)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_float!(f32 => u32, f64 => u64);
+
+/// Parses a comma-separated list of lanes, optionally in brackets.
+pub fn parse_lanes<T: ParseLane>(list: &str, hex: bool) -> Result<Vec<T>, ParseRegisterError> {
+    let list = list.trim();
+    let list = list.strip_prefix('[').and_then(|l| l.strip_suffix(']')).unwrap_or(list).trim();
+    if list.is_empty() {
+        return Ok(Vec::new());
+    }
+    list.split(',').enumerate().map(|(i, x)| T::parse_lane(x.trim(), hex).map_err(|e| e.at_lane(i))).collect()
+}
+
+/// Parses a list of exactly one `T` per lane of `R`.
+fn parse_typed<T: ParseLane, R: Register>(list: &str, hex: bool) -> Result<R, ParseRegisterError> {
+    if !(list.starts_with('[') && list.ends_with(']')) {
+        return Err(ParseRegisterError::Syntax(list.to_string()));
+    }
+    let lanes = parse_lanes::<T>(list, hex)?;
+    let expected = R::lane_count::<T>();
+    if lanes.len() != expected {
+        return Err(ParseRegisterError::LaneCount { ty: T::NAME, expected, found: lanes.len() });
+    }
+    let mut reg = R::default();
+    reg.lanes_mut::<T>().copy_from_slice(&lanes);
+    Ok(reg)
+}
+
+/// Parses the `digits` of the hex string `s`.
+fn parse_hex<R: Register>(s: &str, digits: &str) -> Result<R, ParseRegisterError> {
+    let digits: Vec<u8> = digits
+        .chars()
+        .filter(|&c| c != '_')
+        .map(|c| c.to_digit(16).map(|d| d as u8).ok_or_else(|| ParseRegisterError::Syntax(s.to_string())))
+        .collect::<Result<_, _>>()?;
+    if digits.len() != 2 * R::BYTES {
+        return Err(ParseRegisterError::HexDigits { expected: 2 * R::BYTES, found: digits.len() });
+    }
+    let mut reg = R::default();
+    for (byte, pair) in reg.lanes_mut::<u8>().iter_mut().rev().zip(digits.chunks(2)) {
+        *byte = pair[0] << 4 | pair[1];
+    }
+    Ok(reg)
+}
+
+fn parse_register<R: Register>(s: &str) -> Result<R, ParseRegisterError> {
+    let s = s.trim();
+    if let Some(digits) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        return parse_hex(s, digits);
+    }
+    let Some((ty, list)) = s.split_once(':') else {
+        return Err(ParseRegisterError::Syntax(s.to_string()));
+    };
+    let (ty, list) = (ty.trim(), list.trim());
+    let (name, hex) = match ty.strip_suffix('x') {
+        Some(name) => (name, true),
+        None => (ty, false),
+    };
+    match name {
+        "i8" => parse_typed::<i8, R>(list, hex),
+        "i16" => parse_typed::<i16, R>(list, hex),
+        "i32" => parse_typed::<i32, R>(list, hex),
+        "i64" => parse_typed::<i64, R>(list, hex),
+        "u8" => parse_typed::<u8, R>(list, hex),
+        "u16" => parse_typed::<u16, R>(list, hex),
+        "u32" => parse_typed::<u32, R>(list, hex),
+        "u64" => parse_typed::<u64, R>(list, hex),
+        "f32" => parse_typed::<f32, R>(list, hex),
+        "f64" => parse_typed::<f64, R>(list, hex),
+        _ => Err(ParseRegisterError::UnknownType(ty.to_string())),
+    }
+}
+
+impl FromStr for Xmm {
+    type Err = ParseRegisterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_register(s)
+    }
+}
+
+impl FromStr for Ymm {
+    type Err = ParseRegisterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_register(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_typed_lists() {
+        let a: Xmm = "u16x:[0x1234, ABDC, 0xaa55, 1111, FFFF, 7F7F, 9876, 7F00]".parse().unwrap();
+        assert_eq!(a, Xmm::from_u16([0x1234, 0xABDC, 0xAA55, 0x1111, 0xFFFF, 0x7F7F, 0x9876, 0x7F00]));
+        let b: Xmm = "i16:[0xFFFF,1,2,3,4,5,6,-32768]".parse().unwrap();
+        assert_eq!(b.as_i16(), &[-1, 1, 2, 3, 4, 5, 6, i16::MIN]);
+        let c: Ymm = "f32:[36, 0.03125, -2, 42, inf, -0.0, 3, 1e-3]".parse().unwrap();
+        assert_eq!(c.as_f32(), &[36.0, 0.03125, -2.0, 42.0, f32::INFINITY, -0.0, 3.0, 1e-3]);
+        let d: Xmm = "f64x:[3FF0000000000000, 0x8000000000000000]".parse().unwrap();
+        assert_eq!(d.as_u64(), &[1.0f64.to_bits(), (-0.0f64).to_bits()]);
+        let e: Ymm = " u64 : [1, 2, 3, 18446744073709551615] ".parse().unwrap();
+        assert_eq!(e.as_u64(), &[1, 2, 3, u64::MAX]);
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        let a: Xmm = "0x0F0E0D0C0B0A09080706050403020100".parse().unwrap();
+        assert_eq!(a, Xmm::from_fn::<u8>(|i| i as u8));
+        let b: Ymm = format!("0x{}", "00000001".repeat(8)).parse().unwrap();
+        assert_eq!(b, Ymm::splat_u32(1));
+        assert_eq!(
+            "0x0102".parse::<Xmm>(),
+            Err(ParseRegisterError::HexDigits { expected: 32, found: 4 })
+        );
+        assert_eq!("0x01G2".parse::<Xmm>(), Err(ParseRegisterError::Syntax("0x01G2".to_string())));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |s: &str| s.parse::<Xmm>().unwrap_err().to_string();
+        assert_eq!(err("i32:[1,2,3]"), "expected 4 i32 lanes, got 3");
+        assert_eq!(err("i8:[]"), "expected 16 i8 lanes, got 0");
+        assert_eq!(err("i16:[1,2,3,4,5,6,7,-32769]"), "lane 7: `-32769` is out of range for i16");
+        assert_eq!(err("u32:[1,2,x,4]"), "lane 2: `x` is not a valid u32");
+        assert_eq!(err("u32:[1,2,3,-4]"), "lane 3: `-4` is out of range for u32");
+        assert_eq!(err("u8x:[-1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]"), "lane 0: `-1` is out of range for u8");
+        assert_eq!(err("u32:[1,2,3,--4]"), "lane 3: `--4` is not a valid u32");
+        assert_eq!(err("i32:[1,2,3,-+4]"), "lane 3: `-+4` is not a valid i32");
+        assert_eq!(err("i32:[1,2,3,-]"), "lane 3: `-` is not a valid i32");
+        assert!(err("i12:[1]").starts_with("unknown lane type `i12`"));
+        assert_eq!(err("i16 1,2"), "expected `type:[lanes]` or a 0x hex string, got `i16 1,2`");
+        assert_eq!(err("i16:1,2"), "expected `type:[lanes]` or a 0x hex string, got `1,2`");
+        assert_eq!(u8::parse_lane("256", false).unwrap_err().to_string(), "`256` is out of range for u8");
+        assert_eq!(u64::parse_lane("-18446744073709551616", false).unwrap_err().to_string(), "`-18446744073709551616` is out of range for u64");
+        assert_eq!(i8::parse_lane("-0x81", false).unwrap_err().to_string(), "`-0x81` is out of range for i8");
+        assert_eq!(u8::parse_lane("0x+FF", false).unwrap_err().to_string(), "`0x+FF` is not a valid u8");
+        assert_eq!(err("i16x:[+7F,0,0,0,0,0,0,0]"), "lane 0: `+7F` is not a valid i16");
+        assert_eq!(f32::parse_lane("0x+3F800000", false).unwrap_err().to_string(), "`0x+3F800000` is not a valid f32");
+    }
+
+    #[test]
+    fn test_parse_negative() {
+        assert_eq!(i8::parse_lane("-0x80", false), Ok(-128));
+        assert_eq!(i8::parse_lane("0x80", false), Ok(-128));
+        assert_eq!(i16::parse_lane("-7F", true), Ok(-0x7F));
+        assert_eq!(i64::parse_lane("-9223372036854775808", false), Ok(i64::MIN));
+        assert_eq!(i32::parse_lane("-0X10", false), Ok(-16));
+        assert_eq!(u32::parse_lane("-0", false), Ok(0));
+        let a: Xmm = "i32:[-0x1, -0x80000000, -2, 3]".parse().unwrap();
+        assert_eq!(a, Xmm::from_i32([-1, i32::MIN, -2, 3]));
+    }
+}
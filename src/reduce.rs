@@ -0,0 +1,792 @@
+//! Horizontal reductions over slices of lanes: sum, mean, min and max.
+//!
+//! Every function takes anything that views as a slice, such as `&[T]`,
+//! `Vec<T>`, [`Array<T>`](crate::array::Array) or
+//! [`AlignedVec<T, N>`](crate::aligned_vec::AlignedVec), and runs on AVX2 when
+//! the [dispatch level](crate::dispatch) allows it, on SSE4.1 for the min/max
+//! of the lane types SSE2 lacks an instruction for, on SSE2 otherwise, and
+//! with a sequential loop at [`Isa::Scalar`](crate::dispatch::Isa::Scalar).
+//!
+//! ```
+//! use simd::array::Array;
+//! use simd::reduce;
+//!
+//! let array = Array::from_fn(1000, 32, |i| (i % 256) as u8);
+//! assert_eq!(reduce::sum(&array), 124_716);
+//! assert_eq!(reduce::min_max(&array), Some((0, 255)));
+//! assert_eq!(reduce::max(&[-1.5f32, 2.0, f32::NAN]), Some(2.0));
+//! assert_eq!(reduce::mean(&[] as &[i32]), None);
+//! ```
+//!
+//! # Widening
+//!
+//! Sums are returned in a wider type, [`ReduceLane::Sum`], so they cannot
+//! overflow: `i64` for `i8`…`i32`, `u64` for `u8`…`u32`, `i128`/`u128` for
+//! 64-bit lanes and `f64` for floats. Inside the kernels each register is
+//! widened just enough that the vector accumulator cannot overflow before it
+//! is flushed into the scalar total at the end of a block:
+//!
+//! - 8-bit lanes are summed with `psadbw` against zero, which adds groups of
+//!   eight bytes into 64-bit lanes. Signed bytes are first biased to unsigned
+//!   by flipping their sign bit, and the bias is subtracted afterwards.
+//! - 16-bit lanes are summed in pairs into 32-bit lanes with `pmaddwd` against
+//!   ones, after the same bias for unsigned lanes. A block is short enough
+//!   that the 32-bit lanes cannot overflow.
+//! - 32-bit lanes are sign- or zero-extended to 64 bits.
+//! - 64-bit lanes are split into 32-bit halves that are summed separately in
+//!   64-bit lanes and recombined as `hi << 32 + lo` in 128 bits.
+//! - `f32` lanes are converted to `f64` and `f64` lanes are added as they are.
+//!
+//! Float sums add lanes in a different order than a sequential loop, so they
+//! can differ from it by rounding.
+//!
+//! # Floats
+//!
+//! `min` and `max` ignore NaNs like [`f32::min`] does, and return NaN only if
+//! every element is NaN. Whether `-0.0` or `0.0` is returned when both are
+//! present is unspecified.
+
+#[cfg(simd_x86)]
+use crate::chunks::{load_padded, split_aligned, Chunks, Register};
+use crate::dispatch::select;
+use crate::lane::Lane;
+#[cfg(simd_x86)]
+use crate::xmm::Xmm;
+#[cfg(simd_x86)]
+use crate::ymm::Ymm;
+
+/// Lane types supported by the reductions.
+pub trait ReduceLane: Lane {
+    /// Type the sum is computed in, wide enough to never overflow.
+    type Sum: Copy + PartialEq + std::fmt::Debug;
+
+    #[doc(hidden)]
+    fn sum_slice(data: &[Self]) -> Self::Sum;
+    #[doc(hidden)]
+    fn sum_to_f64(sum: Self::Sum) -> f64;
+    #[doc(hidden)]
+    fn min_max_slice(data: &[Self]) -> Option<(Self, Self)>;
+}
+
+/// Returns the sum of all elements, zero if there are none.
+pub fn sum<T: ReduceLane>(data: &(impl AsRef<[T]> + ?Sized)) -> T::Sum {
+    T::sum_slice(data.as_ref())
+}
+
+/// Returns the arithmetic mean of all elements, `None` if there are none.
+pub fn mean<T: ReduceLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<f64> {
+    let data = data.as_ref();
+    if data.is_empty() {
+        return None;
+    }
+    Some(T::sum_to_f64(T::sum_slice(data)) / data.len() as f64)
+}
+
+/// Returns the smallest element, `None` if there are none.
+pub fn min<T: ReduceLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<T> {
+    T::min_max_slice(data.as_ref()).map(|(min, _)| min)
+}
+
+/// Returns the largest element, `None` if there are none.
+pub fn max<T: ReduceLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<T> {
+    T::min_max_slice(data.as_ref()).map(|(_, max)| max)
+}
+
+/// Returns the smallest and largest elements in one pass, `None` if there are none.
+pub fn min_max<T: ReduceLane>(data: &(impl AsRef<[T]> + ?Sized)) -> Option<(T, T)> {
+    T::min_max_slice(data.as_ref())
+}
+
+macro_rules! impl_reduce_lane {
+    ($($t:ident => $sum:ty, $lowest:expr, $highest:expr, $sum_fn:ident, $min_max_fn:ident;)*) => {
+        $(
+            impl ReduceLane for $t {
+                type Sum = $sum;
+
+                #[inline]
+                fn sum_slice(data: &[Self]) -> $sum {
+                    select!(
+                        Avx2 => unsafe { avx2::$sum_fn(data) },
+                        Sse2 => sse41::$sum_fn(data),
+                        _ => data.iter().map(|&x| x as $sum).sum(),
+                    )
+                }
+
+                #[inline]
+                fn sum_to_f64(sum: $sum) -> f64 {
+                    sum as f64
+                }
+
+                #[inline]
+                fn min_max_slice(data: &[Self]) -> Option<(Self, Self)> {
+                    if data.is_empty() {
+                        return None;
+                    }
+                    let (lo, hi) = select!(
+                        Avx2 => unsafe { avx2::$min_max_fn(data) },
+                        Sse2 => sse41::$min_max_fn(data),
+                        _ => scalar::min_max(data),
+                    );
+                    // Only possible for floats, when every element was a
+                    // skipped NaN and the accumulators kept their seeds.
+                    if lo > hi {
+                        return Some((data[0], data[0]));
+                    }
+                    Some((lo, hi))
+                }
+            }
+
+            impl IdentityLane for $t {
+                const LOWEST: Self = $lowest;
+                const HIGHEST: Self = $highest;
+            }
+        )*
+    };
+}
+
+impl_reduce_lane! {
+    i8 => i64, i8::MIN, i8::MAX, sum_i8, min_max_i8;
+    i16 => i64, i16::MIN, i16::MAX, sum_i16, min_max_i16;
+    i32 => i64, i32::MIN, i32::MAX, sum_i32, min_max_i32;
+    i64 => i128, i64::MIN, i64::MAX, sum_i64, min_max_i64;
+    u8 => u64, u8::MIN, u8::MAX, sum_u8, min_max_u8;
+    u16 => u64, u16::MIN, u16::MAX, sum_u16, min_max_u16;
+    u32 => u64, u32::MIN, u32::MAX, sum_u32, min_max_u32;
+    u64 => u128, u64::MIN, u64::MAX, sum_u64, min_max_u64;
+    f32 => f64, f32::NEG_INFINITY, f32::INFINITY, sum_f32, min_max_f32;
+    f64 => f64, f64::NEG_INFINITY, f64::INFINITY, sum_f64, min_max_f64;
+}
+
+/// Identity elements of min (`HIGHEST`) and max (`LOWEST`).
+pub(crate) trait IdentityLane: Lane {
+    const LOWEST: Self;
+    const HIGHEST: Self;
+}
+
+/// Sums `data` in blocks of at most `block` elements. Inside a block, `step`
+/// widens every register into the vector accumulator, which starts at `zero`.
+/// At the end of the block `finish` reduces the accumulator of `n` lanes,
+/// counting the zero padding of the last register, into a scalar that is
+/// added to the total.
+#[cfg(simd_x86)]
+macro_rules! blocked_sum {
+    ($data:expr, $reg:ident, $t:ty, block: $block:expr, zero: $zero:expr,
+     step: |$acc:ident, $v:ident| $step:expr, finish: |$fin:ident, $n:ident| $finish:expr) => {{
+        let lanes = <$reg as Register>::lane_count::<$t>();
+        let mut total = Default::default();
+        for block in $data.chunks($block) {
+            let mut $acc = $zero;
+            for $v in Chunks::<$t, $reg>::new(block) {
+                $acc = $step;
+            }
+            let $fin = $acc;
+            let $n = block.len().div_ceil(lanes) * lanes;
+            total += $finish;
+        }
+        total
+    }};
+}
+
+/// Seeds the accumulators with the identities and folds every register into
+/// them with `min(v, acc)`/`max(v, acc)`. The unaligned head and the tail are
+/// peeled off and padded with the identities, so the body only does aligned
+/// loads whatever the alignment of `data`. Float kernels return their second
+/// operand when either is NaN, so NaNs in the data are skipped.
+#[cfg(simd_x86)]
+macro_rules! min_max_driver {
+    ($name:ident, $(#[$attr:meta])* [$($unsafety:tt)*] $reg:ident, $t:ty, $min:path, $max:path) => {
+        $(#[$attr])*
+        pub $($unsafety)* fn $name(data: &[$t]) -> ($t, $t) {
+            let (highest, lowest) = (<$t as IdentityLane>::HIGHEST, <$t as IdentityLane>::LOWEST);
+            let mut lo = <$reg as Register>::splat(highest);
+            let mut hi = <$reg as Register>::splat(lowest);
+            let (head, body, tail) = split_aligned::<$t, $reg>(data);
+            for &v in body {
+                lo = $min(v, lo);
+                hi = $max(v, hi);
+            }
+            for part in [head, tail] {
+                if let Some(v) = load_padded(part, highest) {
+                    lo = $min(v, lo);
+                }
+                if let Some(v) = load_padded(part, lowest) {
+                    hi = $max(v, hi);
+                }
+            }
+            let fold = |lanes: &[$t], pick: fn($t, $t) -> bool| {
+                lanes.iter().copied().reduce(|a, b| if pick(b, a) { b } else { a }).unwrap()
+            };
+            (fold(lo.lanes::<$t>(), |b, a| b < a), fold(hi.lanes::<$t>(), |b, a| b > a))
+        }
+    };
+}
+
+/// Portable kernels.
+mod scalar {
+    use super::IdentityLane;
+
+    /// NaNs compare neither less nor greater, so they are skipped like in the
+    /// vector kernels.
+    #[inline]
+    pub fn min_max<T: IdentityLane>(data: &[T]) -> (T, T) {
+        data.iter().fold((T::HIGHEST, T::LOWEST), |(lo, hi), &x| {
+            (if x < lo { x } else { lo }, if x > hi { x } else { hi })
+        })
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic($vec::from(a), $vec::from(b)) }.into()
+            }
+        };
+    }
+
+    /// Applies an unsigned min/max as a signed one, or the other way around,
+    /// by flipping the sign bit of both operands and of the result.
+    macro_rules! flipped {
+        ($name:ident, $inner:path, $splat:ident, $bit:expr) => {
+            #[inline(always)]
+            pub fn $name(a: Xmm, b: Xmm) -> Xmm {
+                let s = Xmm::$splat($bit);
+                xor(s, $inner(xor(a, s), xor(b, s)))
+            }
+        };
+    }
+
+    binary!(xor, __m128i, _mm_xor_si128);
+    binary!(min_epu8, __m128i, _mm_min_epu8);
+    binary!(max_epu8, __m128i, _mm_max_epu8);
+    binary!(min_epi16, __m128i, _mm_min_epi16);
+    binary!(max_epi16, __m128i, _mm_max_epi16);
+    binary!(min_ps, __m128, _mm_min_ps);
+    binary!(max_ps, __m128, _mm_max_ps);
+    binary!(min_pd, __m128d, _mm_min_pd);
+    binary!(max_pd, __m128d, _mm_max_pd);
+
+    flipped!(min_epi8, min_epu8, splat_u8, 0x80);
+    flipped!(max_epi8, max_epu8, splat_u8, 0x80);
+    flipped!(min_epu16, min_epi16, splat_u16, 0x8000);
+    flipped!(max_epu16, max_epi16, splat_u16, 0x8000);
+    flipped!(min_epu32, min_epi32, splat_u32, 1 << 31);
+    flipped!(max_epu32, max_epi32, splat_u32, 1 << 31);
+    flipped!(min_epu64, min_epi64, splat_u64, 1 << 63);
+    flipped!(max_epu64, max_epi64, splat_u64, 1 << 63);
+
+    /// `mask ? a : b` bitwise.
+    #[inline(always)]
+    fn select(mask: __m128i, a: __m128i, b: __m128i) -> Xmm {
+        unsafe { _mm_or_si128(_mm_and_si128(mask, a), _mm_andnot_si128(mask, b)) }.into()
+    }
+
+    #[inline(always)]
+    pub fn min_epi32(a: Xmm, b: Xmm) -> Xmm {
+        let (a, b) = (a.into(), b.into());
+        select(unsafe { _mm_cmpgt_epi32(a, b) }, b, a)
+    }
+
+    #[inline(always)]
+    pub fn max_epi32(a: Xmm, b: Xmm) -> Xmm {
+        let (a, b) = (a.into(), b.into());
+        select(unsafe { _mm_cmpgt_epi32(a, b) }, a, b)
+    }
+
+    /// `_mm_cmpgt_epi64` needs SSE4.2: compare the high halves as signed, and
+    /// if they are equal take the borrow of `b - a`, which compares the low
+    /// halves as unsigned.
+    #[inline(always)]
+    fn cmpgt_epi64(a: __m128i, b: __m128i) -> __m128i {
+        unsafe {
+            let eq_hi_lt_lo = _mm_and_si128(_mm_cmpeq_epi32(a, b), _mm_sub_epi64(b, a));
+            let gt = _mm_or_si128(eq_hi_lt_lo, _mm_cmpgt_epi32(a, b));
+            _mm_shuffle_epi32::<0b11_11_01_01>(gt)
+        }
+    }
+
+    #[inline(always)]
+    pub fn min_epi64(a: Xmm, b: Xmm) -> Xmm {
+        let (a, b) = (a.into(), b.into());
+        select(cmpgt_epi64(a, b), b, a)
+    }
+
+    #[inline(always)]
+    pub fn max_epi64(a: Xmm, b: Xmm) -> Xmm {
+        let (a, b) = (a.into(), b.into());
+        select(cmpgt_epi64(a, b), a, b)
+    }
+
+    /// Adds the 32-bit halves of every 64-bit lane of `v` into `acc`.
+    #[inline(always)]
+    fn add_halves_epi64(acc: (Xmm, Xmm), v: Xmm) -> (Xmm, Xmm) {
+        unsafe {
+            let v: __m128i = v.into();
+            let lo = _mm_add_epi64(acc.0.into(), _mm_and_si128(v, _mm_set1_epi64x(0xFFFF_FFFF)));
+            let hi = _mm_add_epi64(acc.1.into(), _mm_srli_epi64::<32>(v));
+            (lo.into(), hi.into())
+        }
+    }
+
+    fn sum_halves(acc: (Xmm, Xmm)) -> u128 {
+        let lo: u128 = acc.0.as_u64().iter().map(|&x| x as u128).sum();
+        let hi: u128 = acc.1.as_u64().iter().map(|&x| x as u128).sum();
+        lo + (hi << 32)
+    }
+
+    pub fn sum_u8(data: &[u8]) -> u64 {
+        blocked_sum!(data, Xmm, u8, block: usize::MAX, zero: Xmm::default(),
+            step: |acc, v| unsafe { _mm_add_epi64(acc.into(), _mm_sad_epu8(v.into(), _mm_setzero_si128())) }.into(),
+            finish: |acc, _n| acc.as_u64().iter().sum::<u64>())
+    }
+
+    min_max_driver!(min_max_u8, [] Xmm, u8, min_epu8, max_epu8);
+
+    pub fn sum_i8(data: &[i8]) -> i64 {
+        blocked_sum!(data, Xmm, i8, block: usize::MAX, zero: Xmm::default(),
+            step: |acc, v| unsafe {
+                let biased = _mm_xor_si128(v.into(), _mm_set1_epi8(i8::MIN));
+                _mm_add_epi64(acc.into(), _mm_sad_epu8(biased, _mm_setzero_si128()))
+            }.into(),
+            finish: |acc, n| acc.as_i64().iter().sum::<i64>() - 128 * n as i64)
+    }
+
+    min_max_driver!(min_max_i8, [] Xmm, i8, min_epi8, max_epi8);
+
+    pub fn sum_i16(data: &[i16]) -> i64 {
+        blocked_sum!(data, Xmm, i16, block: 1 << 16, zero: Xmm::default(),
+            step: |acc, v| unsafe { _mm_add_epi32(acc.into(), _mm_madd_epi16(v.into(), _mm_set1_epi16(1))) }.into(),
+            finish: |acc, _n| acc.as_i32().iter().map(|&x| x as i64).sum::<i64>())
+    }
+
+    min_max_driver!(min_max_i16, [] Xmm, i16, min_epi16, max_epi16);
+
+    pub fn sum_u16(data: &[u16]) -> u64 {
+        blocked_sum!(data, Xmm, u16, block: 1 << 16, zero: Xmm::default(),
+            step: |acc, v| unsafe {
+                let biased = _mm_xor_si128(v.into(), _mm_set1_epi16(i16::MIN));
+                _mm_add_epi32(acc.into(), _mm_madd_epi16(biased, _mm_set1_epi16(1)))
+            }.into(),
+            finish: |acc, n| (acc.as_i32().iter().map(|&x| x as i64).sum::<i64>() + 32768 * n as i64) as u64)
+    }
+
+    min_max_driver!(min_max_u16, [] Xmm, u16, min_epu16, max_epu16);
+
+    pub fn sum_i32(data: &[i32]) -> i64 {
+        blocked_sum!(data, Xmm, i32, block: 1 << 30, zero: Xmm::default(),
+            step: |acc, v| unsafe {
+                let v: __m128i = v.into();
+                let sign = _mm_srai_epi32::<31>(v);
+                let acc = _mm_add_epi64(acc.into(), _mm_unpacklo_epi32(v, sign));
+                _mm_add_epi64(acc, _mm_unpackhi_epi32(v, sign))
+            }.into(),
+            finish: |acc, _n| acc.as_i64().iter().sum::<i64>())
+    }
+
+    min_max_driver!(min_max_i32, [] Xmm, i32, min_epi32, max_epi32);
+
+    pub fn sum_u32(data: &[u32]) -> u64 {
+        blocked_sum!(data, Xmm, u32, block: 1 << 30, zero: Xmm::default(),
+            step: |acc, v| unsafe {
+                let (v, zero): (__m128i, __m128i) = (v.into(), _mm_setzero_si128());
+                let acc = _mm_add_epi64(acc.into(), _mm_unpacklo_epi32(v, zero));
+                _mm_add_epi64(acc, _mm_unpackhi_epi32(v, zero))
+            }.into(),
+            finish: |acc, _n| acc.as_u64().iter().sum::<u64>())
+    }
+
+    min_max_driver!(min_max_u32, [] Xmm, u32, min_epu32, max_epu32);
+
+    pub fn sum_i64(data: &[i64]) -> i128 {
+        blocked_sum!(data, Xmm, i64, block: 1 << 30, zero: (Xmm::default(), Xmm::default()),
+            step: |acc, v| add_halves_epi64(acc, xor(v, Xmm::splat_u64(1 << 63))),
+            finish: |acc, n| sum_halves(acc) as i128 - (n as i128) * (1 << 63))
+    }
+
+    min_max_driver!(min_max_i64, [] Xmm, i64, min_epi64, max_epi64);
+
+    pub fn sum_u64(data: &[u64]) -> u128 {
+        blocked_sum!(data, Xmm, u64, block: 1 << 30, zero: (Xmm::default(), Xmm::default()),
+            step: |acc, v| add_halves_epi64(acc, v),
+            finish: |acc, _n| sum_halves(acc))
+    }
+
+    min_max_driver!(min_max_u64, [] Xmm, u64, min_epu64, max_epu64);
+
+    pub fn sum_f32(data: &[f32]) -> f64 {
+        blocked_sum!(data, Xmm, f32, block: usize::MAX, zero: Xmm::default(),
+            step: |acc, v| unsafe {
+                let v: __m128 = v.into();
+                let acc = _mm_add_pd(acc.into(), _mm_cvtps_pd(v));
+                _mm_add_pd(acc, _mm_cvtps_pd(_mm_movehl_ps(v, v)))
+            }.into(),
+            finish: |acc, _n| acc.as_f64().iter().sum::<f64>())
+    }
+
+    min_max_driver!(min_max_f32, [] Xmm, f32, min_ps, max_ps);
+
+    pub fn sum_f64(data: &[f64]) -> f64 {
+        blocked_sum!(data, Xmm, f64, block: usize::MAX, zero: Xmm::default(),
+            step: |acc, v| unsafe { _mm_add_pd(acc.into(), v.into()) }.into(),
+            finish: |acc, _n| acc.as_f64().iter().sum::<f64>())
+    }
+
+    min_max_driver!(min_max_f64, [] Xmm, f64, min_pd, max_pd);
+}
+
+/// SSE4.1 kernels over [`Xmm`], falling back to [`sse2`] when the dispatch
+/// level is lower. SSE4.1 adds the min/max of `i8`, `u16`, `i32` and `u32`
+/// that SSE2 emulates. The other kernels are re-exported from [`sse2`].
+#[cfg(simd_x86)]
+mod sse41 {
+    pub use super::sse2::*;
+
+    use super::*;
+    use crate::dispatch::has_sse41;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "sse4.1")]
+            unsafe fn $name(a: Xmm, b: Xmm) -> Xmm {
+                $intrinsic(a.into(), b.into()).into()
+            }
+        };
+    }
+
+    macro_rules! dispatched {
+        ($name:ident, $t:ty, $native:ident) => {
+            #[inline]
+            pub fn $name(data: &[$t]) -> ($t, $t) {
+                if has_sse41() {
+                    unsafe { $native(data) }
+                } else {
+                    super::sse2::$name(data)
+                }
+            }
+        };
+    }
+
+    binary!(min_epi8, _mm_min_epi8);
+    binary!(max_epi8, _mm_max_epi8);
+    binary!(min_epu16, _mm_min_epu16);
+    binary!(max_epu16, _mm_max_epu16);
+    binary!(min_epi32, _mm_min_epi32);
+    binary!(max_epi32, _mm_max_epi32);
+    binary!(min_epu32, _mm_min_epu32);
+    binary!(max_epu32, _mm_max_epu32);
+
+    min_max_driver!(native_min_max_i8, #[target_feature(enable = "sse4.1")] [unsafe] Xmm, i8, min_epi8, max_epi8);
+    min_max_driver!(native_min_max_u16, #[target_feature(enable = "sse4.1")] [unsafe] Xmm, u16, min_epu16, max_epu16);
+    min_max_driver!(native_min_max_i32, #[target_feature(enable = "sse4.1")] [unsafe] Xmm, i32, min_epi32, max_epi32);
+    min_max_driver!(native_min_max_u32, #[target_feature(enable = "sse4.1")] [unsafe] Xmm, u32, min_epu32, max_epu32);
+
+    dispatched!(min_max_i8, i8, native_min_max_i8);
+    dispatched!(min_max_u16, u16, native_min_max_u16);
+    dispatched!(min_max_i32, i32, native_min_max_i32);
+    dispatched!(min_max_u32, u32, native_min_max_u32);
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use super::*;
+    use std::arch::x86_64::*;
+
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, b: Ymm) -> Ymm {
+                $intrinsic($vec::from(a), $vec::from(b)).into()
+            }
+        };
+    }
+
+    binary!(min_epi8, __m256i, _mm256_min_epi8);
+    binary!(max_epi8, __m256i, _mm256_max_epi8);
+    binary!(min_epu8, __m256i, _mm256_min_epu8);
+    binary!(max_epu8, __m256i, _mm256_max_epu8);
+    binary!(min_epi16, __m256i, _mm256_min_epi16);
+    binary!(max_epi16, __m256i, _mm256_max_epi16);
+    binary!(min_epu16, __m256i, _mm256_min_epu16);
+    binary!(max_epu16, __m256i, _mm256_max_epu16);
+    binary!(min_epi32, __m256i, _mm256_min_epi32);
+    binary!(max_epi32, __m256i, _mm256_max_epi32);
+    binary!(min_epu32, __m256i, _mm256_min_epu32);
+    binary!(max_epu32, __m256i, _mm256_max_epu32);
+    binary!(min_ps, __m256, _mm256_min_ps);
+    binary!(max_ps, __m256, _mm256_max_ps);
+    binary!(min_pd, __m256d, _mm256_min_pd);
+    binary!(max_pd, __m256d, _mm256_max_pd);
+
+    /// `bias` is XORed into both operands so the signed compare orders
+    /// unsigned lanes too.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn gt_epi64(a: Ymm, b: Ymm, bias: i64) -> __m256i {
+        let bias = _mm256_set1_epi64x(bias);
+        _mm256_cmpgt_epi64(_mm256_xor_si256(a.into(), bias), _mm256_xor_si256(b.into(), bias))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn min_epi64(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_blendv_epi8(a.into(), b.into(), gt_epi64(a, b, 0)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn max_epi64(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_blendv_epi8(b.into(), a.into(), gt_epi64(a, b, 0)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn min_epu64(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_blendv_epi8(a.into(), b.into(), gt_epi64(a, b, i64::MIN)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn max_epu64(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_blendv_epi8(b.into(), a.into(), gt_epi64(a, b, i64::MIN)).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn add_halves_epi64(acc: (Ymm, Ymm), v: __m256i) -> (Ymm, Ymm) {
+        let lo = _mm256_add_epi64(acc.0.into(), _mm256_and_si256(v, _mm256_set1_epi64x(0xFFFF_FFFF)));
+        let hi = _mm256_add_epi64(acc.1.into(), _mm256_srli_epi64::<32>(v));
+        (lo.into(), hi.into())
+    }
+
+    fn sum_halves(acc: (Ymm, Ymm)) -> u128 {
+        let lo: u128 = acc.0.as_u64().iter().map(|&x| x as u128).sum();
+        let hi: u128 = acc.1.as_u64().iter().map(|&x| x as u128).sum();
+        lo + (hi << 32)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_u8(data: &[u8]) -> u64 {
+        blocked_sum!(data, Ymm, u8, block: usize::MAX, zero: Ymm::default(),
+            step: |acc, v| _mm256_add_epi64(acc.into(), _mm256_sad_epu8(v.into(), _mm256_setzero_si256())).into(),
+            finish: |acc, _n| acc.as_u64().iter().sum::<u64>())
+    }
+
+    min_max_driver!(min_max_u8, #[target_feature(enable = "avx2")] [unsafe] Ymm, u8, min_epu8, max_epu8);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_i8(data: &[i8]) -> i64 {
+        blocked_sum!(data, Ymm, i8, block: usize::MAX, zero: Ymm::default(),
+            step: |acc, v| {
+                let biased = _mm256_xor_si256(v.into(), _mm256_set1_epi8(i8::MIN));
+                _mm256_add_epi64(acc.into(), _mm256_sad_epu8(biased, _mm256_setzero_si256())).into()
+            },
+            finish: |acc, n| acc.as_i64().iter().sum::<i64>() - 128 * n as i64)
+    }
+
+    min_max_driver!(min_max_i8, #[target_feature(enable = "avx2")] [unsafe] Ymm, i8, min_epi8, max_epi8);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_i16(data: &[i16]) -> i64 {
+        blocked_sum!(data, Ymm, i16, block: 1 << 16, zero: Ymm::default(),
+            step: |acc, v| _mm256_add_epi32(acc.into(), _mm256_madd_epi16(v.into(), _mm256_set1_epi16(1))).into(),
+            finish: |acc, _n| acc.as_i32().iter().map(|&x| x as i64).sum::<i64>())
+    }
+
+    min_max_driver!(min_max_i16, #[target_feature(enable = "avx2")] [unsafe] Ymm, i16, min_epi16, max_epi16);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_u16(data: &[u16]) -> u64 {
+        blocked_sum!(data, Ymm, u16, block: 1 << 16, zero: Ymm::default(),
+            step: |acc, v| {
+                let biased = _mm256_xor_si256(v.into(), _mm256_set1_epi16(i16::MIN));
+                _mm256_add_epi32(acc.into(), _mm256_madd_epi16(biased, _mm256_set1_epi16(1))).into()
+            },
+            finish: |acc, n| (acc.as_i32().iter().map(|&x| x as i64).sum::<i64>() + 32768 * n as i64) as u64)
+    }
+
+    min_max_driver!(min_max_u16, #[target_feature(enable = "avx2")] [unsafe] Ymm, u16, min_epu16, max_epu16);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_i32(data: &[i32]) -> i64 {
+        blocked_sum!(data, Ymm, i32, block: 1 << 30, zero: Ymm::default(),
+            step: |acc, v| {
+                let (lo, hi) = v.halves();
+                let acc = _mm256_add_epi64(acc.into(), _mm256_cvtepi32_epi64(lo.into()));
+                _mm256_add_epi64(acc, _mm256_cvtepi32_epi64(hi.into())).into()
+            },
+            finish: |acc, _n| acc.as_i64().iter().sum::<i64>())
+    }
+
+    min_max_driver!(min_max_i32, #[target_feature(enable = "avx2")] [unsafe] Ymm, i32, min_epi32, max_epi32);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_u32(data: &[u32]) -> u64 {
+        blocked_sum!(data, Ymm, u32, block: 1 << 30, zero: Ymm::default(),
+            step: |acc, v| {
+                let (lo, hi) = v.halves();
+                let acc = _mm256_add_epi64(acc.into(), _mm256_cvtepu32_epi64(lo.into()));
+                _mm256_add_epi64(acc, _mm256_cvtepu32_epi64(hi.into())).into()
+            },
+            finish: |acc, _n| acc.as_u64().iter().sum::<u64>())
+    }
+
+    min_max_driver!(min_max_u32, #[target_feature(enable = "avx2")] [unsafe] Ymm, u32, min_epu32, max_epu32);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_i64(data: &[i64]) -> i128 {
+        blocked_sum!(data, Ymm, i64, block: 1 << 30, zero: (Ymm::default(), Ymm::default()),
+            step: |acc, v| add_halves_epi64(acc, _mm256_xor_si256(v.into(), _mm256_set1_epi64x(i64::MIN))),
+            finish: |acc, n| sum_halves(acc) as i128 - (n as i128) * (1 << 63))
+    }
+
+    min_max_driver!(min_max_i64, #[target_feature(enable = "avx2")] [unsafe] Ymm, i64, min_epi64, max_epi64);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_u64(data: &[u64]) -> u128 {
+        blocked_sum!(data, Ymm, u64, block: 1 << 30, zero: (Ymm::default(), Ymm::default()),
+            step: |acc, v| add_halves_epi64(acc, v.into()),
+            finish: |acc, _n| sum_halves(acc))
+    }
+
+    min_max_driver!(min_max_u64, #[target_feature(enable = "avx2")] [unsafe] Ymm, u64, min_epu64, max_epu64);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_f32(data: &[f32]) -> f64 {
+        blocked_sum!(data, Ymm, f32, block: usize::MAX, zero: Ymm::default(),
+            step: |acc, v| {
+                let (lo, hi) = v.halves();
+                let acc = _mm256_add_pd(acc.into(), _mm256_cvtps_pd(lo.into()));
+                _mm256_add_pd(acc, _mm256_cvtps_pd(hi.into())).into()
+            },
+            finish: |acc, _n| acc.as_f64().iter().sum::<f64>())
+    }
+
+    min_max_driver!(min_max_f32, #[target_feature(enable = "avx2")] [unsafe] Ymm, f32, min_ps, max_ps);
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum_f64(data: &[f64]) -> f64 {
+        blocked_sum!(data, Ymm, f64, block: usize::MAX, zero: Ymm::default(),
+            step: |acc, v| _mm256_add_pd(acc.into(), v.into()).into(),
+            finish: |acc, _n| acc.as_f64().iter().sum::<f64>())
+    }
+
+    min_max_driver!(min_max_f64, #[target_feature(enable = "avx2")] [unsafe] Ymm, f64, min_pd, max_pd);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aligned_vec::AlignedVec;
+    use crate::array::Array;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    /// Checks every supported level against a scalar fold for lengths around
+    /// every register boundary, with values biased to the extremes.
+    macro_rules! check_int {
+        ($t:ident, $sum:ty) => {{
+            let mut rng = rand::thread_rng();
+            for len in (0..70).chain([255, 1000, 4099]) {
+                let data: Vec<$t> = (0..len)
+                    .map(|_| match rng.gen_range(0..4) {
+                        0 => $t::MIN,
+                        1 => $t::MAX,
+                        _ => rng.gen(),
+                    })
+                    .collect();
+                let sum: $sum = data.iter().map(|&x| x as $sum).sum();
+                let min_max = data.iter().min().copied().zip(data.iter().max().copied());
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        assert_eq!(super::sum(&data), sum, "{} sum at {}, len {}", stringify!($t), isa, len);
+                        assert_eq!(super::min_max(&data), min_max, "{} min_max at {}", stringify!($t), isa);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_int_reductions_match_scalar() {
+        check_int!(i8, i64);
+        check_int!(u8, u64);
+        check_int!(i16, i64);
+        check_int!(u16, u64);
+        check_int!(i32, i64);
+        check_int!(u32, u64);
+        check_int!(i64, i128);
+        check_int!(u64, u128);
+    }
+
+    /// Any alignment and any values, including all values above 0x81, which
+    /// a signed seed would wrongly report as the minimum.
+    #[test]
+    fn test_u8_min_max_any_alignment() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..500 {
+            let len = rng.gen_range(1..200);
+            let offset = rng.gen_range(0..32);
+            let floor = [0, 0x82, 0xF0, 0xFF][rng.gen_range(0..4)];
+            let data: Vec<u8> = (0..offset + len).map(|_| rng.gen_range(floor..=0xFF)).collect();
+            let data = &data[offset..];
+            let expected = (*data.iter().min().unwrap(), *data.iter().max().unwrap());
+
+            for isa in Isa::supported() {
+                with_isa(isa, || assert_eq!(min_max(data), Some(expected), "{} offset {}, {:?}", isa, offset, data));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sum_does_not_overflow() {
+        let data = vec![u16::MAX; 300_000];
+        assert_eq!(sum(&data), 300_000 * u16::MAX as u64);
+        let data = vec![i16::MIN; 300_000];
+        assert_eq!(sum(&data), 300_000 * i16::MIN as i64);
+        let data = vec![i64::MIN; 1000];
+        assert_eq!(sum(&data), 1000 * i64::MIN as i128);
+    }
+
+    #[test]
+    fn test_float_reductions() {
+        let data: Vec<f32> = (0..1001).map(|i| i as f32 * 0.5 - 100.0).collect();
+        let expected: f64 = data.iter().map(|&x| x as f64).sum();
+        let with_nan = [f64::NAN, 3.0, -1.0, f64::NAN, 7.5];
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                assert_eq!(sum(&data), expected, "{}", isa);
+                assert_eq!(min_max(&data), Some((-100.0, 400.0)), "{}", isa);
+                assert_eq!(min_max(&with_nan), Some((-1.0, 7.5)), "{}", isa);
+                let (lo, hi) = min_max(&[f32::NAN; 9]).unwrap();
+                assert!(lo.is_nan() && hi.is_nan(), "{}", isa);
+            });
+        }
+        assert_eq!(mean(&[1.0f64, 2.0, 4.5]), Some(2.5));
+        assert_eq!(max(&[f32::NEG_INFINITY]), Some(f32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_containers() {
+        let array = Array::from_fn(100, 16, |i| i as i32 - 50);
+        let v: AlignedVec<i32, 32> = (0..100).map(|i| i - 50).collect();
+        assert_eq!(sum(&array), -50);
+        assert_eq!(sum(&v), -50);
+        assert_eq!(mean(&v[..2]), Some(-49.5));
+        assert_eq!(min(&array), Some(-50));
+        assert_eq!(max(&v), Some(49));
+        assert_eq!(min(&[] as &[u8]), None);
+        assert_eq!(sum(&[] as &[f32]), 0.0);
+    }
+}
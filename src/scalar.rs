@@ -0,0 +1,27 @@
+//! Lane-by-lane building blocks of the portable kernels.
+//!
+//! Every module keeps its portable kernels in a private `scalar` module next
+//! to its `sse2` and `avx2` ones, built from these helpers. They run at
+//! [`Isa::Scalar`](crate::dispatch::Isa::Scalar) and are the only kernels on
+//! targets other than x86_64.
+
+use crate::chunks::Register;
+use crate::lane::Lane;
+
+/// Applies `f` to every `T` lane of `a`.
+#[inline]
+pub(crate) fn map<T: Lane, R: Register>(mut a: R, f: impl Fn(T) -> T) -> R {
+    for x in a.lanes_mut::<T>() {
+        *x = f(*x);
+    }
+    a
+}
+
+/// Applies `f` to every pair of `T` lanes of `a` and `b`.
+#[inline]
+pub(crate) fn zip<T: Lane, R: Register>(mut a: R, b: R, f: impl Fn(T, T) -> T) -> R {
+    for (x, &y) in a.lanes_mut::<T>().iter_mut().zip(b.lanes::<T>()) {
+        *x = f(*x, y);
+    }
+    a
+}
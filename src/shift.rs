@@ -0,0 +1,1151 @@
+//! Bit shifts and rotates of integer lanes, and byte shifts of whole
+//! registers.
+//!
+//! Every shift comes in three forms: by a constant, by one count in the low
+//! 64 bits of an [`Xmm`] shared by all lanes, like `_mm_sll_epi32`, and by a
+//! count per lane, like AVX2's `_mm256_sllv_epi32`.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//!
+//! let a = Xmm::from_i16([1, -2, 3, -4, 5, -6, 7, -8]);
+//! assert_eq!(a.shift_left::<i16, 2>(), Xmm::from_i16([4, -8, 12, -16, 20, -24, 28, -32]));
+//! assert_eq!(a.shift_right::<i16, 1>(), Xmm::from_i16([0, -1, 1, -2, 2, -3, 3, -4]));
+//! assert_eq!(a.shift_right::<u16, 15>(), Xmm::from_u16([0, 1, 0, 1, 0, 1, 0, 1]));
+//! assert_eq!(a.shift_left_by::<i16>(Xmm::from_u64([3, 0])), a.shift_left::<i16, 3>());
+//!
+//! let counts = Xmm::from_u32([0, 1, 31, 32]);
+//! assert_eq!(Xmm::splat_u32(1).shift_left_each::<u32>(counts), Xmm::from_u32([1, 2, 1 << 31, 0]));
+//! assert_eq!(Xmm::splat_u32(0x8000_0001).rotate_left::<u32, 4>(), Xmm::splat_u32(0x18));
+//! ```
+//!
+//! `shift_right` is arithmetic for signed lanes and logical for unsigned
+//! ones, so the same register shifts either way depending on the lane type.
+//! Counts are unsigned and, as with the x86 instructions, a count of at
+//! least the lane width clears the lane, or fills it with its sign bit for
+//! an arithmetic shift. Rotates take their counts modulo the lane width.
+//!
+//! SSE2 shifts 16-, 32- and 64-bit lanes by a constant or a shared count.
+//! Bytes are shifted as 16-bit lanes and masked, and the 64-bit arithmetic
+//! shift, which x86 only has from AVX-512, flips negative lanes around a
+//! logical shift. Per-lane counts need AVX2's `sllv`, `srlv` and `srav` for
+//! 32- and 64-bit lanes, which 16-bit lanes use on their two halves of each
+//! 32 bits. SSE2 runs one shift per count for 32- and 64-bit lanes and one
+//! shift per bit of the counts for narrower ones. Rotates are two shifts and
+//! an or.
+//!
+//! # Byte shifts
+//!
+//! `byte_shift_left`, `byte_shift_right` and `alignr` move whole bytes,
+//! across lane boundaries, like `pslldq`, `psrldq` and `palignr`. Their
+//! AVX2 forms work on each 128-bit half of a [`Ymm`] separately, and so do
+//! these methods at every level: nothing moves between the halves.
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//! use simd::ymm::Ymm;
+//!
+//! let a = Xmm::from_fn::<u8>(|i| i as u8);
+//! assert_eq!(a.byte_shift_right::<4>().as_u32(), &[0x0706_0504, 0x0B0A_0908, 0x0F0E_0D0C, 0]);
+//! assert_eq!(a.alignr::<14>(Xmm::splat_u8(0xFF)).as_u8()[..4], [0xFF, 0xFF, 0, 1]);
+//!
+//! let b = Ymm::from_fn::<u8>(|i| i as u8);
+//! assert_eq!(b.byte_shift_left::<1>().as_u8()[15..18], [14, 0, 16]);
+//! ```
+//!
+//! `alignr` needs SSSE3 and is used from the SSE4.1 level; below it the
+//! bytes are copied one by one.
+
+use crate::dispatch::select;
+use crate::lane::Lane;
+use crate::lanes::{U64x2, U64x4};
+use crate::xmm::Xmm;
+use crate::ymm::{by_halves, map_halves, Ymm};
+
+/// Integer lane types that can be shifted.
+pub trait ShiftLane: Lane {
+    #[doc(hidden)]
+    fn shl_imm_xmm<const N: i32>(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shr_imm_xmm<const N: i32>(a: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shl_xmm(a: Xmm, count: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shr_xmm(a: Xmm, count: Xmm) -> Xmm;
+    /// Logical right shift, also for signed lanes.
+    #[doc(hidden)]
+    fn shr_logical_xmm(a: Xmm, count: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shl_each_xmm(a: Xmm, counts: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shr_each_xmm(a: Xmm, counts: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shr_logical_each_xmm(a: Xmm, counts: Xmm) -> Xmm;
+    #[doc(hidden)]
+    fn shl_imm_ymm<const N: i32>(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn shr_imm_ymm<const N: i32>(a: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn shl_ymm(a: Ymm, count: Xmm) -> Ymm;
+    #[doc(hidden)]
+    fn shr_ymm(a: Ymm, count: Xmm) -> Ymm;
+    #[doc(hidden)]
+    fn shr_logical_ymm(a: Ymm, count: Xmm) -> Ymm;
+    #[doc(hidden)]
+    fn shl_each_ymm(a: Ymm, counts: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn shr_each_ymm(a: Ymm, counts: Ymm) -> Ymm;
+    #[doc(hidden)]
+    fn shr_logical_each_ymm(a: Ymm, counts: Ymm) -> Ymm;
+}
+
+/// Implements [`ShiftLane`] with the kernels for the lane width: the left
+/// shifts, the right shifts of the type and the logical right shifts.
+macro_rules! impl_shift_lane {
+    ($t:ty: $slli:ident, $sri:ident; $sll:ident, $sr:ident, $srl:ident; $sllv:ident, $srv:ident, $srlv:ident) => {
+        impl ShiftLane for $t {
+            #[inline(always)]
+            fn shl_imm_xmm<const N: i32>(a: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$slli::<N>(a), _ => scalar::$sll(a, N as u64))
+            }
+
+            #[inline(always)]
+            fn shr_imm_xmm<const N: i32>(a: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$sri::<N>(a), _ => scalar::$sr(a, N as u64))
+            }
+
+            #[inline(always)]
+            fn shl_xmm(a: Xmm, count: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$sll(a, count), _ => scalar::$sll(a, count.as_u64()[0]))
+            }
+
+            #[inline(always)]
+            fn shr_xmm(a: Xmm, count: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$sr(a, count), _ => scalar::$sr(a, count.as_u64()[0]))
+            }
+
+            #[inline(always)]
+            fn shr_logical_xmm(a: Xmm, count: Xmm) -> Xmm {
+                select!(Sse2 => sse2::$srl(a, count), _ => scalar::$srl(a, count.as_u64()[0]))
+            }
+
+            #[inline(always)]
+            fn shl_each_xmm(a: Xmm, counts: Xmm) -> Xmm {
+                select!(
+                    Avx2 => unsafe { avx2::xmm::$sllv(a, counts) },
+                    Sse2 => sse2::$sllv(a, counts),
+                    _ => scalar::$sllv(a, counts),
+                )
+            }
+
+            #[inline(always)]
+            fn shr_each_xmm(a: Xmm, counts: Xmm) -> Xmm {
+                select!(
+                    Avx2 => unsafe { avx2::xmm::$srv(a, counts) },
+                    Sse2 => sse2::$srv(a, counts),
+                    _ => scalar::$srv(a, counts),
+                )
+            }
+
+            #[inline(always)]
+            fn shr_logical_each_xmm(a: Xmm, counts: Xmm) -> Xmm {
+                select!(
+                    Avx2 => unsafe { avx2::xmm::$srlv(a, counts) },
+                    Sse2 => sse2::$srlv(a, counts),
+                    _ => scalar::$srlv(a, counts),
+                )
+            }
+
+            #[inline(always)]
+            fn shl_imm_ymm<const N: i32>(a: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$slli::<N>(a) }, _ => map_halves(a, Self::shl_imm_xmm::<N>))
+            }
+
+            #[inline(always)]
+            fn shr_imm_ymm<const N: i32>(a: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$sri::<N>(a) }, _ => map_halves(a, Self::shr_imm_xmm::<N>))
+            }
+
+            #[inline(always)]
+            fn shl_ymm(a: Ymm, count: Xmm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$sll(a, count) }, _ => {
+                    let (lo, hi) = a.halves();
+                    Ymm::from_halves(Self::shl_xmm(lo, count), Self::shl_xmm(hi, count))
+                })
+            }
+
+            #[inline(always)]
+            fn shr_ymm(a: Ymm, count: Xmm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$sr(a, count) }, _ => {
+                    let (lo, hi) = a.halves();
+                    Ymm::from_halves(Self::shr_xmm(lo, count), Self::shr_xmm(hi, count))
+                })
+            }
+
+            #[inline(always)]
+            fn shr_logical_ymm(a: Ymm, count: Xmm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$srl(a, count) }, _ => {
+                    let (lo, hi) = a.halves();
+                    Ymm::from_halves(Self::shr_logical_xmm(lo, count), Self::shr_logical_xmm(hi, count))
+                })
+            }
+
+            #[inline(always)]
+            fn shl_each_ymm(a: Ymm, counts: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$sllv(a, counts) }, _ => by_halves(a, counts, Self::shl_each_xmm))
+            }
+
+            #[inline(always)]
+            fn shr_each_ymm(a: Ymm, counts: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$srv(a, counts) }, _ => by_halves(a, counts, Self::shr_each_xmm))
+            }
+
+            #[inline(always)]
+            fn shr_logical_each_ymm(a: Ymm, counts: Ymm) -> Ymm {
+                select!(Avx2 => unsafe { avx2::$srlv(a, counts) }, _ => by_halves(a, counts, Self::shr_logical_each_xmm))
+            }
+        }
+    };
+}
+
+impl_shift_lane!(i8: slli_epi8, srai_epi8; sll_epi8, sra_epi8, srl_epi8; sllv_epi8, srav_epi8, srlv_epi8);
+impl_shift_lane!(i16: slli_epi16, srai_epi16; sll_epi16, sra_epi16, srl_epi16; sllv_epi16, srav_epi16, srlv_epi16);
+impl_shift_lane!(i32: slli_epi32, srai_epi32; sll_epi32, sra_epi32, srl_epi32; sllv_epi32, srav_epi32, srlv_epi32);
+impl_shift_lane!(i64: slli_epi64, srai_epi64; sll_epi64, sra_epi64, srl_epi64; sllv_epi64, srav_epi64, srlv_epi64);
+impl_shift_lane!(u8: slli_epi8, srli_epi8; sll_epi8, srl_epi8, srl_epi8; sllv_epi8, srlv_epi8, srlv_epi8);
+impl_shift_lane!(u16: slli_epi16, srli_epi16; sll_epi16, srl_epi16, srl_epi16; sllv_epi16, srlv_epi16, srlv_epi16);
+impl_shift_lane!(u32: slli_epi32, srli_epi32; sll_epi32, srl_epi32, srl_epi32; sllv_epi32, srlv_epi32, srlv_epi32);
+impl_shift_lane!(u64: slli_epi64, srli_epi64; sll_epi64, srl_epi64, srl_epi64; sllv_epi64, srlv_epi64, srlv_epi64);
+
+/// A shared count for the `_by` shifts.
+#[inline(always)]
+fn count(n: u32) -> Xmm {
+    Xmm::from_u64([n as u64, 0])
+}
+
+macro_rules! impl_shift_methods {
+    ($reg:ident, $bits:ident, $shl_imm:ident, $shr_imm:ident, $shl:ident, $shr:ident, $shr_logical:ident,
+     $shl_each:ident, $shr_each:ident, $shr_logical_each:ident) => {
+        impl $reg {
+            /// Shifts every `T` lane left by `N`, shifting in zeros.
+            #[inline(always)]
+            pub fn shift_left<T: ShiftLane, const N: i32>(self) -> $reg {
+                const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+                T::$shl_imm::<N>(self)
+            }
+
+            /// Shifts every `T` lane right by `N`, shifting in sign bits for
+            /// signed `T` and zeros for unsigned `T`.
+            #[inline(always)]
+            pub fn shift_right<T: ShiftLane, const N: i32>(self) -> $reg {
+                const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+                T::$shr_imm::<N>(self)
+            }
+
+            /// Shifts every `T` lane left by the `u64` in the low half of
+            /// `count`.
+            #[inline(always)]
+            pub fn shift_left_by<T: ShiftLane>(self, count: Xmm) -> $reg {
+                T::$shl(self, count)
+            }
+
+            /// Shifts every `T` lane right by the `u64` in the low half of
+            /// `count`, like [`shift_right`](Self::shift_right).
+            #[inline(always)]
+            pub fn shift_right_by<T: ShiftLane>(self, count: Xmm) -> $reg {
+                T::$shr(self, count)
+            }
+
+            /// Shifts every `T` lane left by the same lane of `counts`, read
+            /// as unsigned.
+            #[inline(always)]
+            pub fn shift_left_each<T: ShiftLane>(self, counts: $reg) -> $reg {
+                T::$shl_each(self, counts)
+            }
+
+            /// Shifts every `T` lane right by the same lane of `counts`, read
+            /// as unsigned, like [`shift_right`](Self::shift_right).
+            #[inline(always)]
+            pub fn shift_right_each<T: ShiftLane>(self, counts: $reg) -> $reg {
+                T::$shr_each(self, counts)
+            }
+
+            /// Rotates the bits of every `T` lane left by `N`.
+            #[inline(always)]
+            pub fn rotate_left<T: ShiftLane, const N: i32>(self) -> $reg {
+                const { assert!(0 <= N, "rotate count must not be negative") };
+                let n = N as u32 % T::BITS;
+                ($bits::from(T::$shl(self, count(n))) | $bits::from(T::$shr_logical(self, count(T::BITS - n)))).into()
+            }
+
+            /// Rotates the bits of every `T` lane right by `N`.
+            #[inline(always)]
+            pub fn rotate_right<T: ShiftLane, const N: i32>(self) -> $reg {
+                const { assert!(0 <= N, "rotate count must not be negative") };
+                let n = N as u32 % T::BITS;
+                ($bits::from(T::$shr_logical(self, count(n))) | $bits::from(T::$shl(self, count(T::BITS - n)))).into()
+            }
+
+            /// Rotates the bits of every `T` lane left by the same lane of
+            /// `counts`, modulo the lane width.
+            ///
+            /// Shifting right by `T::BITS - n` would need a count of
+            /// `T::BITS` for `n == 0`, so it shifts by `(T::BITS - 1) ^ n`
+            /// and then by one.
+            #[inline(always)]
+            pub fn rotate_left_each<T: ShiftLane>(self, counts: $reg) -> $reg {
+                let (n, rest) = Self::rotate_counts::<T>(counts);
+                let right = T::$shr_logical(T::$shr_logical_each(self, rest), count(1));
+                ($bits::from(T::$shl_each(self, n)) | $bits::from(right)).into()
+            }
+
+            /// Rotates the bits of every `T` lane right by the same lane of
+            /// `counts`, modulo the lane width.
+            #[inline(always)]
+            pub fn rotate_right_each<T: ShiftLane>(self, counts: $reg) -> $reg {
+                let (n, rest) = Self::rotate_counts::<T>(counts);
+                let left = T::$shl(T::$shl_each(self, rest), count(1));
+                ($bits::from(T::$shr_logical_each(self, n)) | $bits::from(left)).into()
+            }
+
+            /// `counts` modulo the lane width, and `T::BITS - 1` minus that.
+            #[inline(always)]
+            fn rotate_counts<T: ShiftLane>(counts: $reg) -> ($reg, $reg) {
+                let width = std::mem::size_of::<T>();
+                let m = $bits::from($reg::from_fn::<u8>(|i| if i % width == 0 { T::BITS as u8 - 1 } else { 0 }));
+                let n = $bits::from(counts) & m;
+                (n.into(), (n ^ m).into())
+            }
+        }
+    };
+}
+
+impl_shift_methods!(Xmm, U64x2, shl_imm_xmm, shr_imm_xmm, shl_xmm, shr_xmm, shr_logical_xmm,
+    shl_each_xmm, shr_each_xmm, shr_logical_each_xmm);
+impl_shift_methods!(Ymm, U64x4, shl_imm_ymm, shr_imm_ymm, shl_ymm, shr_ymm, shr_logical_ymm,
+    shl_each_ymm, shr_each_ymm, shr_logical_each_ymm);
+
+impl Xmm {
+    /// Shifts the whole register left by `N` bytes, towards the higher
+    /// lanes, like `_mm_slli_si128`. From `N == 16` it is cleared.
+    #[inline(always)]
+    pub fn byte_shift_left<const N: i32>(self) -> Xmm {
+        const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+        select!(Sse2 => sse2::bslli::<N>(self), _ => scalar::bslli(self, N as usize))
+    }
+
+    /// Shifts the whole register right by `N` bytes, towards lane 0, like
+    /// `_mm_srli_si128`. From `N == 16` it is cleared.
+    #[inline(always)]
+    pub fn byte_shift_right<const N: i32>(self) -> Xmm {
+        const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+        select!(Sse2 => sse2::bsrli::<N>(self), _ => scalar::bsrli(self, N as usize))
+    }
+
+    /// Returns the 16 bytes from byte `N` of `b` followed by `self`, like
+    /// `_mm_alignr_epi8(self, b, N)`: `b` for `N == 0`, `self` for
+    /// `N == 16` and zeros past the end.
+    #[inline(always)]
+    pub fn alignr<const N: i32>(self, b: Xmm) -> Xmm {
+        const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+        select!(Sse41 => unsafe { sse41::alignr::<N>(self, b) }, _ => scalar::alignr(self, b, N as usize))
+    }
+}
+
+impl Ymm {
+    /// Shifts each 128-bit half left by `N` bytes, like
+    /// `_mm256_bslli_epi128`. No byte crosses from the low half into the
+    /// high one, which fills with zeros like the low one.
+    #[inline(always)]
+    pub fn byte_shift_left<const N: i32>(self) -> Ymm {
+        const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+        select!(Avx2 => unsafe { avx2::bslli::<N>(self) }, _ => map_halves(self, Xmm::byte_shift_left::<N>))
+    }
+
+    /// Shifts each 128-bit half right by `N` bytes, like
+    /// `_mm256_bsrli_epi128`. The high half shifts in zeros, not the bytes
+    /// of the low half.
+    #[inline(always)]
+    pub fn byte_shift_right<const N: i32>(self) -> Ymm {
+        const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+        select!(Avx2 => unsafe { avx2::bsrli::<N>(self) }, _ => map_halves(self, Xmm::byte_shift_right::<N>))
+    }
+
+    /// [`Xmm::alignr`] of the low halves of `self` and `b` and of their high
+    /// halves, like `_mm256_alignr_epi8`.
+    #[inline(always)]
+    pub fn alignr<const N: i32>(self, b: Ymm) -> Ymm {
+        const { assert!(0 <= N && N < 256, "shift count must be in 0..256") };
+        select!(Avx2 => unsafe { avx2::alignr::<N>(self, b) }, _ => by_halves(self, b, Xmm::alignr::<N>))
+    }
+}
+
+/// Portable kernels over any register, named after their SSE2 counterparts.
+mod scalar {
+    use crate::chunks::Register;
+    use crate::scalar::{map, zip};
+
+    macro_rules! shifts {
+        ($($u:ty, $i:ty => $sll:ident, $srl:ident, $sra:ident, $sllv:ident, $srlv:ident, $srav:ident;)*) => {$(
+            #[inline]
+            pub fn $sll<R: Register>(a: R, count: u64) -> R {
+                map(a, |x: $u| if count < <$u>::BITS as u64 { x << count } else { 0 })
+            }
+
+            #[inline]
+            pub fn $srl<R: Register>(a: R, count: u64) -> R {
+                map(a, |x: $u| if count < <$u>::BITS as u64 { x >> count } else { 0 })
+            }
+
+            #[inline]
+            pub fn $sra<R: Register>(a: R, count: u64) -> R {
+                map(a, |x: $i| x >> count.min(<$i>::BITS as u64 - 1))
+            }
+
+            #[inline]
+            pub fn $sllv<R: Register>(a: R, counts: R) -> R {
+                zip(a, counts, |x: $u, n| x.checked_shl(n as u32).unwrap_or(0))
+            }
+
+            #[inline]
+            pub fn $srlv<R: Register>(a: R, counts: R) -> R {
+                zip(a, counts, |x: $u, n| x.checked_shr(n as u32).unwrap_or(0))
+            }
+
+            #[inline]
+            pub fn $srav<R: Register>(a: R, counts: R) -> R {
+                zip(a, counts, |x: $i, n| x >> (n as $u).min(<$i>::BITS as $u - 1))
+            }
+        )*};
+    }
+
+    shifts! {
+        u8, i8 => sll_epi8, srl_epi8, sra_epi8, sllv_epi8, srlv_epi8, srav_epi8;
+        u16, i16 => sll_epi16, srl_epi16, sra_epi16, sllv_epi16, srlv_epi16, srav_epi16;
+        u32, i32 => sll_epi32, srl_epi32, sra_epi32, sllv_epi32, srlv_epi32, srav_epi32;
+        u64, i64 => sll_epi64, srl_epi64, sra_epi64, sllv_epi64, srlv_epi64, srav_epi64;
+    }
+
+    /// Shifts each 16 bytes of `a` left by `n` bytes.
+    #[inline]
+    pub fn bslli<R: Register>(a: R, n: usize) -> R {
+        let mut out = R::default();
+        for (o, a) in out.lanes_mut::<u8>().chunks_exact_mut(16).zip(a.lanes::<u8>().chunks_exact(16)) {
+            if n < 16 {
+                o[n..].copy_from_slice(&a[..16 - n]);
+            }
+        }
+        out
+    }
+
+    #[inline]
+    pub fn bsrli<R: Register>(a: R, n: usize) -> R {
+        let mut out = R::default();
+        for (o, a) in out.lanes_mut::<u8>().chunks_exact_mut(16).zip(a.lanes::<u8>().chunks_exact(16)) {
+            if n < 16 {
+                o[..16 - n].copy_from_slice(&a[n..]);
+            }
+        }
+        out
+    }
+
+    /// Each 16 bytes of `b` followed by the same 16 of `a`, from byte `n`.
+    #[inline]
+    pub fn alignr<R: Register>(a: R, b: R, n: usize) -> R {
+        let mut out = R::default();
+        let pairs = a.lanes::<u8>().chunks_exact(16).zip(b.lanes::<u8>().chunks_exact(16));
+        for (o, (a, b)) in out.lanes_mut::<u8>().chunks_exact_mut(16).zip(pairs) {
+            for (i, x) in o.iter_mut().enumerate() {
+                *x = b.iter().chain(a).nth(n + i).copied().unwrap_or(0);
+            }
+        }
+        out
+    }
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    macro_rules! immediate {
+        ($($name:ident => $intrinsic:ident),*) => {$(
+            #[inline(always)]
+            pub fn $name<const N: i32>(a: Xmm) -> Xmm {
+                unsafe { $intrinsic::<N>(a.into()) }.into()
+            }
+        )*};
+    }
+
+    macro_rules! uniform {
+        ($($name:ident => $intrinsic:ident),*) => {$(
+            #[inline(always)]
+            pub fn $name(a: Xmm, count: Xmm) -> Xmm {
+                unsafe { $intrinsic(a.into(), count.into()) }.into()
+            }
+        )*};
+    }
+
+    macro_rules! binary {
+        ($($name:ident => $intrinsic:ident),*) => {$(
+            #[inline(always)]
+            fn $name(a: Xmm, b: Xmm) -> Xmm {
+                unsafe { $intrinsic(a.into(), b.into()) }.into()
+            }
+        )*};
+    }
+
+    immediate!(
+        slli_epi16 => _mm_slli_epi16, slli_epi32 => _mm_slli_epi32, slli_epi64 => _mm_slli_epi64,
+        srli_epi16 => _mm_srli_epi16, srli_epi32 => _mm_srli_epi32, srli_epi64 => _mm_srli_epi64,
+        srai_epi16 => _mm_srai_epi16, srai_epi32 => _mm_srai_epi32, bslli => _mm_slli_si128, bsrli => _mm_srli_si128
+    );
+    uniform!(
+        sll_epi16 => _mm_sll_epi16, sll_epi32 => _mm_sll_epi32, sll_epi64 => _mm_sll_epi64,
+        srl_epi16 => _mm_srl_epi16, srl_epi32 => _mm_srl_epi32, srl_epi64 => _mm_srl_epi64,
+        sra_epi16 => _mm_sra_epi16, sra_epi32 => _mm_sra_epi32
+    );
+    binary!(
+        and => _mm_and_si128, andnot => _mm_andnot_si128, or => _mm_or_si128, xor => _mm_xor_si128,
+        add_epi8 => _mm_add_epi8, add_epi16 => _mm_add_epi16, cmpeq_epi8 => _mm_cmpeq_epi8,
+        cmpeq_epi16 => _mm_cmpeq_epi16
+    );
+
+    /// `x` where `m` is all ones, else `y`.
+    #[inline(always)]
+    fn select(m: Xmm, x: Xmm, y: Xmm) -> Xmm {
+        or(and(m, x), andnot(m, y))
+    }
+
+    /// Shifts 16-bit lanes, then clears the bits that crossed into the other
+    /// byte.
+    #[inline(always)]
+    pub fn slli_epi8<const N: i32>(a: Xmm) -> Xmm {
+        and(slli_epi16::<N>(a), Xmm::splat_u8(0xFFu32.checked_shl(N as u32).unwrap_or(0) as u8))
+    }
+
+    #[inline(always)]
+    pub fn srli_epi8<const N: i32>(a: Xmm) -> Xmm {
+        and(srli_epi16::<N>(a), Xmm::splat_u8(0xFFu32.checked_shr(N as u32).unwrap_or(0) as u8))
+    }
+
+    /// The masks are the byte masks of the immediate shifts, computed by
+    /// shifting the byte that does not lose its bits to the other one.
+    #[inline(always)]
+    pub fn sll_epi8(a: Xmm, count: Xmm) -> Xmm {
+        let m = sll_epi16(Xmm::splat_u16(0xFF00), count);
+        and(sll_epi16(a, count), or(m, srli_epi16::<8>(m)))
+    }
+
+    #[inline(always)]
+    pub fn srl_epi8(a: Xmm, count: Xmm) -> Xmm {
+        let m = srl_epi16(Xmm::splat_u16(0x00FF), count);
+        and(srl_epi16(a, count), or(m, slli_epi16::<8>(m)))
+    }
+
+    /// `sra` shifts the high byte of each 16-bit lane right in place, and
+    /// the low byte once moved to the top.
+    #[inline(always)]
+    fn sra_bytes(a: Xmm, sra: impl Fn(Xmm) -> Xmm) -> Xmm {
+        or(and(sra(a), Xmm::splat_u16(0xFF00)), srli_epi16::<8>(sra(slli_epi16::<8>(a))))
+    }
+
+    #[inline(always)]
+    pub fn srai_epi8<const N: i32>(a: Xmm) -> Xmm {
+        sra_bytes(a, srai_epi16::<N>)
+    }
+
+    #[inline(always)]
+    pub fn sra_epi8(a: Xmm, count: Xmm) -> Xmm {
+        sra_bytes(a, |x| sra_epi16(x, count))
+    }
+
+    /// `srl` flips the bits of negative lanes before and after the logical
+    /// shift, which then shifts in ones.
+    #[inline(always)]
+    fn sra_qwords(a: Xmm, srl: impl Fn(Xmm) -> Xmm) -> Xmm {
+        let sign = unsafe { _mm_srai_epi32::<31>(_mm_shuffle_epi32::<0b11_11_01_01>(a.into())) }.into();
+        xor(srl(xor(a, sign)), sign)
+    }
+
+    #[inline(always)]
+    pub fn srai_epi64<const N: i32>(a: Xmm) -> Xmm {
+        sra_qwords(a, srli_epi64::<N>)
+    }
+
+    #[inline(always)]
+    pub fn sra_epi64(a: Xmm, count: Xmm) -> Xmm {
+        sra_qwords(a, |x| srl_epi64(x, count))
+    }
+
+    /// Shifts each byte by 4, 2 and 1 where the count has that bit, moved to
+    /// the top of the byte to select.
+    #[inline(always)]
+    fn by_bits_epi8(mut a: Xmm, counts: Xmm, shift: impl Fn(Xmm, Xmm) -> Xmm) -> Xmm {
+        let mut m = slli_epi16::<5>(counts);
+        for n in [4, 2, 1] {
+            let bit = cmpeq_epi8(and(m, Xmm::splat_u8(0x80)), Xmm::splat_u8(0x80));
+            a = select(bit, shift(a, Xmm::from_u64([n, 0])), a);
+            m = add_epi8(m, m);
+        }
+        a
+    }
+
+    /// Shifts each 16-bit lane by 8, 4, 2 and 1 where the count has that bit.
+    #[inline(always)]
+    fn by_bits_epi16(mut a: Xmm, counts: Xmm, shift: impl Fn(Xmm, Xmm) -> Xmm) -> Xmm {
+        let mut m = slli_epi16::<12>(counts);
+        for n in [8, 4, 2, 1] {
+            a = select(srai_epi16::<15>(m), shift(a, Xmm::from_u64([n, 0])), a);
+            m = add_epi16(m, m);
+        }
+        a
+    }
+
+    /// All ones in the lanes whose count is below the lane width.
+    #[inline(always)]
+    fn in_range_epi8(counts: Xmm) -> Xmm {
+        cmpeq_epi8(and(counts, Xmm::splat_u8(0xF8)), Xmm::default())
+    }
+
+    #[inline(always)]
+    fn in_range_epi16(counts: Xmm) -> Xmm {
+        cmpeq_epi16(and(counts, Xmm::splat_u16(0xFFF0)), Xmm::default())
+    }
+
+    #[inline(always)]
+    pub fn sllv_epi8(a: Xmm, counts: Xmm) -> Xmm {
+        and(by_bits_epi8(a, counts, sll_epi8), in_range_epi8(counts))
+    }
+
+    #[inline(always)]
+    pub fn srlv_epi8(a: Xmm, counts: Xmm) -> Xmm {
+        and(by_bits_epi8(a, counts, srl_epi8), in_range_epi8(counts))
+    }
+
+    #[inline(always)]
+    pub fn srav_epi8(a: Xmm, counts: Xmm) -> Xmm {
+        select(in_range_epi8(counts), by_bits_epi8(a, counts, sra_epi8), srai_epi8::<7>(a))
+    }
+
+    #[inline(always)]
+    pub fn sllv_epi16(a: Xmm, counts: Xmm) -> Xmm {
+        and(by_bits_epi16(a, counts, sll_epi16), in_range_epi16(counts))
+    }
+
+    #[inline(always)]
+    pub fn srlv_epi16(a: Xmm, counts: Xmm) -> Xmm {
+        and(by_bits_epi16(a, counts, srl_epi16), in_range_epi16(counts))
+    }
+
+    #[inline(always)]
+    pub fn srav_epi16(a: Xmm, counts: Xmm) -> Xmm {
+        select(in_range_epi16(counts), by_bits_epi16(a, counts, sra_epi16), srai_epi16::<15>(a))
+    }
+
+    /// Shifts the whole register by each count in turn and keeps the lane
+    /// of each.
+    #[inline(always)]
+    fn per_dword(a: Xmm, counts: Xmm, shift: impl Fn(Xmm, Xmm) -> Xmm) -> Xmm {
+        unsafe {
+            let c = __m128i::from(counts);
+            let low = _mm_set_epi32(0, -1, 0, -1);
+            let s0 = shift(a, _mm_and_si128(c, low).into());
+            let s1 = shift(a, _mm_and_si128(_mm_srli_si128::<4>(c), low).into());
+            let s2 = shift(a, _mm_and_si128(_mm_srli_si128::<8>(c), low).into());
+            let s3 = shift(a, _mm_srli_si128::<12>(c).into());
+            let s01 = _mm_shuffle_ps::<0b01_01_00_00>(s0.into(), s1.into());
+            let s23 = _mm_shuffle_ps::<0b11_11_10_10>(s2.into(), s3.into());
+            _mm_shuffle_ps::<0b10_00_10_00>(s01, s23).into()
+        }
+    }
+
+    #[inline(always)]
+    fn per_qword(a: Xmm, counts: Xmm, shift: impl Fn(Xmm, Xmm) -> Xmm) -> Xmm {
+        unsafe {
+            let high = _mm_unpackhi_epi64(counts.into(), counts.into());
+            _mm_move_sd(shift(a, high.into()).into(), shift(a, counts).into()).into()
+        }
+    }
+
+    #[inline(always)]
+    pub fn sllv_epi32(a: Xmm, counts: Xmm) -> Xmm {
+        per_dword(a, counts, sll_epi32)
+    }
+
+    #[inline(always)]
+    pub fn srlv_epi32(a: Xmm, counts: Xmm) -> Xmm {
+        per_dword(a, counts, srl_epi32)
+    }
+
+    #[inline(always)]
+    pub fn srav_epi32(a: Xmm, counts: Xmm) -> Xmm {
+        per_dword(a, counts, sra_epi32)
+    }
+
+    #[inline(always)]
+    pub fn sllv_epi64(a: Xmm, counts: Xmm) -> Xmm {
+        per_qword(a, counts, sll_epi64)
+    }
+
+    #[inline(always)]
+    pub fn srlv_epi64(a: Xmm, counts: Xmm) -> Xmm {
+        per_qword(a, counts, srl_epi64)
+    }
+
+    #[inline(always)]
+    pub fn srav_epi64(a: Xmm, counts: Xmm) -> Xmm {
+        sra_qwords(a, |x| srlv_epi64(x, counts))
+    }
+}
+
+/// SSE4.1 kernels over [`Xmm`]. Callers must check
+/// [`has_sse41`](crate::dispatch::has_sse41) first.
+#[cfg(simd_x86)]
+mod sse41 {
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    /// `palignr` is SSSE3, which every SSE4.1 processor has.
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    pub unsafe fn alignr<const N: i32>(a: Xmm, b: Xmm) -> Xmm {
+        _mm_alignr_epi8::<N>(a.into(), b.into()).into()
+    }
+}
+
+/// AVX2 kernels over [`Ymm`], and in [`xmm`] the variable shifts of [`Xmm`].
+/// Callers must check [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use crate::xmm::Xmm;
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    macro_rules! immediate {
+        ($($name:ident => $intrinsic:ident),*) => {$(
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name<const N: i32>(a: Ymm) -> Ymm {
+                $intrinsic::<N>(a.into()).into()
+            }
+        )*};
+    }
+
+    macro_rules! uniform {
+        ($($name:ident => $intrinsic:ident),*) => {$(
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, count: Xmm) -> Ymm {
+                $intrinsic(a.into(), count.into()).into()
+            }
+        )*};
+    }
+
+    macro_rules! variable {
+        ($($name:ident => $intrinsic:ident),*) => {$(
+            #[inline]
+            #[target_feature(enable = "avx2")]
+            pub unsafe fn $name(a: Ymm, counts: Ymm) -> Ymm {
+                $intrinsic(a.into(), counts.into()).into()
+            }
+        )*};
+    }
+
+    immediate!(
+        slli_epi16 => _mm256_slli_epi16, slli_epi32 => _mm256_slli_epi32, slli_epi64 => _mm256_slli_epi64,
+        srli_epi16 => _mm256_srli_epi16, srli_epi32 => _mm256_srli_epi32, srli_epi64 => _mm256_srli_epi64,
+        srai_epi16 => _mm256_srai_epi16, srai_epi32 => _mm256_srai_epi32,
+        bslli => _mm256_bslli_epi128, bsrli => _mm256_bsrli_epi128
+    );
+    uniform!(
+        sll_epi16 => _mm256_sll_epi16, sll_epi32 => _mm256_sll_epi32, sll_epi64 => _mm256_sll_epi64,
+        srl_epi16 => _mm256_srl_epi16, srl_epi32 => _mm256_srl_epi32, srl_epi64 => _mm256_srl_epi64,
+        sra_epi16 => _mm256_sra_epi16, sra_epi32 => _mm256_sra_epi32
+    );
+    variable!(
+        sllv_epi32 => _mm256_sllv_epi32, sllv_epi64 => _mm256_sllv_epi64,
+        srlv_epi32 => _mm256_srlv_epi32, srlv_epi64 => _mm256_srlv_epi64, srav_epi32 => _mm256_srav_epi32
+    );
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn alignr<const N: i32>(a: Ymm, b: Ymm) -> Ymm {
+        _mm256_alignr_epi8::<N>(a.into(), b.into()).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn slli_epi8<const N: i32>(a: Ymm) -> Ymm {
+        let m = Ymm::splat_u8(0xFFu32.checked_shl(N as u32).unwrap_or(0) as u8);
+        _mm256_and_si256(_mm256_slli_epi16::<N>(a.into()), m.into()).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srli_epi8<const N: i32>(a: Ymm) -> Ymm {
+        let m = Ymm::splat_u8(0xFFu32.checked_shr(N as u32).unwrap_or(0) as u8);
+        _mm256_and_si256(_mm256_srli_epi16::<N>(a.into()), m.into()).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srai_epi8<const N: i32>(a: Ymm) -> Ymm {
+        let a = __m256i::from(a);
+        let hi = _mm256_and_si256(_mm256_srai_epi16::<N>(a), Ymm::splat_u16(0xFF00).into());
+        _mm256_or_si256(hi, _mm256_srli_epi16::<8>(_mm256_srai_epi16::<N>(_mm256_slli_epi16::<8>(a)))).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sll_epi8(a: Ymm, count: Xmm) -> Ymm {
+        let (a, count) = (__m256i::from(a), __m128i::from(count));
+        let m = _mm256_sll_epi16(Ymm::splat_u16(0xFF00).into(), count);
+        _mm256_and_si256(_mm256_sll_epi16(a, count), _mm256_or_si256(m, _mm256_srli_epi16::<8>(m))).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srl_epi8(a: Ymm, count: Xmm) -> Ymm {
+        let (a, count) = (__m256i::from(a), __m128i::from(count));
+        let m = _mm256_srl_epi16(Ymm::splat_u16(0x00FF).into(), count);
+        _mm256_and_si256(_mm256_srl_epi16(a, count), _mm256_or_si256(m, _mm256_slli_epi16::<8>(m))).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sra_epi8(a: Ymm, count: Xmm) -> Ymm {
+        let (a, count) = (__m256i::from(a), __m128i::from(count));
+        let hi = _mm256_and_si256(_mm256_sra_epi16(a, count), Ymm::splat_u16(0xFF00).into());
+        _mm256_or_si256(hi, _mm256_srli_epi16::<8>(_mm256_sra_epi16(_mm256_slli_epi16::<8>(a), count))).into()
+    }
+
+    /// All ones in the negative 64-bit lanes.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn sign_epi64(a: __m256i) -> __m256i {
+        _mm256_srai_epi32::<31>(_mm256_shuffle_epi32::<0b11_11_01_01>(a))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srai_epi64<const N: i32>(a: Ymm) -> Ymm {
+        let sign = sign_epi64(a.into());
+        _mm256_xor_si256(_mm256_srli_epi64::<N>(_mm256_xor_si256(a.into(), sign)), sign).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sra_epi64(a: Ymm, count: Xmm) -> Ymm {
+        let sign = sign_epi64(a.into());
+        _mm256_xor_si256(_mm256_srl_epi64(_mm256_xor_si256(a.into(), sign), count.into()), sign).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srav_epi64(a: Ymm, counts: Ymm) -> Ymm {
+        let sign = sign_epi64(a.into());
+        _mm256_xor_si256(_mm256_srlv_epi64(_mm256_xor_si256(a.into(), sign), counts.into()), sign).into()
+    }
+
+    /// 16-bit lanes shift as the low and the high half of 32-bit lanes,
+    /// with the bits that cross between them masked off.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sllv_epi16(a: Ymm, counts: Ymm) -> Ymm {
+        let (a, counts) = (__m256i::from(a), __m256i::from(counts));
+        let low = _mm256_set1_epi32(0xFFFF);
+        let lo = _mm256_and_si256(_mm256_sllv_epi32(a, _mm256_and_si256(counts, low)), low);
+        let hi = _mm256_sllv_epi32(_mm256_andnot_si256(low, a), _mm256_srli_epi32::<16>(counts));
+        _mm256_or_si256(lo, hi).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srlv_epi16(a: Ymm, counts: Ymm) -> Ymm {
+        let (a, counts) = (__m256i::from(a), __m256i::from(counts));
+        let low = _mm256_set1_epi32(0xFFFF);
+        let lo = _mm256_srlv_epi32(_mm256_and_si256(a, low), _mm256_and_si256(counts, low));
+        let hi = _mm256_andnot_si256(low, _mm256_srlv_epi32(a, _mm256_srli_epi32::<16>(counts)));
+        _mm256_or_si256(lo, hi).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srav_epi16(a: Ymm, counts: Ymm) -> Ymm {
+        let (a, counts) = (__m256i::from(a), __m256i::from(counts));
+        let low = _mm256_set1_epi32(0xFFFF);
+        let lo = _mm256_srli_epi32::<16>(_mm256_srav_epi32(_mm256_slli_epi32::<16>(a), _mm256_and_si256(counts, low)));
+        let hi = _mm256_andnot_si256(low, _mm256_srav_epi32(a, _mm256_srli_epi32::<16>(counts)));
+        _mm256_or_si256(lo, hi).into()
+    }
+
+    /// Shifts each byte by 4, 2 and 1 where the count has that bit, moved to
+    /// the top of the byte for `vpblendvb`. Returns the lanes whose count is
+    /// below 8 too.
+    macro_rules! by_bits_epi8 {
+        ($a:expr, $counts:expr, $shift:ident) => {{
+            let (mut a, counts) = (__m256i::from($a), __m256i::from($counts));
+            let mut m = _mm256_slli_epi16::<5>(counts);
+            a = _mm256_blendv_epi8(a, $shift::<4>(a.into()).into(), m);
+            m = _mm256_add_epi8(m, m);
+            a = _mm256_blendv_epi8(a, $shift::<2>(a.into()).into(), m);
+            m = _mm256_add_epi8(m, m);
+            a = _mm256_blendv_epi8(a, $shift::<1>(a.into()).into(), m);
+            let in_range = _mm256_cmpeq_epi8(_mm256_and_si256(counts, _mm256_set1_epi8(0xF8u8 as i8)), _mm256_setzero_si256());
+            (a, in_range)
+        }};
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sllv_epi8(a: Ymm, counts: Ymm) -> Ymm {
+        let (shifted, in_range) = by_bits_epi8!(a, counts, slli_epi8);
+        _mm256_and_si256(shifted, in_range).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srlv_epi8(a: Ymm, counts: Ymm) -> Ymm {
+        let (shifted, in_range) = by_bits_epi8!(a, counts, srli_epi8);
+        _mm256_and_si256(shifted, in_range).into()
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn srav_epi8(a: Ymm, counts: Ymm) -> Ymm {
+        let (shifted, in_range) = by_bits_epi8!(a, counts, srai_epi8);
+        _mm256_blendv_epi8(srai_epi8::<7>(a).into(), shifted, in_range).into()
+    }
+
+    /// The 128-bit variable shifts of AVX2. Bytes use the SSE2 emulation.
+    pub mod xmm {
+        use crate::xmm::Xmm;
+        use std::arch::x86_64::*;
+
+        macro_rules! variable {
+            ($($name:ident => $intrinsic:ident),*) => {$(
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                pub unsafe fn $name(a: Xmm, counts: Xmm) -> Xmm {
+                    $intrinsic(a.into(), counts.into()).into()
+                }
+            )*};
+        }
+
+        macro_rules! emulated {
+            ($($name:ident),*) => {$(
+                #[inline]
+                #[target_feature(enable = "avx2")]
+                pub unsafe fn $name(a: Xmm, counts: Xmm) -> Xmm {
+                    super::super::sse2::$name(a, counts)
+                }
+            )*};
+        }
+
+        variable!(
+            sllv_epi32 => _mm_sllv_epi32, sllv_epi64 => _mm_sllv_epi64,
+            srlv_epi32 => _mm_srlv_epi32, srlv_epi64 => _mm_srlv_epi64, srav_epi32 => _mm_srav_epi32
+        );
+        emulated!(sllv_epi8, srlv_epi8, srav_epi8);
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn srav_epi64(a: Xmm, counts: Xmm) -> Xmm {
+            let a = __m128i::from(a);
+            let sign = _mm_srai_epi32::<31>(_mm_shuffle_epi32::<0b11_11_01_01>(a));
+            _mm_xor_si128(_mm_srlv_epi64(_mm_xor_si128(a, sign), counts.into()), sign).into()
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn sllv_epi16(a: Xmm, counts: Xmm) -> Xmm {
+            let (a, counts) = (__m128i::from(a), __m128i::from(counts));
+            let low = _mm_set1_epi32(0xFFFF);
+            let lo = _mm_and_si128(_mm_sllv_epi32(a, _mm_and_si128(counts, low)), low);
+            let hi = _mm_sllv_epi32(_mm_andnot_si128(low, a), _mm_srli_epi32::<16>(counts));
+            _mm_or_si128(lo, hi).into()
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn srlv_epi16(a: Xmm, counts: Xmm) -> Xmm {
+            let (a, counts) = (__m128i::from(a), __m128i::from(counts));
+            let low = _mm_set1_epi32(0xFFFF);
+            let lo = _mm_srlv_epi32(_mm_and_si128(a, low), _mm_and_si128(counts, low));
+            let hi = _mm_andnot_si128(low, _mm_srlv_epi32(a, _mm_srli_epi32::<16>(counts)));
+            _mm_or_si128(lo, hi).into()
+        }
+
+        #[inline]
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn srav_epi16(a: Xmm, counts: Xmm) -> Xmm {
+            let (a, counts) = (__m128i::from(a), __m128i::from(counts));
+            let low = _mm_set1_epi32(0xFFFF);
+            let lo = _mm_srli_epi32::<16>(_mm_srav_epi32(_mm_slli_epi32::<16>(a), _mm_and_si128(counts, low)));
+            let hi = _mm_andnot_si128(low, _mm_srav_epi32(a, _mm_srli_epi32::<16>(counts)));
+            _mm_or_si128(lo, hi).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::chunks::Register;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    /// Counts around the lane width, and some far past it.
+    fn counts<T: Lane>() -> Vec<u64> {
+        let bits = T::BITS as u64;
+        vec![0, 1, bits / 2 + 1, bits - 1, bits, bits + 1, 255, 256, 1 << 33, u64::MAX]
+    }
+
+    fn random<R: Register>() -> R {
+        let mut rng = rand::thread_rng();
+        let mut reg = R::default();
+        reg.lanes_mut::<u8>().iter_mut().for_each(|x| *x = rng.gen());
+        reg
+    }
+
+    macro_rules! check_immediates {
+        ($a:expr, $t:ty, $expect:expr, $shl:expr, $shr:expr, $at:expr; $($n:literal),*) => {$(
+            assert_eq!($a.shift_left::<$t, $n>(), $expect($a, &|x, _| $shl(x, $n)), "shl {} {}", $n, $at);
+            assert_eq!($a.shift_right::<$t, $n>(), $expect($a, &|x, _| $shr(x, $n)), "shr {} {}", $n, $at);
+            assert_eq!($a.rotate_left::<$t, $n>(), $expect($a, &|x, _| x.rotate_left($n)), "rotl {} {}", $n, $at);
+            assert_eq!($a.rotate_right::<$t, $n>(), $expect($a, &|x, _| x.rotate_right($n)), "rotr {} {}", $n, $at);
+        )*};
+    }
+
+    macro_rules! check_shift {
+        ($reg:ident, $t:ty, $u:ty) => {{
+            let bits = <$t>::BITS;
+            let shl = |x: $t, n: u64| if n < bits as u64 { ((x as $u) << n) as $t } else { 0 };
+            let shr = |x: $t, n: u64| x.checked_shr(n.min(u32::MAX as u64) as u32).unwrap_or(if x < (0 as $t) { !0 } else { 0 });
+            let shr_signed = |x: $t, n: u64| if <$t>::MIN == 0 as $t { shr(x, n) } else { shr(x, n.min(bits as u64 - 1)) };
+            let expect = |a: $reg, f: &dyn Fn($t, usize) -> $t| $reg::from_fn::<$t>(|i| f(a.lanes::<$t>()[i], i));
+            let mut rng = rand::thread_rng();
+
+            for _ in 0..16 {
+                let a: $reg = random();
+                let mut counts_each = random::<$reg>();
+                for (i, n) in counts_each.lanes_mut::<$t>().iter_mut().enumerate() {
+                    if i % 3 != 0 {
+                        *n = rng.gen_range(0..2 * bits) as $t;
+                    }
+                }
+                let count_of = |i: usize| counts_each.lanes::<$t>()[i] as $u as u64;
+
+                for isa in Isa::supported() {
+                    with_isa(isa, || {
+                        let at = format!("{} {} at {}", stringify!($reg), stringify!($t), isa);
+                        check_immediates!(a, $t, expect, shl, shr_signed, at; 0, 1, 3, 7, 8, 9, 15, 16, 17, 31, 32, 33, 63, 64, 65, 200, 255);
+
+                        for n in counts::<$t>() {
+                            let count = Xmm::from_u64([n, rng.gen()]);
+                            assert_eq!(a.shift_left_by::<$t>(count), expect(a, &|x, _| shl(x, n)), "shl by {} {}", n, at);
+                            assert_eq!(a.shift_right_by::<$t>(count), expect(a, &|x, _| shr_signed(x, n)), "shr by {} {}", n, at);
+                        }
+
+                        let each = |f: &dyn Fn($t, u64) -> $t| expect(a, &|x, i| f(x, count_of(i)));
+                        assert_eq!(a.shift_left_each::<$t>(counts_each), each(&|x, n| shl(x, n)), "shl each {}", at);
+                        assert_eq!(a.shift_right_each::<$t>(counts_each), each(&|x, n| shr_signed(x, n)), "shr each {}", at);
+                        assert_eq!(a.rotate_left_each::<$t>(counts_each), each(&|x, n| x.rotate_left(n as u32)), "rotl each {}", at);
+                        assert_eq!(a.rotate_right_each::<$t>(counts_each), each(&|x, n| x.rotate_right(n as u32)), "rotr each {}", at);
+                    });
+                }
+            }
+        }};
+    }
+
+    #[test]
+    fn test_xmm_shifts() {
+        check_shift!(Xmm, i8, u8);
+        check_shift!(Xmm, u8, u8);
+        check_shift!(Xmm, i16, u16);
+        check_shift!(Xmm, u16, u16);
+        check_shift!(Xmm, i32, u32);
+        check_shift!(Xmm, u32, u32);
+        check_shift!(Xmm, i64, u64);
+        check_shift!(Xmm, u64, u64);
+    }
+
+    #[test]
+    fn test_ymm_shifts() {
+        check_shift!(Ymm, i8, u8);
+        check_shift!(Ymm, u8, u8);
+        check_shift!(Ymm, i16, u16);
+        check_shift!(Ymm, u16, u16);
+        check_shift!(Ymm, i32, u32);
+        check_shift!(Ymm, u32, u32);
+        check_shift!(Ymm, i64, u64);
+        check_shift!(Ymm, u64, u64);
+    }
+
+    macro_rules! check_bytes {
+        ($reg:ident, $a:expr, $b:expr, $at:expr; $($n:literal),*) => {$(
+            // Byte `i` of the half starting at byte `h`, zero outside it.
+            let byte = |r: $reg, h: usize, i: usize| if i < 16 { r.lanes::<u8>()[h + i] } else { 0 };
+            let expect = |f: &dyn Fn(usize, usize) -> u8| $reg::from_fn::<u8>(|i| f(i / 16 * 16, i % 16));
+            let left = expect(&|h, i| i.checked_sub($n).map_or(0, |j| byte($a, h, j)));
+            let right = expect(&|h, i| byte($a, h, i + $n));
+            let alignr = expect(&|h, i| if i + $n < 16 { byte($b, h, i + $n) } else { byte($a, h, i + $n - 16) });
+            assert_eq!($a.byte_shift_left::<$n>(), left, "byte shift left {} {}", $n, $at);
+            assert_eq!($a.byte_shift_right::<$n>(), right, "byte shift right {} {}", $n, $at);
+            assert_eq!($a.alignr::<$n>($b), alignr, "alignr {} {}", $n, $at);
+        )*};
+    }
+
+    #[test]
+    fn test_byte_shifts() {
+        for _ in 0..16 {
+            let (a, b) = (random::<Xmm>(), random::<Xmm>());
+            let (c, d) = (random::<Ymm>(), random::<Ymm>());
+            for isa in Isa::supported() {
+                with_isa(isa, || {
+                    let at = format!("at {}", isa);
+                    check_bytes!(Xmm, a, b, at; 0, 1, 3, 4, 8, 15, 16, 17, 24, 31, 32, 33, 200, 255);
+                    check_bytes!(Ymm, c, d, at; 0, 1, 3, 4, 8, 15, 16, 17, 24, 31, 32, 33, 200, 255);
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn test_ymm_byte_shifts_stay_in_halves() {
+        let a = Ymm::from_fn::<u8>(|i| i as u8 + 1);
+        let b = Ymm::from_fn::<u8>(|i| i as u8 + 101);
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                let left = a.byte_shift_left::<2>().lanes::<u8>().to_vec();
+                assert_eq!(left[..4], [0, 0, 1, 2], "at {}", isa);
+                assert_eq!(left[16..20], [0, 0, 17, 18], "at {}", isa);
+                let right = a.byte_shift_right::<2>().lanes::<u8>().to_vec();
+                assert_eq!(right[12..20], [15, 16, 0, 0, 19, 20, 21, 22], "at {}", isa);
+                let aligned = a.alignr::<15>(b).lanes::<u8>().to_vec();
+                assert_eq!(aligned[..3], [116, 1, 2], "at {}", isa);
+                assert_eq!(aligned[16..19], [132, 17, 18], "at {}", isa);
+                assert_eq!(a.alignr::<16>(b), a, "at {}", isa);
+                assert_eq!(a.byte_shift_right::<16>(), Ymm::default(), "at {}", isa);
+            });
+        }
+    }
+
+    #[test]
+    fn test_sign_fill() {
+        let a = Xmm::from_i64([i64::MIN, -1]);
+        for isa in Isa::supported() {
+            with_isa(isa, || {
+                assert_eq!(a.shift_right::<i64, 63>(), Xmm::splat_i64(-1), "at {}", isa);
+                assert_eq!(a.shift_right::<i64, 200>(), Xmm::splat_i64(-1), "at {}", isa);
+                assert_eq!(a.shift_right::<u64, 63>(), Xmm::from_u64([1, 1]), "at {}", isa);
+                assert_eq!(a.shift_right_each::<i64>(Xmm::from_u64([1, u64::MAX])), Xmm::from_i64([i64::MIN >> 1, -1]), "at {}", isa);
+                let b = Ymm::splat_i8(-128);
+                assert_eq!(b.shift_right::<i8, 3>(), Ymm::splat_i8(-16), "at {}", isa);
+                assert_eq!(b.shift_right::<u8, 3>(), Ymm::splat_u8(16), "at {}", isa);
+            });
+        }
+    }
+}
@@ -0,0 +1,669 @@
+//! Lane permutations with index patterns fixed at compile time.
+//!
+//! [`swizzle!`](crate::swizzle) rearranges the lanes of one register and
+//! [`shuffle!`](crate::shuffle!) picks lanes from two, the indices from the
+//! lane count up selecting from the second one. The lane width is the
+//! register width divided by the number of indices, so four indices move the
+//! 32-bit lanes of an [`Xmm`] and the 64-bit lanes of a [`Ymm`].
+//!
+//! ```
+//! use simd::xmm::Xmm;
+//! use simd::ymm::Ymm;
+//! use simd::{shuffle, swizzle};
+//!
+//! let a = Xmm::from_i32([10, 11, 12, 13]);
+//! let b = Xmm::from_i32([20, 21, 22, 23]);
+//! assert_eq!(swizzle!(a, [3, 2, 1, 0]), Xmm::from_i32([13, 12, 11, 10]));
+//! assert_eq!(shuffle!(a, b, [0, 4, 1, 5]), Xmm::from_i32([10, 20, 11, 21]));
+//! assert_eq!(shuffle!(a, b, [0, 5, 2, 7]), Xmm::from_i32([10, 21, 12, 23]));
+//!
+//! let c = Ymm::from_fn::<u8>(|i| i as u8);
+//! let reversed = swizzle!(c, [31, 30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 18, 17, 16,
+//!                             15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+//! assert_eq!(reversed, Ymm::from_fn::<u8>(|i| 31 - i as u8));
+//! ```
+//!
+//! A pattern whose length is not a lane count of the register, or with an
+//! index past the lanes it can select from, does not compile:
+//!
+//! ```compile_fail
+//! use simd::{swizzle, xmm::Xmm};
+//!
+//! swizzle!(Xmm::default(), [0, 1, 2]);
+//! ```
+//!
+//! ```compile_fail
+//! use simd::{swizzle, xmm::Xmm};
+//!
+//! swizzle!(Xmm::default(), [0, 1, 2, 4]);
+//! ```
+//!
+//! The instructions are chosen from the pattern when the code is compiled,
+//! and from the level at run time:
+//!
+//! | Pattern                             | SSE2                | SSE4.1           | AVX2                      |
+//! |-------------------------------------|---------------------|------------------|---------------------------|
+//! | 32- and 64-bit lanes                | `pshufd`            | `pshufd`         | `vpshufd`, `vpermq` or `vpermd` |
+//! | 16-bit lanes within each 64 bits    | `pshuflw`/`pshufhw` | same             | same if both halves match |
+//! | 8- and 16-bit lanes within 128 bits | portable            | `pshufb`         | `vpshufb`                 |
+//! | across the 128-bit halves           |                     |                  | `vpermd`, or `vpshufb` on both halves |
+//! | lane `i` from `a` or `b` only       | and, andnot, or     | `pblendw` or `pblendvb` | `vpblendd`, `vpblendw` or `vpblendvb` |
+//!
+//! Other [`shuffle!`](crate::shuffle!) patterns permute both registers the
+//! same way and blend the results. [`Ymm`] runs the portable permutation
+//! below AVX2, since a lane may come from any of four halves.
+
+use crate::chunks::Register;
+use crate::dispatch::select;
+use crate::xmm::Xmm;
+use crate::ymm::Ymm;
+
+/// Rearranges the lanes of a register: lane `i` of the result is lane
+/// `indices[i]` of `a`. See the [module documentation](mod@crate::shuffle).
+///
+/// ```
+/// use simd::{swizzle, xmm::Xmm};
+///
+/// let a = Xmm::from_u16([0, 1, 2, 3, 4, 5, 6, 7]);
+/// assert_eq!(swizzle!(a, [1, 0, 3, 2, 5, 4, 7, 6]), Xmm::from_u16([1, 0, 3, 2, 5, 4, 7, 6]));
+/// assert_eq!(swizzle!(a, [0, 0, 0, 0, 0, 0, 0, 0]), Xmm::splat_u16(0));
+/// ```
+#[macro_export]
+macro_rules! swizzle {
+    ($a:expr, [$($i:expr),+ $(,)?]) => {
+        match $a {
+            a => $crate::__shuffle!(a, a, 1, [$($i),+]),
+        }
+    };
+}
+
+/// Picks lanes from two registers: lane `i` of the result is lane
+/// `indices[i]` of `a`, or lane `indices[i] - n` of `b` from the lane count
+/// `n` up. See the [module documentation](mod@crate::shuffle).
+///
+/// ```
+/// use simd::{shuffle, xmm::Xmm};
+///
+/// let a = Xmm::from_f64([1.0, 2.0]);
+/// let b = Xmm::from_f64([3.0, 4.0]);
+/// assert_eq!(shuffle!(a, b, [1, 3]), Xmm::from_f64([2.0, 4.0]));
+/// ```
+#[macro_export]
+macro_rules! shuffle {
+    ($a:expr, $b:expr, [$($i:expr),+ $(,)?]) => {
+        match ($a, $b) {
+            (a, b) => $crate::__shuffle!(a, b, 2, [$($i),+]),
+        }
+    };
+}
+
+/// Expands [`swizzle!`] and [`shuffle!`] over `$sources` registers.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __shuffle {
+    ($a:ident, $b:ident, $sources:literal, [$($i:expr),+]) => {{
+        const __N: usize = [$($i),+].len();
+        struct __Indices;
+        impl $crate::shuffle::Pattern<__N> for __Indices {
+            const INDICES: [u8; __N] = [$($i),+];
+        }
+        const _: () = $crate::shuffle::check(&<__Indices as $crate::shuffle::Pattern<__N>>::INDICES, $sources);
+        <_ as $crate::shuffle::Shuffle<__N>>::shuffle::<
+            __Indices,
+            { <__Indices as $crate::shuffle::Pattern<__N>>::LOW },
+            { <__Indices as $crate::shuffle::Pattern<__N>>::HIGH },
+            { <__Indices as $crate::shuffle::Pattern<__N>>::BLEND },
+        >($a, $b)
+    }};
+}
+
+/// A lane pattern of [`swizzle!`](crate::swizzle) or
+/// [`shuffle!`](crate::shuffle!), with the immediates it lowers to.
+#[doc(hidden)]
+pub trait Pattern<const N: usize> {
+    const INDICES: [u8; N];
+    /// `pshufd` control for the first four lanes, or for two 64-bit lanes.
+    const LOW: i32 = imm(&Self::INDICES, 0);
+    /// `pshufhw` control for lanes 4 to 8.
+    const HIGH: i32 = imm(&Self::INDICES, 4);
+    /// `pblendw`/`vpblendd` control of the lanes taken from `b`.
+    const BLEND: i32 = blend_imm(&Self::INDICES);
+}
+
+/// Registers whose lanes [`swizzle!`](crate::swizzle) and
+/// [`shuffle!`](crate::shuffle!) rearrange, by number of lanes.
+#[diagnostic::on_unimplemented(message = "`{Self}` cannot be split into {N} lanes of 8, 16, 32 or 64 bits")]
+pub trait Shuffle<const N: usize>: Register {
+    #[doc(hidden)]
+    fn shuffle<P: Pattern<N>, const LOW: i32, const HIGH: i32, const BLEND: i32>(self, b: Self) -> Self;
+}
+
+macro_rules! impl_shuffle {
+    (Xmm: $($n:literal),*) => {$(
+        impl Shuffle<$n> for Xmm {
+            #[inline(always)]
+            fn shuffle<P: Pattern<$n>, const LOW: i32, const HIGH: i32, const BLEND: i32>(self, b: Xmm) -> Xmm {
+                select!(
+                    Sse41 => unsafe { sse41::shuffle::<$n, P, LOW, HIGH, BLEND>(self, b) },
+                    Sse2 => sse2::shuffle::<$n, P, LOW, HIGH>(self, b),
+                    _ => scalar::shuffle(self, b, &P::INDICES),
+                )
+            }
+        }
+    )*};
+    (Ymm: $($n:literal),*) => {$(
+        impl Shuffle<$n> for Ymm {
+            #[inline(always)]
+            fn shuffle<P: Pattern<$n>, const LOW: i32, const HIGH: i32, const BLEND: i32>(self, b: Ymm) -> Ymm {
+                select!(
+                    Avx2 => unsafe { avx2::shuffle::<$n, P, LOW, HIGH, BLEND>(self, b) },
+                    _ => scalar::shuffle(self, b, &P::INDICES),
+                )
+            }
+        }
+    )*};
+}
+
+impl_shuffle!(Xmm: 2, 4, 8, 16);
+impl_shuffle!(Ymm: 4, 8, 16, 32);
+
+/// Fails to compile a pattern with an index past the lanes of `sources`
+/// registers.
+#[doc(hidden)]
+pub const fn check(indices: &[u8], sources: usize) {
+    let mut i = 0;
+    while i < indices.len() {
+        if indices[i] as usize >= sources * indices.len() {
+            if sources == 1 {
+                panic!("swizzle index out of range: it must be less than the number of lanes");
+            }
+            panic!("shuffle index out of range: it must be less than twice the number of lanes");
+        }
+        i += 1;
+    }
+}
+
+/// Two bits per lane for four lanes from `start`, or for the two halves of
+/// each of two 64-bit lanes.
+const fn imm(indices: &[u8], start: usize) -> i32 {
+    let n = indices.len();
+    let mut imm = 0;
+    let mut i = 0;
+    while i < 4 {
+        let lane = if n == 2 {
+            2 * (indices[i / 2] as i32 & 1) + i as i32 % 2
+        } else if start + i < n {
+            indices[start + i] as i32 & 3
+        } else {
+            0
+        };
+        imm |= lane << (2 * i);
+        i += 1;
+    }
+    imm
+}
+
+/// One bit per 16-bit lane of an [`Xmm`] or 32-bit lane of a [`Ymm`] for
+/// patterns of up to eight lanes, else one bit per lane for the first eight.
+const fn blend_imm(indices: &[u8]) -> i32 {
+    let n = indices.len();
+    let width = if n < 8 { 8 / n } else { 1 };
+    let mut imm = 0;
+    let mut i = 0;
+    while i < n && i < 8 {
+        if indices[i] as usize >= n {
+            imm |= ((1 << width) - 1) << (i * width);
+        }
+        i += 1;
+    }
+    imm
+}
+
+/// Whether every lane comes from `a`.
+#[cfg(simd_x86)]
+const fn is_swizzle(indices: &[u8]) -> bool {
+    let mut i = 0;
+    while i < indices.len() {
+        if indices[i] as usize >= indices.len() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether lane `i` is lane `i` of `a` or of `b`.
+#[cfg(simd_x86)]
+const fn is_blend(indices: &[u8]) -> bool {
+    let mut i = 0;
+    while i < indices.len() {
+        if indices[i] as usize % indices.len() != i {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether every lane stays within its group of `group` lanes.
+#[cfg(simd_x86)]
+const fn within(indices: &[u8], group: usize) -> bool {
+    let mut i = 0;
+    while i < indices.len() {
+        if indices[i] as usize % indices.len() / group != i / group {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether each lane moves like the one `period` lanes before it.
+#[cfg(simd_x86)]
+const fn repeats(indices: &[u8], period: usize) -> bool {
+    let n = indices.len();
+    let mut i = period;
+    while i < n {
+        if indices[i] as usize % n != indices[i - period] as usize % n + period {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether the lanes move in aligned groups of `group`.
+#[cfg(simd_x86)]
+const fn grouped(indices: &[u8], group: usize) -> bool {
+    let n = indices.len();
+    let mut i = 0;
+    while i < n {
+        let first = indices[i - i % group] as usize % n;
+        if !first.is_multiple_of(group) || indices[i] as usize % n != first + i % group {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The byte of its source register that each of `B` bytes comes from.
+#[cfg(simd_x86)]
+const fn source_bytes<const B: usize>(indices: &[u8]) -> [u8; B] {
+    let n = indices.len();
+    let width = B / n;
+    let mut bytes = [0; B];
+    let mut k = 0;
+    while k < B {
+        bytes[k] = ((indices[k / width] as usize % n) * width + k % width) as u8;
+        k += 1;
+    }
+    bytes
+}
+
+/// All ones in the bytes taken from `b`.
+#[cfg(simd_x86)]
+const fn from_b<const B: usize>(indices: &[u8]) -> [u8; B] {
+    let n = indices.len();
+    let mut bytes = [0; B];
+    let mut k = 0;
+    while k < B {
+        if indices[k / (B / n)] as usize >= n {
+            bytes[k] = 0xFF;
+        }
+        k += 1;
+    }
+    bytes
+}
+
+/// SSE2 kernels over [`Xmm`].
+#[cfg(simd_x86)]
+mod sse2 {
+    use super::{from_b, is_blend, is_swizzle, within, Pattern};
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    /// `b` where `m` is all ones, else `a`.
+    #[inline(always)]
+    pub fn merge(m: __m128i, a: __m128i, b: __m128i) -> __m128i {
+        unsafe { _mm_or_si128(_mm_and_si128(m, b), _mm_andnot_si128(m, a)) }
+    }
+
+    /// Permutes 32- and 64-bit lanes with `pshufd`, and 16-bit lanes that
+    /// stay within their 64 bits with `pshuflw` and `pshufhw`.
+    #[inline(always)]
+    pub fn permute<const N: usize, P: Pattern<N>, const LOW: i32, const HIGH: i32>(v: __m128i) -> Option<__m128i> {
+        unsafe {
+            match N {
+                2 | 4 => Some(_mm_shuffle_epi32::<LOW>(v)),
+                8 if const { within(&P::INDICES, 4) } => Some(_mm_shufflehi_epi16::<HIGH>(_mm_shufflelo_epi16::<LOW>(v))),
+                _ => None,
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn shuffle<const N: usize, P: Pattern<N>, const LOW: i32, const HIGH: i32>(a: Xmm, b: Xmm) -> Xmm {
+        let (a, b) = (__m128i::from(a), __m128i::from(b));
+        let m = Xmm::from_u8(const { from_b::<16>(&P::INDICES) }).into();
+        if const { is_blend(&P::INDICES) } {
+            return merge(m, a, b).into();
+        }
+        let Some(x) = permute::<N, P, LOW, HIGH>(a) else {
+            return super::scalar::shuffle(a.into(), b.into(), &P::INDICES);
+        };
+        if const { is_swizzle(&P::INDICES) } {
+            return x.into();
+        }
+        let y = permute::<N, P, LOW, HIGH>(b).unwrap();
+        merge(m, x, y).into()
+    }
+}
+
+/// SSE4.1 kernels over [`Xmm`]. Callers must check
+/// [`has_sse41`](crate::dispatch::has_sse41) first.
+#[cfg(simd_x86)]
+mod sse41 {
+    use super::{from_b, is_blend, is_swizzle, source_bytes, sse2, Pattern};
+    use crate::xmm::Xmm;
+    use std::arch::x86_64::*;
+
+    /// `pshufb` control picking the bytes of one source and zeroing those
+    /// of the other.
+    const fn control<const N: usize>(indices: &[u8; N], second: bool) -> [u8; 16] {
+        let mut bytes = source_bytes::<16>(indices);
+        let from_b = from_b::<16>(indices);
+        let mut k = 0;
+        while k < 16 {
+            if (from_b[k] != 0) != second {
+                bytes[k] = 0x80;
+            }
+            k += 1;
+        }
+        bytes
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    pub unsafe fn shuffle<const N: usize, P: Pattern<N>, const LOW: i32, const HIGH: i32, const BLEND: i32>(a: Xmm, b: Xmm) -> Xmm {
+        let (a, b) = (__m128i::from(a), __m128i::from(b));
+        let swizzle = const { is_swizzle(&P::INDICES) };
+        if const { is_blend(&P::INDICES) } {
+            return match N {
+                16 => _mm_blendv_epi8(a, b, Xmm::from_u8(const { from_b::<16>(&P::INDICES) }).into()),
+                _ => _mm_blend_epi16::<BLEND>(a, b),
+            }
+            .into();
+        }
+        if let Some(x) = sse2::permute::<N, P, LOW, HIGH>(a) {
+            return match swizzle {
+                true => x,
+                false => _mm_blend_epi16::<BLEND>(x, sse2::permute::<N, P, LOW, HIGH>(b).unwrap()),
+            }
+            .into();
+        }
+        let x = _mm_shuffle_epi8(a, Xmm::from_u8(const { control(&P::INDICES, false) }).into());
+        match swizzle {
+            true => x,
+            false => _mm_or_si128(x, _mm_shuffle_epi8(b, Xmm::from_u8(const { control(&P::INDICES, true) }).into())),
+        }
+        .into()
+    }
+}
+
+/// AVX2 kernels over [`Ymm`]. Callers must check
+/// [`has_avx2`](crate::ymm::has_avx2) first.
+#[cfg(simd_x86)]
+mod avx2 {
+    use super::{from_b, grouped, is_blend, is_swizzle, repeats, source_bytes, within, Pattern};
+    use crate::ymm::Ymm;
+    use std::arch::x86_64::*;
+
+    /// The dword of its source register that each dword comes from.
+    const fn dwords(indices: &[u8]) -> [u32; 8] {
+        let bytes = source_bytes::<32>(indices);
+        let mut dwords = [0; 8];
+        let mut k = 0;
+        while k < 8 {
+            dwords[k] = bytes[4 * k] as u32 / 4;
+            k += 1;
+        }
+        dwords
+    }
+
+    /// All ones in the bytes taken from the other 128-bit half.
+    const fn crossing(indices: &[u8]) -> [u8; 32] {
+        let mut bytes = source_bytes::<32>(indices);
+        let mut k = 0;
+        while k < 32 {
+            bytes[k] = if bytes[k] as usize / 16 != k / 16 { 0xFF } else { 0 };
+            k += 1;
+        }
+        bytes
+    }
+
+    /// Whether both 128-bit halves take the same lanes from `b`.
+    const fn same_sources(indices: &[u8]) -> bool {
+        let from_b = from_b::<32>(indices);
+        let mut k = 0;
+        while k < 16 {
+            if from_b[k] != from_b[k + 16] {
+                return false;
+            }
+            k += 1;
+        }
+        true
+    }
+
+    /// Moves every lane to its place in the pattern, ignoring which source
+    /// it is taken from. `vpshufb` only reads the low four bits of each
+    /// control byte, so the source bytes work as its control in both halves.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn permute<const N: usize, P: Pattern<N>, const LOW: i32, const HIGH: i32>(v: __m256i) -> __m256i {
+        let in_halves = const { within(&P::INDICES, N / 2) };
+        let same_halves = const { within(&P::INDICES, N / 2) && repeats(&P::INDICES, N / 2) };
+        let control = Ymm::from_u8(const { source_bytes::<32>(&P::INDICES) }).into();
+        match N {
+            4 => _mm256_permute4x64_epi64::<LOW>(v),
+            8 if same_halves => _mm256_shuffle_epi32::<LOW>(v),
+            16 if same_halves && const { within(&P::INDICES, 4) } => {
+                _mm256_shufflehi_epi16::<HIGH>(_mm256_shufflelo_epi16::<LOW>(v))
+            }
+            _ if in_halves => _mm256_shuffle_epi8(v, control),
+            _ if const { grouped(&P::INDICES, if N < 8 { 1 } else { N / 8 }) } => {
+                _mm256_permutevar8x32_epi32(v, Ymm::from_u32(const { dwords(&P::INDICES) }).into())
+            }
+            _ => {
+                let same = _mm256_shuffle_epi8(v, control);
+                let other = _mm256_shuffle_epi8(_mm256_permute2x128_si256::<0x01>(v, v), control);
+                _mm256_blendv_epi8(same, other, Ymm::from_u8(const { crossing(&P::INDICES) }).into())
+            }
+        }
+    }
+
+    /// Blends `b` into `a` where the pattern takes from `b`.
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn blend<const N: usize, P: Pattern<N>, const BLEND: i32>(a: __m256i, b: __m256i) -> __m256i {
+        match N {
+            4 | 8 => _mm256_blend_epi32::<BLEND>(a, b),
+            16 if const { same_sources(&P::INDICES) } => _mm256_blend_epi16::<BLEND>(a, b),
+            _ => _mm256_blendv_epi8(a, b, Ymm::from_u8(const { from_b::<32>(&P::INDICES) }).into()),
+        }
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn shuffle<const N: usize, P: Pattern<N>, const LOW: i32, const HIGH: i32, const BLEND: i32>(a: Ymm, b: Ymm) -> Ymm {
+        let (a, b) = (__m256i::from(a), __m256i::from(b));
+        if const { is_blend(&P::INDICES) } {
+            return blend::<N, P, BLEND>(a, b).into();
+        }
+        let x = permute::<N, P, LOW, HIGH>(a);
+        if const { is_swizzle(&P::INDICES) } {
+            return x.into();
+        }
+        blend::<N, P, BLEND>(x, permute::<N, P, LOW, HIGH>(b)).into()
+    }
+}
+
+mod scalar {
+    use crate::chunks::Register;
+
+    /// Copies the bytes of every lane from its source, the reference of
+    /// every other kernel.
+    pub fn shuffle<R: Register>(a: R, b: R, indices: &[u8]) -> R {
+        let n = indices.len();
+        let width = R::BYTES / n;
+        let mut out = R::default();
+        for (k, byte) in out.lanes_mut::<u8>().iter_mut().enumerate() {
+            let lane = indices[k / width] as usize;
+            let source = if lane < n { &a } else { &b };
+            *byte = source.lanes::<u8>()[lane % n * width + k % width];
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dispatch::{with_isa, Isa};
+    use rand::Rng;
+
+    fn random<R: Register>() -> R {
+        let mut rng = rand::thread_rng();
+        let mut reg = R::default();
+        reg.lanes_mut::<u8>().iter_mut().for_each(|x| *x = rng.gen());
+        reg
+    }
+
+    macro_rules! check {
+        ($reg:ident, swizzle [$($i:expr),+ $(,)?]) => {{
+            let a: $reg = random();
+            let expect = scalar::shuffle(a, a, &[$($i),+]);
+            for isa in Isa::supported() {
+                with_isa(isa, || assert_eq!(swizzle!(a, [$($i),+]), expect, "{} {:?} at {}", stringify!($reg), [$($i),+], isa));
+            }
+        }};
+        ($reg:ident, shuffle [$($i:expr),+ $(,)?]) => {{
+            let (a, b): ($reg, $reg) = (random(), random());
+            let expect = scalar::shuffle(a, b, &[$($i),+]);
+            for isa in Isa::supported() {
+                with_isa(isa, || assert_eq!(shuffle!(a, b, [$($i),+]), expect, "{} {:?} at {}", stringify!($reg), [$($i),+], isa));
+            }
+        }};
+    }
+
+    #[test]
+    fn test_scalar() {
+        let a = Xmm::from_u16([0, 1, 2, 3, 4, 5, 6, 7]);
+        let b = Xmm::from_u16([8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(scalar::shuffle(a, b, &[7, 0, 4, 3]), Xmm::from_u16([14, 15, 0, 1, 8, 9, 6, 7]));
+        assert_eq!(scalar::shuffle(a, b, &[0, 3]), Xmm::from_u16([0, 1, 2, 3, 12, 13, 14, 15]));
+    }
+
+    #[test]
+    fn test_immediates() {
+        assert_eq!(imm(&[3, 2, 1, 0], 0), 0b00_01_10_11);
+        assert_eq!(imm(&[1, 0], 0), 0b01_00_11_10);
+        assert_eq!(imm(&[0, 1, 2, 3, 7, 6, 5, 4], 4), 0b00_01_10_11);
+        assert_eq!(blend_imm(&[0, 3]), 0xF0);
+        assert_eq!(blend_imm(&[4, 1, 2, 7]), 0b1100_0011);
+        assert_eq!(blend_imm(&[0, 9, 2, 3, 4, 5, 6, 15]), 0b1000_0010);
+    }
+
+    #[cfg(simd_x86)]
+    #[test]
+    fn test_patterns() {
+        assert!(is_swizzle(&[3, 0, 1, 2]) && !is_swizzle(&[3, 0, 1, 4]));
+        assert!(is_blend(&[4, 1, 6, 3]) && !is_blend(&[1, 1, 2, 3]));
+        assert!(grouped(&[2, 3, 0, 1], 2) && !grouped(&[1, 2, 3, 0], 2));
+        assert!(within(&[1, 0, 3, 2], 2) && !within(&[2, 0, 3, 1], 2));
+        assert!(repeats(&[1, 0, 3, 2], 2) && !repeats(&[1, 0, 2, 3], 2));
+    }
+
+    #[test]
+    fn test_xmm_swizzle() {
+        check!(Xmm, swizzle [1, 0]);
+        check!(Xmm, swizzle [1, 1]);
+        check!(Xmm, swizzle [3, 2, 1, 0]);
+        check!(Xmm, swizzle [2, 2, 2, 2]);
+        check!(Xmm, swizzle [1, 0, 3, 2, 7, 5, 6, 4]);
+        check!(Xmm, swizzle [7, 6, 5, 4, 3, 2, 1, 0]);
+        check!(Xmm, swizzle [0, 1, 2, 3, 4, 5, 6, 7]);
+        check!(Xmm, swizzle [15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+        check!(Xmm, swizzle [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        check!(Xmm, swizzle [3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12]);
+    }
+
+    #[test]
+    fn test_xmm_shuffle() {
+        check!(Xmm, shuffle [0, 3]);
+        check!(Xmm, shuffle [2, 1]);
+        check!(Xmm, shuffle [3, 0]);
+        check!(Xmm, shuffle [0, 5, 2, 7]);
+        check!(Xmm, shuffle [1, 4, 3, 6]);
+        check!(Xmm, shuffle [7, 6, 5, 4]);
+        check!(Xmm, shuffle [0, 9, 2, 11, 4, 13, 6, 15]);
+        check!(Xmm, shuffle [8, 9, 10, 3, 12, 13, 14, 7]);
+        check!(Xmm, shuffle [1, 8, 3, 10, 5, 12, 7, 14]);
+        check!(Xmm, shuffle [0, 8, 1, 9, 2, 10, 3, 11]);
+        check!(Xmm, shuffle [0, 17, 2, 19, 4, 21, 6, 23, 8, 25, 10, 27, 12, 29, 14, 31]);
+        check!(Xmm, shuffle [0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23]);
+        check!(Xmm, shuffle [31, 30, 29, 28, 27, 26, 25, 24, 7, 6, 5, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_ymm_swizzle() {
+        check!(Ymm, swizzle [3, 2, 1, 0]);
+        check!(Ymm, swizzle [1, 0, 3, 2, 5, 4, 7, 6]);
+        check!(Ymm, swizzle [1, 0, 3, 2, 4, 5, 6, 7]);
+        check!(Ymm, swizzle [7, 6, 5, 4, 3, 2, 1, 0]);
+        check!(Ymm, swizzle [1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14]);
+        check!(Ymm, swizzle [7, 6, 5, 4, 3, 2, 1, 0, 15, 14, 13, 12, 11, 10, 9, 8]);
+        check!(Ymm, swizzle [14, 15, 12, 13, 10, 11, 8, 9, 6, 7, 4, 5, 2, 3, 0, 1]);
+        check!(Ymm, swizzle [15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+        check!(Ymm, swizzle [
+            15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+            31, 30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 18, 17, 16,
+        ]);
+        check!(Ymm, swizzle [
+            28, 29, 30, 31, 24, 25, 26, 27, 20, 21, 22, 23, 16, 17, 18, 19,
+            12, 13, 14, 15, 8, 9, 10, 11, 4, 5, 6, 7, 0, 1, 2, 3,
+        ]);
+        check!(Ymm, swizzle [
+            31, 30, 29, 28, 27, 26, 25, 24, 23, 22, 21, 20, 19, 18, 17, 16,
+            15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+        ]);
+        check!(Ymm, swizzle [
+            0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23,
+            8, 24, 9, 25, 10, 26, 11, 27, 12, 28, 13, 29, 14, 30, 15, 31,
+        ]);
+    }
+
+    #[test]
+    fn test_ymm_shuffle() {
+        check!(Ymm, shuffle [0, 5, 2, 7]);
+        check!(Ymm, shuffle [0, 4, 1, 5]);
+        check!(Ymm, shuffle [0, 9, 2, 11, 12, 5, 6, 15]);
+        check!(Ymm, shuffle [0, 8, 1, 9, 4, 12, 5, 13]);
+        check!(Ymm, shuffle [15, 14, 13, 12, 3, 2, 1, 0]);
+        check!(Ymm, shuffle [0, 17, 2, 19, 4, 21, 6, 23, 8, 25, 10, 27, 12, 29, 14, 31]);
+        check!(Ymm, shuffle [16, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 31]);
+        check!(Ymm, shuffle [0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23]);
+        check!(Ymm, shuffle [
+            32, 1, 34, 3, 36, 5, 38, 7, 40, 9, 42, 11, 44, 13, 46, 15,
+            48, 17, 50, 19, 52, 21, 54, 23, 56, 25, 58, 27, 60, 29, 62, 31,
+        ]);
+        check!(Ymm, shuffle [
+            0, 32, 1, 33, 2, 34, 3, 35, 4, 36, 5, 37, 6, 38, 7, 39,
+            8, 40, 9, 41, 10, 42, 11, 43, 12, 44, 13, 45, 14, 46, 15, 47,
+        ]);
+        check!(Ymm, shuffle [
+            63, 62, 61, 60, 59, 58, 57, 56, 55, 54, 53, 52, 51, 50, 49, 48,
+            15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+        ]);
+    }
+}
@@ -1,6 +1,11 @@
-use crate::{fmt_as_simd, fmt_as_simd_hex};
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{__m128i, __m128, __m128d};
+use std::fmt;
+
+use crate::{fmt_as_simd, fmt_as_simd_hex, HexBytes};
 
 /// 128-bit wide SIMD data type.
+#[derive(Clone, Copy)]
 #[repr(C, align(16))]
 pub union Xmm {
     pub int8: [i8; 16],
@@ -15,76 +20,183 @@ pub union Xmm {
     pub float64: [f64; 2],
 }
 
+impl_lane_accessors!(Xmm {
+    int8: [i8; 16] => as_i8, as_i8_mut, lane_i8, set_lane_i8, from_i8, splat_i8;
+    int16: [i16; 8] => as_i16, as_i16_mut, lane_i16, set_lane_i16, from_i16, splat_i16;
+    int32: [i32; 4] => as_i32, as_i32_mut, lane_i32, set_lane_i32, from_i32, splat_i32;
+    int64: [i64; 2] => as_i64, as_i64_mut, lane_i64, set_lane_i64, from_i64, splat_i64;
+    uint8: [u8; 16] => as_u8, as_u8_mut, lane_u8, set_lane_u8, from_u8, splat_u8;
+    uint16: [u16; 8] => as_u16, as_u16_mut, lane_u16, set_lane_u16, from_u16, splat_u16;
+    uint32: [u32; 4] => as_u32, as_u32_mut, lane_u32, set_lane_u32, from_u32, splat_u32;
+    uint64: [u64; 2] => as_u64, as_u64_mut, lane_u64, set_lane_u64, from_u64, splat_u64;
+    float32: [f32; 4] => as_f32, as_f32_mut, lane_f32, set_lane_f32, from_f32, splat_f32;
+    float64: [f64; 2] => as_f64, as_f64_mut, lane_f64, set_lane_f64, from_f64, splat_f64;
+});
+
+#[cfg(target_arch = "x86_64")]
+impl_arch_conversions!(Xmm: __m128i, __m128, __m128d);
+
+impl Default for Xmm {
+    /// Returns a register with all bits cleared.
+    fn default() -> Self {
+        Xmm { uint64: [0; 2] }
+    }
+}
+
+impl PartialEq for Xmm {
+    /// Compares the registers bitwise, so `-0.0` and `0.0` lanes differ and
+    /// NaN lanes with the same bit pattern are equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.as_u64() == other.as_u64()
+    }
+}
+
+impl Eq for Xmm {}
+
+impl fmt::Debug for Xmm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Xmm")
+            .field("bytes", &HexBytes(self.as_u8()))
+            .field("int32", self.as_i32())
+            .finish()
+    }
+}
+
 impl Xmm {
     pub fn fmt_i16(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int16, std::mem::size_of::<Xmm>() / std::mem::size_of::<i16>(), 8).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i16(), std::mem::size_of::<Xmm>() / std::mem::size_of::<i16>(), 8).unwrap();
         s
     }
 
     pub fn fmt_u16(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint16, std::mem::size_of::<Xmm>() / std::mem::size_of::<u16>(), 8).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u16(), std::mem::size_of::<Xmm>() / std::mem::size_of::<u16>(), 8).unwrap();
         s
     }
 
     pub fn fmt_u16hex(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd_hex(&mut s, &self.uint16, std::mem::size_of::<Xmm>() / std::mem::size_of::<u16>(), 6).unwrap();
-        }
+        fmt_as_simd_hex(&mut s, self.as_u16(), std::mem::size_of::<Xmm>() / std::mem::size_of::<u16>(), 6).unwrap();
         s
     }
 
     pub fn fmt_i8(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int8, std::mem::size_of::<Xmm>() / std::mem::size_of::<i8>(), 4).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i8(), std::mem::size_of::<Xmm>() / std::mem::size_of::<i8>(), 4).unwrap();
         s
     }
 
     pub fn fmt_u8(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint8, std::mem::size_of::<Xmm>() / std::mem::size_of::<u8>(), 4).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u8(), std::mem::size_of::<Xmm>() / std::mem::size_of::<u8>(), 4).unwrap();
         s
     }
 
     pub fn fmt_i32(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int32, std::mem::size_of::<Xmm>() / std::mem::size_of::<i32>(), 12).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i32(), std::mem::size_of::<Xmm>() / std::mem::size_of::<i32>(), 12).unwrap();
         s
     }
 
     pub fn fmt_u32(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint32, std::mem::size_of::<Xmm>() / std::mem::size_of::<u32>(), 12).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u32(), std::mem::size_of::<Xmm>() / std::mem::size_of::<u32>(), 12).unwrap();
+        s
+    }
+
+    pub fn fmt_f32(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_f32(), std::mem::size_of::<Xmm>() / std::mem::size_of::<f32>(), 16).unwrap();
         s
     }
 
     pub fn fmt_i64(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int64, std::mem::size_of::<Xmm>() / std::mem::size_of::<i64>(), 20).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i64(), std::mem::size_of::<Xmm>() / std::mem::size_of::<i64>(), 20).unwrap();
         s
     }
 
     pub fn fmt_u64(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint64, std::mem::size_of::<Xmm>() / std::mem::size_of::<u64>(), 20).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u64(), std::mem::size_of::<Xmm>() / std::mem::size_of::<u64>(), 20).unwrap();
+        s
+    }
+
+    pub fn fmt_f64(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_f64(), std::mem::size_of::<Xmm>() / std::mem::size_of::<f64>(), 32).unwrap();
         s
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_xmm_default_is_zero() {
+        assert_eq!(Xmm::default().as_u64(), &[0; 2]);
+    }
+
+    #[test]
+    fn test_xmm_eq_is_bitwise() {
+        let a = Xmm { int16: [1, 2, 3, 4, 5, 6, 7, 8] };
+        let b = a;
+        assert_eq!(a, b);
+        assert_ne!(a, Xmm { int16: [1, 2, 3, 4, 5, 6, 7, 9] });
+        assert_ne!(Xmm { float32: [0.0; 4] }, Xmm { float32: [-0.0; 4] });
+        assert_eq!(Xmm { float32: [f32::NAN; 4] }, Xmm { float32: [f32::NAN; 4] });
+    }
+
+    #[test]
+    fn test_xmm_lane_accessors() {
+        let mut a = Xmm::from_i16([10, 200, 30, -32766, 50, 60, 32000, -32000]);
+        assert_eq!(a.lane_i16(3), -32766);
+        assert_eq!(a.lane_u16(3), 0x8002);
+        a.set_lane_i16(3, 7);
+        a.as_i16_mut()[0] = -1;
+        assert_eq!(a.as_i16(), &[-1, 200, 30, 7, 50, 60, 32000, -32000]);
+        assert_eq!(a.lane_u8(0), 0xFF);
+
+        let mut b = Xmm::splat_f32(1.5);
+        b.set_lane_f32(2, -0.5);
+        assert_eq!(b.as_f32(), &[1.5, 1.5, -0.5, 1.5]);
+        assert_eq!(b.lane_u32(0), 1.5f32.to_bits());
+        assert_eq!(Xmm::splat_u8(0xAB).as_u64(), &[0xABAB_ABAB_ABAB_ABAB; 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_xmm_lane_out_of_bounds() {
+        Xmm::default().lane_i32(4);
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_xmm_arch_conversions() {
+        use std::arch::x86_64::{_mm_add_epi16, _mm_cvtss_f32, _mm_set_epi32, _mm_set_pd, _mm_set_ps};
+
+        let a: Xmm = unsafe { _mm_set_epi32(4, 3, 2, 1) }.into();
+        assert_eq!(a.as_i32(), &[1, 2, 3, 4]);
+
+        let b = Xmm::from_i16([1, 2, 3, 4, 5, 6, 7, i16::MAX]);
+        let c: Xmm = unsafe { _mm_add_epi16(b.into(), b.into()) }.into();
+        assert_eq!(c.as_i16(), &[2, 4, 6, 8, 10, 12, 14, -2]);
+
+        let d: Xmm = unsafe { _mm_set_ps(4.0, 3.0, 2.0, 1.5) }.into();
+        assert_eq!(d.as_f32(), &[1.5, 2.0, 3.0, 4.0]);
+        assert_eq!(unsafe { _mm_cvtss_f32(d.into()) }, 1.5);
+
+        let e: Xmm = unsafe { _mm_set_pd(-2.0, 0.25) }.into();
+        assert_eq!(e.as_f64(), &[0.25, -2.0]);
+    }
+
+    #[test]
+    fn test_xmm_debug() {
+        let a = Xmm { int32: [1, -1, 0x100, 0] };
+        let s = format!("{:?}", a);
+        assert!(s.starts_with("Xmm { bytes: [0x01, 0x00, 0x00, 0x00, 0xff, 0xff"));
+        assert!(s.ends_with("int32: [1, -1, 256, 0] }"));
+    }
+}
@@ -1,6 +1,13 @@
-use crate::fmt_as_simd;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{__m256i, __m256, __m256d};
+use std::fmt;
+
+use crate::dispatch::{level, Isa};
+use crate::xmm::Xmm;
+use crate::{fmt_as_simd, fmt_as_simd_hex, HexBytes};
 
 /// 256-bit wide SIMD data type.
+#[derive(Clone, Copy)]
 #[repr(C, align(32))]
 pub union Ymm {
     pub int8: [i8; 32],
@@ -15,6 +22,73 @@ pub union Ymm {
     pub double: [f64; 4],
 }
 
+impl_lane_accessors!(Ymm {
+    int8: [i8; 32] => as_i8, as_i8_mut, lane_i8, set_lane_i8, from_i8, splat_i8;
+    int16: [i16; 16] => as_i16, as_i16_mut, lane_i16, set_lane_i16, from_i16, splat_i16;
+    int32: [i32; 8] => as_i32, as_i32_mut, lane_i32, set_lane_i32, from_i32, splat_i32;
+    int64: [i64; 4] => as_i64, as_i64_mut, lane_i64, set_lane_i64, from_i64, splat_i64;
+    uint8: [u8; 32] => as_u8, as_u8_mut, lane_u8, set_lane_u8, from_u8, splat_u8;
+    uint16: [u16; 16] => as_u16, as_u16_mut, lane_u16, set_lane_u16, from_u16, splat_u16;
+    uint32: [u32; 8] => as_u32, as_u32_mut, lane_u32, set_lane_u32, from_u32, splat_u32;
+    uint64: [u64; 4] => as_u64, as_u64_mut, lane_u64, set_lane_u64, from_u64, splat_u64;
+    float: [f32; 8] => as_f32, as_f32_mut, lane_f32, set_lane_f32, from_f32, splat_f32;
+    double: [f64; 4] => as_f64, as_f64_mut, lane_f64, set_lane_f64, from_f64, splat_f64;
+});
+
+#[cfg(target_arch = "x86_64")]
+impl_arch_conversions!(Ymm: __m256i, __m256, __m256d);
+
+/// Returns true if kernels may use AVX2, and with it AVX.
+///
+/// Integer operations on [`Ymm`] use AVX2 when available and otherwise run
+/// the SSE2 version on each 128-bit half. Float operations use AVX at the same
+/// level, as [`Isa`] has no AVX-only level.
+#[inline]
+pub fn has_avx2() -> bool {
+    level() >= Isa::Avx2
+}
+
+/// Applies the 128-bit kernel `f` to the low halves and to the high halves of
+/// `a` and `b`. Used by [`Ymm`] operations without AVX2.
+#[inline(always)]
+pub(crate) fn by_halves(a: Ymm, b: Ymm, f: fn(Xmm, Xmm) -> Xmm) -> Ymm {
+    let (a_lo, a_hi) = a.halves();
+    let (b_lo, b_hi) = b.halves();
+    Ymm::from_halves(f(a_lo, b_lo), f(a_hi, b_hi))
+}
+
+/// Applies the 128-bit kernel `f` to each half of `a`.
+#[inline(always)]
+pub(crate) fn map_halves(a: Ymm, f: fn(Xmm) -> Xmm) -> Ymm {
+    let (lo, hi) = a.halves();
+    Ymm::from_halves(f(lo), f(hi))
+}
+
+impl Default for Ymm {
+    /// Returns a register with all bits cleared.
+    fn default() -> Self {
+        Ymm { uint64: [0; 4] }
+    }
+}
+
+impl PartialEq for Ymm {
+    /// Bitwise comparison, same as for [`Xmm`].
+    fn eq(&self, other: &Self) -> bool {
+        self.as_u64() == other.as_u64()
+    }
+}
+
+impl Eq for Ymm {}
+
+impl fmt::Debug for Ymm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ymm")
+            .field("bytes", &HexBytes(self.as_u8()))
+            .field("int32", self.as_i32())
+            .finish()
+    }
+}
+
 impl Ymm {
     #[inline(always)]
     pub fn as_ptr(&self) -> *const Ymm {
@@ -26,83 +100,116 @@ impl Ymm {
         self as *mut Ymm
     }
 
+    /// Splits the register into its low and high 128-bit halves.
+    #[inline(always)]
+    pub fn halves(self) -> (Xmm, Xmm) {
+        let [lo, hi] = unsafe { std::mem::transmute::<Ymm, [Xmm; 2]>(self) };
+        (lo, hi)
+    }
+
+    /// Joins two 128-bit halves into a register, `lo` in the low 128 bits.
+    #[inline(always)]
+    pub fn from_halves(lo: Xmm, hi: Xmm) -> Ymm {
+        unsafe { std::mem::transmute::<[Xmm; 2], Ymm>([lo, hi]) }
+    }
+
     pub fn fmt_i16(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int16, std::mem::size_of::<Ymm>() / std::mem::size_of::<i16>(), 8).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i16(), std::mem::size_of::<Ymm>() / std::mem::size_of::<i16>(), 8).unwrap();
         s
     }
 
     pub fn fmt_u16(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint16, std::mem::size_of::<Ymm>() / std::mem::size_of::<u16>(), 8).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u16(), std::mem::size_of::<Ymm>() / std::mem::size_of::<u16>(), 8).unwrap();
+        s
+    }
+
+    pub fn fmt_u16hex(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd_hex(&mut s, self.as_u16(), std::mem::size_of::<Ymm>() / std::mem::size_of::<u16>(), 6).unwrap();
         s
     }
 
     pub fn fmt_i8(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int8, std::mem::size_of::<Ymm>() / std::mem::size_of::<i8>(), 4).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i8(), std::mem::size_of::<Ymm>() / std::mem::size_of::<i8>(), 4).unwrap();
         s
     }
 
     pub fn fmt_u8(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint8, std::mem::size_of::<Ymm>() / std::mem::size_of::<u8>(), 4).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u8(), std::mem::size_of::<Ymm>() / std::mem::size_of::<u8>(), 4).unwrap();
         s
     }
 
     pub fn fmt_i32(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int32, std::mem::size_of::<Ymm>() / std::mem::size_of::<i32>(), 12).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i32(), std::mem::size_of::<Ymm>() / std::mem::size_of::<i32>(), 12).unwrap();
         s
     }
 
     pub fn fmt_u32(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint32, std::mem::size_of::<Ymm>() / std::mem::size_of::<u32>(), 12).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u32(), std::mem::size_of::<Ymm>() / std::mem::size_of::<u32>(), 12).unwrap();
         s
     }
 
     pub fn fmt_f32(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.float, std::mem::size_of::<Ymm>() / std::mem::size_of::<f32>(), 16).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_f32(), std::mem::size_of::<Ymm>() / std::mem::size_of::<f32>(), 16).unwrap();
         s
     }
 
     pub fn fmt_i64(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.int64, std::mem::size_of::<Ymm>() / std::mem::size_of::<i64>(), 24).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_i64(), std::mem::size_of::<Ymm>() / std::mem::size_of::<i64>(), 24).unwrap();
         s
     }
 
     pub fn fmt_u64(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.uint64, std::mem::size_of::<Ymm>() / std::mem::size_of::<u64>(), 24).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_u64(), std::mem::size_of::<Ymm>() / std::mem::size_of::<u64>(), 24).unwrap();
         s
     }
 
     pub fn fmt_f64(&self) -> String {
         let mut s = String::new();
-        unsafe {
-            fmt_as_simd(&mut s, &self.double, std::mem::size_of::<Ymm>() / std::mem::size_of::<f64>(), 32).unwrap();
-        }
+        fmt_as_simd(&mut s, self.as_f64(), std::mem::size_of::<Ymm>() / std::mem::size_of::<f64>(), 32).unwrap();
         s
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_ymm_arch_conversions() {
+        use std::arch::x86_64::{_mm256_add_epi16, _mm256_cvtss_f32, _mm256_set_epi32, _mm256_set_pd, _mm256_set_ps};
+
+        let a = Ymm::from_u8(std::array::from_fn(|i| i as u8));
+        assert_eq!(Ymm::from(__m256i::from(a)), a);
+        assert_eq!(Ymm::from(__m256::from(a)), a);
+        assert_eq!(Ymm::from(__m256d::from(a)), a);
+
+        if !has_avx2() {
+            return;
+        }
+
+        let b: Ymm = unsafe { _mm256_set_epi32(8, 7, 6, 5, 4, 3, 2, 1) }.into();
+        assert_eq!(b.as_i32(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let c = Ymm::from_i16([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, i16::MAX]);
+        let d: Ymm = unsafe { _mm256_add_epi16(c.into(), c.into()) }.into();
+        assert_eq!(d.as_i16(), &[2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30, -2]);
+
+        let e: Ymm = unsafe { _mm256_set_ps(8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0, 1.5) }.into();
+        assert_eq!(e.as_f32(), &[1.5, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        assert_eq!(unsafe { _mm256_cvtss_f32(e.into()) }, 1.5);
+
+        let f: Ymm = unsafe { _mm256_set_pd(4.0, 3.0, -2.0, 0.25) }.into();
+        assert_eq!(f.as_f64(), &[0.25, -2.0, 3.0, 4.0]);
+    }
+}
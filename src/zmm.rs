@@ -0,0 +1,323 @@
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{__m512i, __m512, __m512d};
+use std::fmt;
+
+use crate::dispatch::{level, select, Isa};
+use crate::{fmt_as_simd, fmt_as_simd_hex, HexBytes};
+
+/// 512-bit wide SIMD data type.
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+pub union Zmm {
+    pub int8: [i8; 64],
+    pub int16: [i16; 32],
+    pub int32: [i32; 16],
+    pub int64: [i64; 8],
+    pub uint8: [u8; 64],
+    pub uint16: [u16; 32],
+    pub uint32: [u32; 16],
+    pub uint64: [u64; 8],
+    pub float: [f32; 16],
+    pub double: [f64; 8],
+}
+
+impl_lane_accessors!(Zmm {
+    int8: [i8; 64] => as_i8, as_i8_mut, lane_i8, set_lane_i8, from_i8, splat_i8;
+    int16: [i16; 32] => as_i16, as_i16_mut, lane_i16, set_lane_i16, from_i16, splat_i16;
+    int32: [i32; 16] => as_i32, as_i32_mut, lane_i32, set_lane_i32, from_i32, splat_i32;
+    int64: [i64; 8] => as_i64, as_i64_mut, lane_i64, set_lane_i64, from_i64, splat_i64;
+    uint8: [u8; 64] => as_u8, as_u8_mut, lane_u8, set_lane_u8, from_u8, splat_u8;
+    uint16: [u16; 32] => as_u16, as_u16_mut, lane_u16, set_lane_u16, from_u16, splat_u16;
+    uint32: [u32; 16] => as_u32, as_u32_mut, lane_u32, set_lane_u32, from_u32, splat_u32;
+    uint64: [u64; 8] => as_u64, as_u64_mut, lane_u64, set_lane_u64, from_u64, splat_u64;
+    float: [f32; 16] => as_f32, as_f32_mut, lane_f32, set_lane_f32, from_f32, splat_f32;
+    double: [f64; 8] => as_f64, as_f64_mut, lane_f64, set_lane_f64, from_f64, splat_f64;
+});
+
+#[cfg(target_arch = "x86_64")]
+impl_arch_conversions!(Zmm: __m512i, __m512, __m512d);
+
+impl Default for Zmm {
+    /// Returns a register with all bits cleared.
+    fn default() -> Self {
+        Zmm { uint64: [0; 8] }
+    }
+}
+
+impl PartialEq for Zmm {
+    /// Bitwise comparison, same as for [`Xmm`](crate::xmm::Xmm).
+    fn eq(&self, other: &Self) -> bool {
+        self.as_u64() == other.as_u64()
+    }
+}
+
+impl Eq for Zmm {}
+
+impl fmt::Debug for Zmm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Zmm")
+            .field("bytes", &HexBytes(self.as_u8()))
+            .field("int32", self.as_i32())
+            .finish()
+    }
+}
+
+/// Returns true if kernels may use AVX-512F.
+///
+/// Operations on [`Zmm`] use AVX-512 instructions when this returns true and
+/// fall back to a scalar emulation otherwise.
+#[inline]
+pub fn has_avx512f() -> bool {
+    level() >= Isa::Avx512
+}
+
+impl Zmm {
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *const Zmm {
+        self as *const Zmm
+    }
+
+    #[inline(always)]
+    pub fn as_mut_ptr(&mut self) -> *mut Zmm {
+        self as *mut Zmm
+    }
+
+    /// Adds packed 32-bit integers (wraparound).
+    pub fn add_i32(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::add_i32(self, other) }, _ => scalar::add_i32(self, other))
+    }
+
+    /// Adds packed 64-bit integers (wraparound).
+    pub fn add_i64(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::add_i64(self, other) }, _ => scalar::add_i64(self, other))
+    }
+
+    /// Subtracts packed 32-bit integers (wraparound).
+    pub fn sub_i32(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::sub_i32(self, other) }, _ => scalar::sub_i32(self, other))
+    }
+
+    /// Subtracts packed 64-bit integers (wraparound).
+    pub fn sub_i64(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::sub_i64(self, other) }, _ => scalar::sub_i64(self, other))
+    }
+
+    /// Adds packed single-precision floating-point values.
+    pub fn add_f32(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::add_f32(self, other) }, _ => scalar::add_f32(self, other))
+    }
+
+    /// Adds packed double-precision floating-point values.
+    pub fn add_f64(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::add_f64(self, other) }, _ => scalar::add_f64(self, other))
+    }
+
+    /// Bitwise AND of the full 512 bits.
+    pub fn and(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::and(self, other) }, _ => scalar::and(self, other))
+    }
+
+    /// Bitwise OR of the full 512 bits.
+    pub fn or(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::or(self, other) }, _ => scalar::or(self, other))
+    }
+
+    /// Bitwise XOR of the full 512 bits.
+    pub fn xor(&self, other: &Zmm) -> Zmm {
+        select!(Avx512 => unsafe { avx512::xor(self, other) }, _ => scalar::xor(self, other))
+    }
+
+    pub fn fmt_i16(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_i16(), std::mem::size_of::<Zmm>() / std::mem::size_of::<i16>(), 8).unwrap();
+        s
+    }
+
+    pub fn fmt_u16(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_u16(), std::mem::size_of::<Zmm>() / std::mem::size_of::<u16>(), 8).unwrap();
+        s
+    }
+
+    pub fn fmt_u16hex(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd_hex(&mut s, self.as_u16(), std::mem::size_of::<Zmm>() / std::mem::size_of::<u16>(), 6).unwrap();
+        s
+    }
+
+    pub fn fmt_i8(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_i8(), std::mem::size_of::<Zmm>() / std::mem::size_of::<i8>(), 4).unwrap();
+        s
+    }
+
+    pub fn fmt_u8(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_u8(), std::mem::size_of::<Zmm>() / std::mem::size_of::<u8>(), 4).unwrap();
+        s
+    }
+
+    pub fn fmt_i32(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_i32(), std::mem::size_of::<Zmm>() / std::mem::size_of::<i32>(), 12).unwrap();
+        s
+    }
+
+    pub fn fmt_u32(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_u32(), std::mem::size_of::<Zmm>() / std::mem::size_of::<u32>(), 12).unwrap();
+        s
+    }
+
+    pub fn fmt_f32(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_f32(), std::mem::size_of::<Zmm>() / std::mem::size_of::<f32>(), 16).unwrap();
+        s
+    }
+
+    pub fn fmt_i64(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_i64(), std::mem::size_of::<Zmm>() / std::mem::size_of::<i64>(), 24).unwrap();
+        s
+    }
+
+    pub fn fmt_u64(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_u64(), std::mem::size_of::<Zmm>() / std::mem::size_of::<u64>(), 24).unwrap();
+        s
+    }
+
+    pub fn fmt_f64(&self) -> String {
+        let mut s = String::new();
+        fmt_as_simd(&mut s, self.as_f64(), std::mem::size_of::<Zmm>() / std::mem::size_of::<f64>(), 32).unwrap();
+        s
+    }
+}
+
+/// AVX-512F implementations. Callers must check [`has_avx512f`] first.
+#[cfg(simd_x86)]
+mod avx512 {
+    use super::Zmm;
+    use std::arch::x86_64::{
+        __m512, __m512d, __m512i, _mm512_add_epi32, _mm512_add_epi64, _mm512_add_pd, _mm512_add_ps,
+        _mm512_and_si512, _mm512_or_si512, _mm512_sub_epi32, _mm512_sub_epi64, _mm512_xor_si512,
+    };
+
+    macro_rules! binary {
+        ($name:ident, $vec:ident, $intrinsic:ident) => {
+            #[target_feature(enable = "avx512f")]
+            pub unsafe fn $name(a: &Zmm, b: &Zmm) -> Zmm {
+                $intrinsic($vec::from(*a), $vec::from(*b)).into()
+            }
+        };
+    }
+
+    binary!(add_i32, __m512i, _mm512_add_epi32);
+    binary!(add_i64, __m512i, _mm512_add_epi64);
+    binary!(sub_i32, __m512i, _mm512_sub_epi32);
+    binary!(sub_i64, __m512i, _mm512_sub_epi64);
+    binary!(and, __m512i, _mm512_and_si512);
+    binary!(or, __m512i, _mm512_or_si512);
+    binary!(xor, __m512i, _mm512_xor_si512);
+    binary!(add_f32, __m512, _mm512_add_ps);
+    binary!(add_f64, __m512d, _mm512_add_pd);
+}
+
+/// Scalar emulation used when AVX-512 is not available. These are also the
+/// portable kernels.
+mod scalar {
+    use super::Zmm;
+
+    macro_rules! binary_lanes {
+        ($name:ident, $as:ident, $as_mut:ident, |$x:ident, $y:ident| $op:expr) => {
+            pub fn $name(a: &Zmm, b: &Zmm) -> Zmm {
+                let mut out = Zmm::default();
+                for ((o, &$x), &$y) in out.$as_mut().iter_mut().zip(a.$as()).zip(b.$as()) {
+                    *o = $op;
+                }
+                out
+            }
+        };
+    }
+
+    binary_lanes!(add_i32, as_i32, as_i32_mut, |x, y| x.wrapping_add(y));
+    binary_lanes!(add_i64, as_i64, as_i64_mut, |x, y| x.wrapping_add(y));
+    binary_lanes!(sub_i32, as_i32, as_i32_mut, |x, y| x.wrapping_sub(y));
+    binary_lanes!(sub_i64, as_i64, as_i64_mut, |x, y| x.wrapping_sub(y));
+    binary_lanes!(add_f32, as_f32, as_f32_mut, |x, y| x + y);
+    binary_lanes!(add_f64, as_f64, as_f64_mut, |x, y| x + y);
+    binary_lanes!(and, as_u64, as_u64_mut, |x, y| x & y);
+    binary_lanes!(or, as_u64, as_u64_mut, |x, y| x | y);
+    binary_lanes!(xor, as_u64, as_u64_mut, |x, y| x ^ y);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn operands_i32() -> (Zmm, Zmm) {
+        let a = Zmm { int32: [1, -2, 3, i32::MAX, 5, -6, 7, i32::MIN, 9, 10, -11, 12, 13, 14, 15, -16] };
+        let b = Zmm { int32: [10, 20, -30, 1, 50, 60, 70, -1, 90, -100, 110, 120, 130, -140, 150, 160] };
+        (a, b)
+    }
+
+    #[test]
+    fn test_zmm_layout() {
+        assert_eq!(std::mem::size_of::<Zmm>(), 64);
+        assert_eq!(std::mem::align_of::<Zmm>(), 64);
+    }
+
+    #[test]
+    fn test_zmm_scalar_add_sub_i32() {
+        let (a, b) = operands_i32();
+        let sum = scalar::add_i32(&a, &b);
+        let diff = scalar::sub_i32(&a, &b);
+        for i in 0..16 {
+            assert_eq!(sum.lane_i32(i), a.lane_i32(i).wrapping_add(b.lane_i32(i)));
+            assert_eq!(diff.lane_i32(i), a.lane_i32(i).wrapping_sub(b.lane_i32(i)));
+        }
+    }
+
+    #[test]
+    #[cfg(simd_x86)]
+    fn test_zmm_avx512_matches_scalar() {
+        if !has_avx512f() {
+            return;
+        }
+        let (a, b) = operands_i32();
+        let c = Zmm { double: [1.5, -2.25, 3.0, 1e300, -0.0, 6.0, 7.125, 8.0] };
+        let d = Zmm { double: [0.5, 2.25, -3.0, 1e300, 0.0, -6.5, 1.0, 1e-3] };
+        let e = Zmm { float: [1.5, -2.25, 3.0, 1e30, -0.0, 6.0, 7.125, 8.0, 0.1, 0.2, 0.3, 0.4, -0.5, 0.6, 0.7, 0.8] };
+        let f = Zmm { float: [0.5, 2.25, -3.0, 1e30, 0.0, -6.5, 1.0, 1e-3, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0] };
+        unsafe {
+            assert_eq!(avx512::add_i32(&a, &b), scalar::add_i32(&a, &b));
+            assert_eq!(avx512::add_i64(&a, &b), scalar::add_i64(&a, &b));
+            assert_eq!(avx512::sub_i32(&a, &b), scalar::sub_i32(&a, &b));
+            assert_eq!(avx512::sub_i64(&a, &b), scalar::sub_i64(&a, &b));
+            assert_eq!(avx512::and(&a, &b), scalar::and(&a, &b));
+            assert_eq!(avx512::or(&a, &b), scalar::or(&a, &b));
+            assert_eq!(avx512::xor(&a, &b), scalar::xor(&a, &b));
+            assert_eq!(avx512::add_f64(&c, &d), scalar::add_f64(&c, &d));
+            assert_eq!(avx512::add_f32(&e, &f), scalar::add_f32(&e, &f));
+        }
+    }
+
+    #[test]
+    fn test_zmm_traits() {
+        let (a, b) = operands_i32();
+        let c = a;
+        assert_eq!(a, c);
+        assert_ne!(a, b);
+        assert_eq!(Zmm::default(), Zmm { int8: [0; 64] });
+        assert!(format!("{:?}", a).contains("int32: [1, -2, 3, 2147483647"));
+    }
+
+    #[test]
+    fn test_zmm_fmt() {
+        let a = Zmm { int64: [1, 2, 3, 4, -5, -6, -7, -8] };
+        let s = a.fmt_i64();
+        assert_eq!(s.matches('|').count(), 1);
+        assert!(s.ends_with('\n'));
+        assert!(s.contains("-8"));
+    }
+}